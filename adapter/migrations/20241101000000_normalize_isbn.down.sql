@@ -0,0 +1,2 @@
+-- ISBN の正規化は元に戻せないため、インデックスのみ削除する
+DROP INDEX books_isbn_idx ON books;
@@ -0,0 +1,38 @@
+-- ISBN はハイフン・空白を取り除いた 13 桁の数字で保存する
+UPDATE books SET isbn = REPLACE(REPLACE(isbn, '-', ''), ' ', '');
+
+-- ISBN-10 で登録されている蔵書は ISBN-13 に変換する
+-- 先頭に 978 を付け、チェックディジットを計算し直す
+-- 変換するのは、先頭 9 桁が数字で ISBN-10 のチェックディジットが正しいものに限る
+-- （10, 9, ..., 1 の重みをかけた和が 11 で割り切れれば正しい。X は 10 を表す）
+-- それ以外の不正な値は変換せずにそのまま残し、読み出し時も保存されている値をそのまま返す
+UPDATE books
+SET isbn = CONCAT(
+  '978',
+  LEFT(isbn, 9),
+  MOD(
+    10 - MOD(
+      38
+      + 3 * SUBSTRING(isbn, 1, 1) + SUBSTRING(isbn, 2, 1)
+      + 3 * SUBSTRING(isbn, 3, 1) + SUBSTRING(isbn, 4, 1)
+      + 3 * SUBSTRING(isbn, 5, 1) + SUBSTRING(isbn, 6, 1)
+      + 3 * SUBSTRING(isbn, 7, 1) + SUBSTRING(isbn, 8, 1)
+      + 3 * SUBSTRING(isbn, 9, 1),
+      10
+    ),
+    10
+  )
+)
+WHERE UPPER(isbn) REGEXP '^[0-9]{9}[0-9X]$'
+AND MOD(
+  10 * SUBSTRING(isbn, 1, 1) + 9 * SUBSTRING(isbn, 2, 1)
+  + 8 * SUBSTRING(isbn, 3, 1) + 7 * SUBSTRING(isbn, 4, 1)
+  + 6 * SUBSTRING(isbn, 5, 1) + 5 * SUBSTRING(isbn, 6, 1)
+  + 4 * SUBSTRING(isbn, 7, 1) + 3 * SUBSTRING(isbn, 8, 1)
+  + 2 * SUBSTRING(isbn, 9, 1)
+  + IF(UPPER(RIGHT(isbn, 1)) = 'X', 10, RIGHT(isbn, 1)),
+  11
+) = 0;
+
+-- ISBN による絞り込みと重複の確認に使うインデックス
+CREATE INDEX books_isbn_idx ON books (isbn, user_id);
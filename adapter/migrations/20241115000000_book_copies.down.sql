@@ -0,0 +1,11 @@
+-- 蔵書ごとに 1 件しか貸し出せない状態に戻すため、貸出中のレコードが
+-- 同じ蔵書に複数ある場合はこの down migration は失敗する
+ALTER TABLE returned_checkouts DROP COLUMN copy_id;
+
+ALTER TABLE checkouts
+  ADD CONSTRAINT book_id UNIQUE (book_id),
+  DROP INDEX checkouts_book_id_idx,
+  DROP FOREIGN KEY checkouts_copy_id_fkey;
+ALTER TABLE checkouts DROP COLUMN copy_id;
+
+DROP TABLE IF EXISTS book_copies;
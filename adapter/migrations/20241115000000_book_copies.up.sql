@@ -0,0 +1,39 @@
+-- 蔵書（書誌情報）に属する、貸し出しの単位となる所蔵資料のテーブル
+CREATE TABLE IF NOT EXISTS book_copies (
+  copy_id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+  book_id CHAR(36) NOT NULL,
+  barcode VARCHAR(255) UNIQUE,
+  acquired_on DATE NOT NULL DEFAULT (CURRENT_DATE),
+  copy_condition VARCHAR(32) NOT NULL DEFAULT 'Good',
+  shelf_location VARCHAR(255),
+  created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  updated_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3) ON UPDATE CURRENT_TIMESTAMP(3),
+
+  FOREIGN KEY (book_id) REFERENCES books(book_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+-- 既存の蔵書には、所蔵資料を 1 冊ずつ作成する
+INSERT INTO book_copies (book_id, acquired_on)
+SELECT book_id, DATE(created_at) FROM books;
+
+-- 貸出は所蔵資料ごとに行うため、蔵書 ID の一意制約を外し、所蔵資料 ID を追加する
+ALTER TABLE checkouts ADD COLUMN copy_id CHAR(36) AFTER book_id;
+UPDATE checkouts AS c
+INNER JOIN book_copies AS bc ON bc.book_id = c.book_id
+SET c.copy_id = bc.copy_id;
+-- book_id の外部キー制約のために、一意制約の代わりに通常のインデックスを張る
+ALTER TABLE checkouts
+  MODIFY copy_id CHAR(36) NOT NULL,
+  ADD CONSTRAINT checkouts_copy_id_key UNIQUE (copy_id),
+  ADD CONSTRAINT checkouts_copy_id_fkey FOREIGN KEY (copy_id) REFERENCES book_copies(copy_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE,
+  ADD INDEX checkouts_book_id_idx (book_id),
+  DROP INDEX book_id;
+
+ALTER TABLE returned_checkouts ADD COLUMN copy_id CHAR(36) AFTER book_id;
+UPDATE returned_checkouts AS r
+INNER JOIN book_copies AS bc ON bc.book_id = r.book_id
+SET r.copy_id = bc.copy_id;
@@ -0,0 +1,5 @@
+ALTER TABLE returned_checkouts DROP COLUMN due_at;
+
+ALTER TABLE checkouts
+  DROP INDEX checkouts_due_at_idx,
+  DROP COLUMN due_at;
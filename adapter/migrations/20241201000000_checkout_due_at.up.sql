@@ -0,0 +1,11 @@
+-- 貸出に返却期限を追加する
+-- 既存の貸出は、貸出日から既定の貸出期間（14 日）後を返却期限とする
+ALTER TABLE checkouts ADD COLUMN due_at TIMESTAMP(3) NULL AFTER checked_out_at;
+UPDATE checkouts SET due_at = checked_out_at + INTERVAL 14 DAY;
+ALTER TABLE checkouts
+  MODIFY due_at TIMESTAMP(3) NOT NULL,
+  ADD INDEX checkouts_due_at_idx (due_at);
+
+ALTER TABLE returned_checkouts ADD COLUMN due_at TIMESTAMP(3) NULL AFTER checked_out_at;
+UPDATE returned_checkouts SET due_at = checked_out_at + INTERVAL 14 DAY;
+ALTER TABLE returned_checkouts MODIFY due_at TIMESTAMP(3) NOT NULL;
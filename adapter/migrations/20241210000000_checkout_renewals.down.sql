@@ -0,0 +1,2 @@
+ALTER TABLE returned_checkouts DROP COLUMN renewal_count;
+ALTER TABLE checkouts DROP COLUMN renewal_count;
@@ -0,0 +1,20 @@
+-- 貸出中の蔵書に対する予約（取り置きの申し込み）のテーブル
+-- 他のユーザーの予約がある蔵書は貸出を延長できない
+-- 返却された所蔵資料を予約の先頭のユーザーのために取り置く
+-- 取り置き中の予約は ready_until に受け取りの期限を持つ
+CREATE TABLE IF NOT EXISTS reservations (
+  reservation_id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+  book_id CHAR(36) NOT NULL,
+  user_id CHAR(36) NOT NULL,
+  reserved_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  ready_until TIMESTAMP(3) NULL,
+
+  UNIQUE (book_id, user_id),
+  INDEX reservations_queue_idx (book_id, reserved_at, reservation_id),
+  FOREIGN KEY (book_id) REFERENCES books(book_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE,
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
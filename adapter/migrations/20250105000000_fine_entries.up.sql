@@ -0,0 +1,21 @@
+-- 延滞料金の台帳
+-- 延滞料金の発生（Fine）、支払い（Payment）、免除（Waiver）を 1 行ずつ記録する
+CREATE TABLE IF NOT EXISTS fine_entries (
+  fine_entry_id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+  user_id CHAR(36) NOT NULL,
+  checkout_id CHAR(36),
+  entry_kind VARCHAR(32) NOT NULL,
+  amount BIGINT NOT NULL,
+  note TEXT,
+  recorded_by CHAR(36),
+  recorded_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+
+  INDEX fine_entries_user_id_idx (user_id, recorded_at),
+  CHECK (amount > 0),
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE,
+  FOREIGN KEY (recorded_by) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE SET NULL
+);
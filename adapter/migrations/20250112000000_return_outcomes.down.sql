@@ -0,0 +1,2 @@
+ALTER TABLE returned_checkouts DROP COLUMN return_note;
+ALTER TABLE returned_checkouts DROP COLUMN return_outcome;
@@ -0,0 +1,4 @@
+-- 返却時の所蔵資料の状態（Ok / Damaged / Lost）と、破損・紛失についてのメモ
+ALTER TABLE returned_checkouts
+  ADD COLUMN return_outcome VARCHAR(32) NOT NULL DEFAULT 'Ok' AFTER returned_at,
+  ADD COLUMN return_note TEXT AFTER return_outcome;
@@ -0,0 +1,3 @@
+ALTER TABLE returned_checkouts DROP COLUMN return_processed_by;
+ALTER TABLE returned_checkouts DROP COLUMN checkout_processed_by;
+ALTER TABLE checkouts DROP COLUMN checkout_processed_by;
@@ -0,0 +1,15 @@
+-- 貸出・返却の手続きをしたユーザー
+-- 管理者が借りるユーザーに代わって手続きした場合は、借りたユーザー（user_id）とは異なる
+-- 既存のレコードは借りたユーザー自身が手続きしたものとする
+ALTER TABLE checkouts ADD COLUMN checkout_processed_by CHAR(36) NULL AFTER user_id;
+UPDATE checkouts SET checkout_processed_by = user_id;
+ALTER TABLE checkouts MODIFY checkout_processed_by CHAR(36) NOT NULL;
+
+ALTER TABLE returned_checkouts
+  ADD COLUMN checkout_processed_by CHAR(36) NULL AFTER user_id,
+  ADD COLUMN return_processed_by CHAR(36) NULL AFTER returned_at;
+UPDATE returned_checkouts
+SET checkout_processed_by = user_id, return_processed_by = user_id;
+ALTER TABLE returned_checkouts
+  MODIFY checkout_processed_by CHAR(36) NOT NULL,
+  MODIFY return_processed_by CHAR(36) NOT NULL;
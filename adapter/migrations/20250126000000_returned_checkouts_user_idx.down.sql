@@ -0,0 +1 @@
+ALTER TABLE returned_checkouts DROP INDEX returned_checkouts_user_id_idx;
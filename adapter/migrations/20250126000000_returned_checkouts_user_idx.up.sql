@@ -0,0 +1,3 @@
+-- ユーザーごとの貸出履歴を貸出日で絞り込んで取得するためのインデックス
+ALTER TABLE returned_checkouts
+  ADD INDEX returned_checkouts_user_id_idx (user_id, checked_out_at);
@@ -0,0 +1,2 @@
+ALTER TABLE books DROP COLUMN cover_updated_at;
+ALTER TABLE books DROP COLUMN cover_content_type;
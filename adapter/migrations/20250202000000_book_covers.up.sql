@@ -0,0 +1,5 @@
+-- 蔵書の表紙画像の形式と更新日時
+-- 画像そのものはオブジェクトストレージに保存し、表紙画像がない蔵書は NULL のままにする
+ALTER TABLE books
+  ADD COLUMN cover_content_type VARCHAR(64) NULL,
+  ADD COLUMN cover_updated_at TIMESTAMP(3) NULL;
@@ -0,0 +1,24 @@
+-- 蔵書の分類に使うタグ
+CREATE TABLE IF NOT EXISTS tags (
+  tag_id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+  name VARCHAR(64) NOT NULL UNIQUE,
+  created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  updated_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3) ON UPDATE CURRENT_TIMESTAMP(3)
+);
+
+-- 蔵書とタグの多対多の関連
+-- タグでの絞り込みのために、tag_id からも引けるようにインデックスを張る
+CREATE TABLE IF NOT EXISTS book_tags (
+  book_id CHAR(36) NOT NULL,
+  tag_id CHAR(36) NOT NULL,
+  created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+
+  PRIMARY KEY (book_id, tag_id),
+  INDEX book_tags_tag_id_idx (tag_id),
+  FOREIGN KEY (book_id) REFERENCES books(book_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE,
+  FOREIGN KEY (tag_id) REFERENCES tags(tag_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
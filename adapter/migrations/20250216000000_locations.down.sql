@@ -0,0 +1,15 @@
+ALTER TABLE returned_checkouts
+  DROP FOREIGN KEY returned_checkouts_return_branch_id_fkey,
+  DROP FOREIGN KEY returned_checkouts_checkout_branch_id_fkey;
+ALTER TABLE returned_checkouts
+  DROP COLUMN return_branch_id,
+  DROP COLUMN checkout_branch_id;
+
+ALTER TABLE checkouts DROP FOREIGN KEY checkouts_checkout_branch_id_fkey;
+ALTER TABLE checkouts DROP COLUMN checkout_branch_id;
+
+ALTER TABLE books DROP FOREIGN KEY books_shelf_id_fkey;
+ALTER TABLE books DROP COLUMN shelf_id;
+
+DROP TABLE IF EXISTS shelves;
+DROP TABLE IF EXISTS branches;
@@ -0,0 +1,49 @@
+-- 蔵書を配架している館（閲覧室など）
+CREATE TABLE IF NOT EXISTS branches (
+  branch_id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+  name VARCHAR(255) NOT NULL UNIQUE,
+  created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  updated_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3) ON UPDATE CURRENT_TIMESTAMP(3)
+);
+
+-- 館の中にある書架
+CREATE TABLE IF NOT EXISTS shelves (
+  shelf_id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+  branch_id CHAR(36) NOT NULL,
+  name VARCHAR(255) NOT NULL,
+  created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  updated_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3) ON UPDATE CURRENT_TIMESTAMP(3),
+
+  CONSTRAINT shelves_branch_id_name_key UNIQUE (branch_id, name),
+  FOREIGN KEY (branch_id) REFERENCES branches(branch_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+-- 蔵書の配架場所。書架が削除された場合は未設定に戻す
+ALTER TABLE books
+  ADD COLUMN shelf_id CHAR(36) NULL,
+  ADD CONSTRAINT books_shelf_id_fkey FOREIGN KEY (shelf_id) REFERENCES shelves(shelf_id)
+    ON UPDATE CASCADE
+    ON DELETE SET NULL;
+
+-- 貸出・返却の手続きをした館
+-- 記録していない場合や、館が削除された場合は NULL になる
+ALTER TABLE checkouts
+  ADD COLUMN checkout_branch_id CHAR(36) NULL AFTER checkout_processed_by,
+  ADD CONSTRAINT checkouts_checkout_branch_id_fkey FOREIGN KEY (checkout_branch_id)
+    REFERENCES branches(branch_id)
+    ON UPDATE CASCADE
+    ON DELETE SET NULL;
+
+ALTER TABLE returned_checkouts
+  ADD COLUMN checkout_branch_id CHAR(36) NULL AFTER checkout_processed_by,
+  ADD COLUMN return_branch_id CHAR(36) NULL AFTER return_processed_by,
+  ADD CONSTRAINT returned_checkouts_checkout_branch_id_fkey FOREIGN KEY (checkout_branch_id)
+    REFERENCES branches(branch_id)
+    ON UPDATE CASCADE
+    ON DELETE SET NULL,
+  ADD CONSTRAINT returned_checkouts_return_branch_id_fkey FOREIGN KEY (return_branch_id)
+    REFERENCES branches(branch_id)
+    ON UPDATE CASCADE
+    ON DELETE SET NULL;
@@ -0,0 +1,5 @@
+ALTER TABLE returned_checkouts
+  DROP COLUMN book_isbn,
+  DROP COLUMN book_author,
+  DROP COLUMN book_title;
+ALTER TABLE books DROP COLUMN deleted_at;
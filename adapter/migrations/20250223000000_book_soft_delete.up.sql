@@ -0,0 +1,10 @@
+-- 蔵書を削除済みにした日時
+-- 削除済みの蔵書も貸出や貸出履歴を残すため行は削除せず、削除済みでない蔵書は NULL のままにする
+ALTER TABLE books ADD COLUMN deleted_at TIMESTAMP(3) NULL;
+
+-- 蔵書を完全に削除しても返却済みの貸出履歴は残し、削除した時点の蔵書の情報を記録しておく
+-- 蔵書が残っている貸出履歴は NULL のままにする
+ALTER TABLE returned_checkouts
+  ADD COLUMN book_title VARCHAR(255) NULL,
+  ADD COLUMN book_author VARCHAR(255) NULL,
+  ADD COLUMN book_isbn VARCHAR(255) NULL;
@@ -0,0 +1,4 @@
+-- 楽観的排他制御に使うバージョン
+-- 蔵書・ユーザーの内容を変更するたびに 1 ずつ増やし、ETag として公開する
+ALTER TABLE books ADD COLUMN version BIGINT NOT NULL DEFAULT 1;
+ALTER TABLE users ADD COLUMN version BIGINT NOT NULL DEFAULT 1;
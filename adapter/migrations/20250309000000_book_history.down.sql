@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS book_history_changes;
+DROP TABLE IF EXISTS book_history;
@@ -0,0 +1,29 @@
+-- 蔵書の変更履歴
+-- 蔵書の登録・更新・削除・復元・完全な削除を 1 行ずつ記録する
+-- 蔵書を完全に削除した後も履歴を残すため、book_id には蔵書を参照する外部キーを付けない
+CREATE TABLE IF NOT EXISTS book_history (
+  book_history_id CHAR(36) PRIMARY KEY DEFAULT (UUID()),
+  book_id CHAR(36) NOT NULL,
+  action VARCHAR(32) NOT NULL,
+  changed_by CHAR(36),
+  changed_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+
+  INDEX book_history_book_id_idx (book_id, changed_at),
+  FOREIGN KEY (changed_by) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE SET NULL
+);
+
+-- 変更履歴ごとの、値が変わった項目の変更前後の値
+-- 登録時は変更前の値が、完全に削除した時は変更後の値が NULL になる
+CREATE TABLE IF NOT EXISTS book_history_changes (
+  book_history_id CHAR(36) NOT NULL,
+  field_name VARCHAR(32) NOT NULL,
+  before_value TEXT,
+  after_value TEXT,
+
+  PRIMARY KEY (book_history_id, field_name),
+  FOREIGN KEY (book_history_id) REFERENCES book_history(book_history_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
@@ -0,0 +1,185 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use kernel::model::list::SortOrder;
+use shared::error::{AppError, AppResult};
+use sqlx::{Encode, MySql, QueryBuilder, Type};
+
+// カーソルの値同士の区切り文字
+// 蔵書のタイトルなどの値にまず含まれない制御文字（Unit Separator）を使う
+const SEPARATOR: char = '\u{1f}';
+
+// カーソルが指す位置から、どちら向きにページを取得するかを表す型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorDirection {
+    Next,
+    Prev,
+}
+
+// キーセット方式のページネーションで使うカーソル
+// values には並び替えのキーの値など、ページの境界となるレコードを特定する値を格納する。
+// クライアントには base64 でエンコードした不透明な文字列として渡す
+#[derive(Debug, PartialEq, Eq)]
+pub struct Cursor {
+    pub direction: CursorDirection,
+    pub values: Vec<String>,
+}
+
+impl Cursor {
+    pub fn new(direction: CursorDirection, values: Vec<String>) -> Self {
+        Self { direction, values }
+    }
+
+    pub fn encode(&self) -> String {
+        let direction = match self.direction {
+            CursorDirection::Next => "n",
+            CursorDirection::Prev => "p",
+        };
+        let raw = std::iter::once(direction.to_string())
+            .chain(self.values.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(&SEPARATOR.to_string());
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(cursor: &str) -> AppResult<Self> {
+        let invalid = || AppError::InvalidCursor(cursor.to_string());
+        let raw = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+        let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+        let mut values = raw.split(SEPARATOR).map(String::from);
+        let direction = match values.next().as_deref() {
+            Some("n") => CursorDirection::Next,
+            Some("p") => CursorDirection::Prev,
+            _ => return Err(invalid()),
+        };
+        Ok(Self {
+            direction,
+            values: values.collect(),
+        })
+    }
+}
+
+// 実際に SQL で使う並び順を返す
+// 前のページを取得する場合は逆順で取得し、取得後に並べ直す
+fn effective_order(order: SortOrder, direction: CursorDirection) -> SortOrder {
+    match (order, direction) {
+        (order, CursorDirection::Next) => order,
+        (SortOrder::Asc, CursorDirection::Prev) => SortOrder::Desc,
+        (SortOrder::Desc, CursorDirection::Prev) => SortOrder::Asc,
+    }
+}
+
+// カーソルが指すレコードより後（前のページの場合は前）のレコードに絞り込む条件を追加する
+// (並び替えのキー, ID) の組で比較することで、キーの値が同じレコードがあっても
+// 取りこぼしや重複が起きないようにしている
+pub fn push_keyset_condition<'a, K, I>(
+    query: &mut QueryBuilder<'a, MySql>,
+    (key_column, id_column): (&str, &str),
+    order: SortOrder,
+    direction: CursorDirection,
+    key: K,
+    id: I,
+) where
+    K: 'a + Encode<'a, MySql> + Type<MySql> + Send,
+    I: 'a + Encode<'a, MySql> + Type<MySql> + Send,
+{
+    let op = match effective_order(order, direction) {
+        SortOrder::Asc => ">",
+        SortOrder::Desc => "<",
+    };
+    query
+        .push(format!(" AND ({key_column}, {id_column}) {op} ("))
+        .push_bind(key)
+        .push(", ")
+        .push_bind(id)
+        .push(")");
+}
+
+// キーセット方式のページネーション用の ORDER BY 句を追加する
+pub fn push_keyset_order_by(
+    query: &mut QueryBuilder<'_, MySql>,
+    (key_column, id_column): (&str, &str),
+    order: SortOrder,
+    direction: CursorDirection,
+) {
+    let order = match effective_order(order, direction) {
+        SortOrder::Asc => "ASC",
+        SortOrder::Desc => "DESC",
+    };
+    query.push(format!(
+        " ORDER BY {key_column} {order}, {id_column} {order}"
+    ));
+}
+
+// limit + 1 件取得したレコードから、1 ページ分のレコードと前後のカーソルを求める
+// to_values は、レコードからカーソルに格納する値を取り出す関数
+pub fn paginate_by_cursor<T>(
+    mut rows: Vec<T>,
+    limit: i64,
+    cursor: Option<&Cursor>,
+    to_values: impl Fn(&T) -> Vec<String>,
+) -> (Vec<T>, Option<String>, Option<String>) {
+    let has_more = rows.len() as i64 > limit;
+    rows.truncate(limit.max(0) as usize);
+
+    let direction = cursor.map(|c| c.direction).unwrap_or(CursorDirection::Next);
+    if direction == CursorDirection::Prev {
+        rows.reverse();
+    }
+
+    let cursor_of =
+        |row: Option<&T>, direction| row.map(|row| Cursor::new(direction, to_values(row)).encode());
+    let next = cursor_of(rows.last(), CursorDirection::Next);
+    let prev = cursor_of(rows.first(), CursorDirection::Prev);
+    let (next_cursor, prev_cursor) = match (direction, cursor) {
+        // 先頭のページ
+        (_, None) => (next.filter(|_| has_more), None),
+        (CursorDirection::Next, Some(_)) => (next.filter(|_| has_more), prev),
+        (CursorDirection::Prev, Some(_)) => (next, prev.filter(|_| has_more)),
+    };
+    (rows, next_cursor, prev_cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_roundtrip() -> anyhow::Result<()> {
+        let cursor = Cursor::new(
+            CursorDirection::Prev,
+            vec!["title".into(), "Rust|入門".into(), "".into()],
+        );
+        let decoded = Cursor::decode(&cursor.encode())?;
+        assert_eq!(decoded, cursor);
+
+        assert!(Cursor::decode("not a cursor").is_err());
+        assert!(Cursor::decode(&URL_SAFE_NO_PAD.encode("x")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_paginate_by_cursor() -> anyhow::Result<()> {
+        let to_values = |v: &i32| vec![v.to_string()];
+
+        // 先頭のページ: 次のページがあれば next_cursor のみ返す
+        let (items, next, prev) = paginate_by_cursor(vec![1, 2, 3], 2, None, to_values);
+        assert_eq!(items, vec![1, 2]);
+        assert_eq!(Cursor::decode(&next.unwrap())?.values, vec!["2"]);
+        assert!(prev.is_none());
+
+        // 最後のページ: next_cursor は返さない
+        let cursor = Cursor::new(CursorDirection::Next, vec!["2".into()]);
+        let (items, next, prev) = paginate_by_cursor(vec![3], 2, Some(&cursor), to_values);
+        assert_eq!(items, vec![3]);
+        assert!(next.is_none());
+        assert_eq!(Cursor::decode(&prev.unwrap())?.values, vec!["3"]);
+
+        // 前のページ: 逆順で取得したレコードを並べ直す
+        let cursor = Cursor::new(CursorDirection::Prev, vec!["3".into()]);
+        let (items, next, prev) = paginate_by_cursor(vec![2, 1], 2, Some(&cursor), to_values);
+        assert_eq!(items, vec![1, 2]);
+        assert_eq!(Cursor::decode(&next.unwrap())?.values, vec!["2"]);
+        assert!(prev.is_none());
+
+        Ok(())
+    }
+}
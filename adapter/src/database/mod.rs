@@ -5,7 +5,9 @@ use shared::{
 // ★★★ 修正点 1: PgConnectOptions, PgPool を MySqlConnectOptions, MySqlPool に変更 ★★★
 use sqlx::{mysql::MySqlConnectOptions, MySqlPool};
 
+pub mod cursor;
 pub mod model;
+pub mod version;
 
 // ★★★ 修正点 2: make_pg_connect_options を make_mysql_connect_options に変更 ★★★
 fn make_mysql_connect_options(cfg: &DatabaseConfig) -> MySqlConnectOptions {
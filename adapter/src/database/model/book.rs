@@ -1,10 +1,22 @@
 use chrono::{DateTime, Utc};
 use kernel::model::{
-    book::{Book, Checkout},
-    id::{BookId, CheckoutId, UserId},
+    book::{
+        cover::BookCover,
+        history::{
+            BookField, BookFieldChange, BookFieldValues, BookHistoryAction, BookHistoryEntry,
+        },
+        isbn::Isbn,
+        Book, BookCopyCounts, Checkout,
+    },
+    id::{BookHistoryId, BookId, BranchId, CheckoutId, CopyId, ShelfId, TagId, UserId},
+    location::{BookLocation, Branch, Shelf},
+    tag::Tag,
     user::{BookOwner, CheckoutUser},
 };
+use shared::error::{AppError, AppResult};
+use std::str::FromStr;
 
+#[derive(sqlx::FromRow)]
 pub struct BookRow {
     pub book_id: BookId,
     pub title: String,
@@ -13,11 +25,25 @@ pub struct BookRow {
     pub description: String,
     pub owned_by: UserId,
     pub owner_name: String,
+    pub cover_content_type: Option<String>,
+    pub cover_updated_at: Option<DateTime<Utc>>,
+    // 配架場所が設定されていなければ、いずれも None になる
+    pub shelf_id: Option<ShelfId>,
+    pub shelf_name: Option<String>,
+    pub branch_id: Option<BranchId>,
+    pub branch_name: Option<String>,
+    pub version: i64,
 }
 
 // From トレイトの実装の代わりに、引数をとる into_book メソッドを定義し実装する
+// ISBN の正規化以前に登録された不正な ISBN も、一覧や詳細の取得を妨げないようそのまま返す
 impl BookRow {
-    pub fn into_book(self, checkout: Option<Checkout>) -> Book {
+    pub fn into_book(
+        self,
+        copies: BookCopyCounts,
+        checkouts: Vec<Checkout>,
+        tags: Vec<Tag>,
+    ) -> Book {
         let BookRow {
             book_id,
             title,
@@ -26,7 +52,15 @@ impl BookRow {
             description,
             owned_by,
             owner_name,
+            cover_content_type,
+            cover_updated_at,
+            shelf_id,
+            shelf_name,
+            branch_id,
+            branch_name,
+            version,
         } = self;
+        let isbn = Isbn::from_stored(isbn);
         Book {
             id: book_id,
             title,
@@ -37,24 +71,85 @@ impl BookRow {
                 id: owned_by,
                 name: owner_name,
             },
-            checkout,
+            copies,
+            checkouts,
+            // 表紙画像は形式と更新日時の両方が記録されている場合のみ登録済みとみなす
+            cover: cover_content_type
+                .zip(cover_updated_at)
+                .map(|(content_type, updated_at)| BookCover {
+                    content_type,
+                    updated_at,
+                }),
+            tags,
+            location: match (shelf_id, shelf_name, branch_id, branch_name) {
+                (Some(shelf_id), Some(shelf_name), Some(branch_id), Some(branch_name)) => {
+                    Some(BookLocation {
+                        branch: Branch {
+                            id: branch_id,
+                            name: branch_name,
+                        },
+                        shelf: Shelf {
+                            id: shelf_id,
+                            branch_id,
+                            name: shelf_name,
+                        },
+                    })
+                }
+                _ => None,
+            },
+            version,
         }
     }
 }
 
 // ページネーション用の adapter 内部の型
+#[derive(sqlx::FromRow)]
 pub struct PaginatedBookRow {
     pub total: i64,
     pub id: BookId,
 }
 
+// カーソル方式のページネーション用の adapter 内部の型
+// カーソルを作るために、並び替えのキーになりうる列の値もあわせて取得する
+#[derive(sqlx::FromRow)]
+pub struct BookCursorRow {
+    pub book_id: BookId,
+    pub title: String,
+    pub author: String,
+    pub isbn: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 蔵書ごとの所蔵資料の数を集計する際に使う型
+#[derive(sqlx::FromRow)]
+pub struct BookCopyCountRow {
+    pub book_id: BookId,
+    pub total: i64,
+    pub available: i64,
+}
+
+impl From<BookCopyCountRow> for BookCopyCounts {
+    fn from(value: BookCopyCountRow) -> Self {
+        let BookCopyCountRow {
+            book_id: _,
+            total,
+            available,
+        } = value;
+        BookCopyCounts { total, available }
+    }
+}
+
 // 貸し出し情報を格納する型を新規追加
+#[derive(sqlx::FromRow)]
 pub struct BookCheckoutRow {
     pub checkout_id: CheckoutId,
     pub book_id: BookId,
+    pub copy_id: CopyId,
     pub user_id: UserId,
     pub user_name: String,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
 }
 
 // Checkout 型に変換する From トレイト実装を追加
@@ -63,17 +158,152 @@ impl From<BookCheckoutRow> for Checkout {
         let BookCheckoutRow {
             checkout_id,
             book_id: _,
+            copy_id,
             user_id,
             user_name,
             checked_out_at,
+            due_at,
         } = value;
         Checkout {
             checkout_id,
+            copy_id,
             checked_out_by: CheckoutUser {
                 id: user_id,
                 name: user_name,
             },
             checked_out_at,
+            due_at,
         }
     }
 }
+
+// 蔵書に付けられたタグを、蔵書の ID とあわせて取得する際に使う型
+#[derive(sqlx::FromRow)]
+pub struct BookTagRow {
+    pub book_id: BookId,
+    pub tag_id: TagId,
+    pub name: String,
+}
+
+impl From<BookTagRow> for Tag {
+    fn from(value: BookTagRow) -> Self {
+        let BookTagRow {
+            book_id: _,
+            tag_id,
+            name,
+        } = value;
+        Tag { id: tag_id, name }
+    }
+}
+
+// 完全に削除する前にロックした蔵書の状態
+// 変更履歴に記録するため、削除する時点の値もあわせて取得する
+pub struct PurgeStateRow {
+    pub deleted: bool,
+    pub checked_out: bool,
+    pub title: String,
+    pub author: String,
+    pub isbn: String,
+    pub description: String,
+}
+
+impl PurgeStateRow {
+    pub fn into_fields(self) -> BookFieldValues {
+        let PurgeStateRow {
+            title,
+            author,
+            isbn,
+            description,
+            ..
+        } = self;
+        BookFieldValues {
+            title,
+            author,
+            isbn: Isbn::from_stored(isbn),
+            description,
+        }
+    }
+}
+
+// 更新・削除の前にロックした蔵書の行
+// 変更履歴に記録するため、バージョンとあわせて変更前の値を取得する
+pub struct LockedBookRow {
+    pub version: i64,
+    pub title: String,
+    pub author: String,
+    pub isbn: String,
+    pub description: String,
+}
+
+impl LockedBookRow {
+    pub fn into_fields(self) -> (i64, BookFieldValues) {
+        let LockedBookRow {
+            version,
+            title,
+            author,
+            isbn,
+            description,
+        } = self;
+        let isbn = Isbn::from_stored(isbn);
+        (
+            version,
+            BookFieldValues {
+                title,
+                author,
+                isbn,
+                description,
+            },
+        )
+    }
+}
+
+pub struct BookHistoryRow {
+    pub book_history_id: BookHistoryId,
+    pub action: String,
+    pub changed_by: Option<UserId>,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl BookHistoryRow {
+    pub fn into_entry(self, changes: Vec<BookFieldChange>) -> AppResult<BookHistoryEntry> {
+        let BookHistoryRow {
+            book_history_id,
+            action,
+            changed_by,
+            changed_at,
+        } = self;
+        Ok(BookHistoryEntry {
+            id: book_history_id,
+            action: BookHistoryAction::from_str(action.as_str())
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            changed_by,
+            changed_at,
+            changes,
+        })
+    }
+}
+
+pub struct BookHistoryChangeRow {
+    pub book_history_id: BookHistoryId,
+    pub field_name: String,
+    pub before_value: Option<String>,
+    pub after_value: Option<String>,
+}
+
+impl TryFrom<BookHistoryChangeRow> for BookFieldChange {
+    type Error = AppError;
+    fn try_from(value: BookHistoryChangeRow) -> Result<Self, Self::Error> {
+        let BookHistoryChangeRow {
+            book_history_id: _,
+            field_name,
+            before_value,
+            after_value,
+        } = value;
+        Ok(BookFieldChange {
+            field: BookField::from_str(field_name.as_str())
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            before: before_value,
+            after: after_value,
+        })
+    }
+}
@@ -1,25 +1,55 @@
 use kernel::model::{
-    checkout::{Checkout, CheckoutBook},
-    id::{BookId, CheckoutId, UserId},
+    checkout::{Checkout, CheckoutBook, ReturnOutcome},
+    id::{BookId, BranchId, CheckoutId, CopyId, UserId},
 };
+use shared::error::AppError;
 use sqlx::types::chrono::{DateTime, Utc};
+use std::str::FromStr;
 
 // 貸し出し状態を確認するための型
 // 蔵書が存在する場合はこの型にはまるレコードが存在し、
-// その蔵書が貸出中の場合は checkout_id および user_id が None ではない値になる
-// 蔵書が貸出中でない場合は checkout_id も user_id も None
+// その所蔵資料が貸出中の場合は checkout_id および user_id が None ではない値になる
+// 所蔵資料が貸出中でない場合は checkout_id も user_id も None
 pub struct CheckoutStateRow {
     pub book_id: BookId,
+    pub copy_id: Option<CopyId>,
     pub checkout_id: Option<CheckoutId>,
     pub user_id: Option<UserId>,
 }
 
+// 貸出の延長が可能かを確認するための型
+// 蔵書が存在する場合はこの型にはまるレコードが存在し、
+// 指定の貸出が存在する場合は checkout_id 以降の値が None ではない値になる
+pub struct RenewalStateRow {
+    pub book_id: BookId,
+    pub checkout_id: Option<CheckoutId>,
+    pub user_id: Option<UserId>,
+    pub renewal_count: Option<i32>,
+    // 延長を申し込んだユーザー以外による予約があるか
+    pub reserved_by_others: bool,
+}
+
+// 貸出ポリシーの判定のために、借りるユーザーの状態を確認するための型
+pub struct LoanerStateRow {
+    pub role_name: String,
+    // 貸出中の冊数
+    pub loans: i64,
+    // 貸出中のうち、返却期限を過ぎている冊数
+    pub overdue_loans: i64,
+}
+
 // 貸出中の一覧を取得する際に使う型
+#[derive(sqlx::FromRow)]
 pub struct CheckoutRow {
     pub checkout_id: CheckoutId,
     pub book_id: BookId,
+    pub copy_id: CopyId,
     pub user_id: UserId,
+    pub checkout_processed_by: UserId,
+    pub checkout_branch_id: Option<BranchId>,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+    pub renewal_count: i32,
     pub title: String,
     pub author: String,
     pub isbn: String,
@@ -30,18 +60,32 @@ impl From<CheckoutRow> for Checkout {
         let CheckoutRow {
             checkout_id,
             book_id,
+            copy_id,
             user_id,
+            checkout_processed_by,
+            checkout_branch_id,
             checked_out_at,
+            due_at,
+            renewal_count,
             title,
             author,
             isbn,
         } = value;
         Checkout {
             id: checkout_id,
+            copy_id,
             checked_out_by: user_id,
             checked_out_at,
-            // 未返却なので、returned_at は None を入れる
+            checkout_processed_by,
+            checkout_branch_id,
+            due_at,
+            renewal_count,
+            // 未返却なので、returned_at などの返却時の情報は None を入れる
             returned_at: None,
+            return_outcome: None,
+            return_note: None,
+            return_processed_by: None,
+            return_branch_id: None,
             book: CheckoutBook {
                 book_id,
                 title,
@@ -52,42 +96,75 @@ impl From<CheckoutRow> for Checkout {
     }
 }
 
-// 返却済みの貸し出し一覧を取得する際に使う型
-pub struct ReturnedCheckoutRow {
+// 貸出中・返却済みの両方を含む貸し出し履歴を取得する際に使う型
+// 貸出中のレコードは returned_at 以降の返却時の情報が None になる
+#[derive(sqlx::FromRow)]
+pub struct CheckoutHistoryRow {
     pub checkout_id: CheckoutId,
     pub book_id: BookId,
+    pub copy_id: CopyId,
     pub user_id: UserId,
+    pub checkout_processed_by: UserId,
+    pub checkout_branch_id: Option<BranchId>,
     pub checked_out_at: DateTime<Utc>,
-    pub returned_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+    pub renewal_count: i32,
+    pub returned_at: Option<DateTime<Utc>>,
+    pub return_outcome: Option<String>,
+    pub return_note: Option<String>,
+    pub return_processed_by: Option<UserId>,
+    pub return_branch_id: Option<BranchId>,
     pub title: String,
     pub author: String,
     pub isbn: String,
 }
 
-impl From<ReturnedCheckoutRow> for Checkout {
-    fn from(value: ReturnedCheckoutRow) -> Self {
-        let ReturnedCheckoutRow {
+impl TryFrom<CheckoutHistoryRow> for Checkout {
+    type Error = AppError;
+    fn try_from(value: CheckoutHistoryRow) -> Result<Self, Self::Error> {
+        let CheckoutHistoryRow {
             checkout_id,
             book_id,
+            copy_id,
             user_id,
+            checkout_processed_by,
+            checkout_branch_id,
             checked_out_at,
+            due_at,
+            renewal_count,
             returned_at,
+            return_outcome,
+            return_note,
+            return_processed_by,
+            return_branch_id,
             title,
             author,
             isbn,
         } = value;
-        Checkout {
+        let return_outcome = return_outcome
+            .map(|outcome| ReturnOutcome::from_str(outcome.as_str()))
+            .transpose()
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+        Ok(Checkout {
             id: checkout_id,
+            copy_id,
             checked_out_by: user_id,
             checked_out_at,
-            // 返却済みなので returned_at には日時データが入る
-            returned_at: Some(returned_at),
+            checkout_processed_by,
+            checkout_branch_id,
+            due_at,
+            renewal_count,
+            returned_at,
+            return_outcome,
+            return_note,
+            return_processed_by,
+            return_branch_id,
             book: CheckoutBook {
                 book_id,
                 title,
                 author,
                 isbn,
             },
-        }
+        })
     }
 }
@@ -0,0 +1,74 @@
+use chrono::NaiveDate;
+use kernel::model::{
+    book::Checkout,
+    copy::{BookCopy, CopyCondition},
+    id::{BookId, CheckoutId, CopyId, UserId},
+    user::CheckoutUser,
+};
+use shared::error::AppError;
+use sqlx::types::chrono::{DateTime, Utc};
+use std::str::FromStr;
+
+// 所蔵資料を、貸出中であれば貸出情報とあわせて取得する際に使う型
+// 貸出中でない場合は checkout_id 以降の値が None になる
+pub struct BookCopyRow {
+    pub copy_id: CopyId,
+    pub book_id: BookId,
+    pub barcode: Option<String>,
+    pub acquired_on: NaiveDate,
+    pub copy_condition: String,
+    pub shelf_location: Option<String>,
+    pub checkout_id: Option<CheckoutId>,
+    pub user_id: Option<UserId>,
+    pub user_name: Option<String>,
+    pub checked_out_at: Option<DateTime<Utc>>,
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<BookCopyRow> for BookCopy {
+    type Error = AppError;
+    fn try_from(value: BookCopyRow) -> Result<Self, Self::Error> {
+        let BookCopyRow {
+            copy_id,
+            book_id,
+            barcode,
+            acquired_on,
+            copy_condition,
+            shelf_location,
+            checkout_id,
+            user_id,
+            user_name,
+            checked_out_at,
+            due_at,
+        } = value;
+        let checkout = match (checkout_id, user_id, user_name, checked_out_at, due_at) {
+            (
+                Some(checkout_id),
+                Some(user_id),
+                Some(user_name),
+                Some(checked_out_at),
+                Some(due_at),
+            ) => Some(Checkout {
+                checkout_id,
+                copy_id,
+                checked_out_by: CheckoutUser {
+                    id: user_id,
+                    name: user_name,
+                },
+                checked_out_at,
+                due_at,
+            }),
+            _ => None,
+        };
+        Ok(BookCopy {
+            id: copy_id,
+            book_id,
+            barcode,
+            acquired_on,
+            condition: CopyCondition::from_str(copy_condition.as_str())
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            shelf_location,
+            checkout,
+        })
+    }
+}
@@ -0,0 +1,45 @@
+use kernel::model::{
+    fine::{FineEntry, FineEntryKind},
+    id::{CheckoutId, FineEntryId, UserId},
+};
+use shared::error::AppError;
+use sqlx::types::chrono::{DateTime, Utc};
+use std::str::FromStr;
+
+pub struct FineEntryRow {
+    pub fine_entry_id: FineEntryId,
+    pub user_id: UserId,
+    pub checkout_id: Option<CheckoutId>,
+    pub entry_kind: String,
+    pub amount: i64,
+    pub note: Option<String>,
+    pub recorded_by: Option<UserId>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl TryFrom<FineEntryRow> for FineEntry {
+    type Error = AppError;
+    fn try_from(value: FineEntryRow) -> Result<Self, Self::Error> {
+        let FineEntryRow {
+            fine_entry_id,
+            user_id,
+            checkout_id,
+            entry_kind,
+            amount,
+            note,
+            recorded_by,
+            recorded_at,
+        } = value;
+        Ok(FineEntry {
+            id: fine_entry_id,
+            user_id,
+            checkout_id,
+            kind: FineEntryKind::from_str(entry_kind.as_str())
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            amount,
+            note,
+            recorded_by,
+            recorded_at,
+        })
+    }
+}
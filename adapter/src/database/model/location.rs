@@ -0,0 +1,40 @@
+use kernel::model::{
+    id::{BranchId, ShelfId},
+    location::{Branch, Shelf},
+};
+
+pub struct BranchRow {
+    pub branch_id: BranchId,
+    pub name: String,
+}
+
+impl From<BranchRow> for Branch {
+    fn from(value: BranchRow) -> Self {
+        let BranchRow { branch_id, name } = value;
+        Branch {
+            id: branch_id,
+            name,
+        }
+    }
+}
+
+pub struct ShelfRow {
+    pub shelf_id: ShelfId,
+    pub branch_id: BranchId,
+    pub name: String,
+}
+
+impl From<ShelfRow> for Shelf {
+    fn from(value: ShelfRow) -> Self {
+        let ShelfRow {
+            shelf_id,
+            branch_id,
+            name,
+        } = value;
+        Shelf {
+            id: shelf_id,
+            branch_id,
+            name,
+        }
+    }
+}
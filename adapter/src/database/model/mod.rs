@@ -1,4 +1,10 @@
 pub mod auth;
 pub mod book;
 pub mod checkout;
+pub mod copy;
+pub mod fine;
+pub mod location;
+pub mod report;
+pub mod reservation;
+pub mod tag;
 pub mod user;
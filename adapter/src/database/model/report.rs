@@ -0,0 +1,55 @@
+use kernel::model::{id::BookId, report::BookLoanCount};
+use sqlx::types::chrono::{DateTime, NaiveDate, Utc};
+
+// 期間ごとの貸出件数の集計結果
+#[derive(sqlx::FromRow)]
+pub struct LoanStatsRow {
+    pub period_start: NaiveDate,
+    pub loans: i64,
+    pub active_borrowers: i64,
+}
+
+// 期間ごとの返却件数の集計結果
+#[derive(sqlx::FromRow)]
+pub struct ReturnStatsRow {
+    pub period_start: NaiveDate,
+    pub returns: i64,
+    pub overdue_returns: i64,
+    pub average_loan_days: Option<f64>,
+}
+
+// 集計する範囲のいずれかの時点で、返却期限を過ぎても返却されていなかった貸出
+// 貸出中のものは returned_at が None になる
+#[derive(sqlx::FromRow)]
+pub struct OverdueLoanRow {
+    pub due_at: DateTime<Utc>,
+    pub returned_at: Option<DateTime<Utc>>,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct BookLoanCountRow {
+    pub period_start: NaiveDate,
+    pub book_id: BookId,
+    pub title: String,
+    pub author: String,
+    pub loans: i64,
+}
+
+impl From<BookLoanCountRow> for BookLoanCount {
+    fn from(value: BookLoanCountRow) -> Self {
+        let BookLoanCountRow {
+            period_start,
+            book_id,
+            title,
+            author,
+            loans,
+        } = value;
+        BookLoanCount {
+            period_start,
+            book_id,
+            title,
+            author,
+            loans,
+        }
+    }
+}
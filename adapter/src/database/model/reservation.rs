@@ -0,0 +1,42 @@
+use kernel::model::{
+    id::{BookId, ReservationId, UserId},
+    reservation::Reservation,
+};
+use sqlx::types::chrono::{DateTime, Utc};
+
+pub struct ReservationRow {
+    pub reservation_id: ReservationId,
+    pub book_id: BookId,
+    pub user_id: UserId,
+    pub reserved_at: DateTime<Utc>,
+    pub ready_until: Option<DateTime<Utc>>,
+}
+
+impl ReservationRow {
+    // 予約の順番はレコードの並び順から決まるので、取得後に引数で受け取る
+    pub fn into_reservation(self, position: i64) -> Reservation {
+        let ReservationRow {
+            reservation_id,
+            book_id,
+            user_id,
+            reserved_at,
+            ready_until,
+        } = self;
+        Reservation {
+            id: reservation_id,
+            book_id,
+            reserved_by: user_id,
+            reserved_at,
+            position,
+            ready_until,
+        }
+    }
+}
+
+// 取り置きの状態を確認するための型
+pub struct HoldStateRow {
+    // 貸出中でない所蔵資料の数
+    pub available_copies: i64,
+    // 取り置き中の予約の数
+    pub ready_reservations: i64,
+}
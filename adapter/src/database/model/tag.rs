@@ -0,0 +1,13 @@
+use kernel::model::{id::TagId, tag::Tag};
+
+pub struct TagRow {
+    pub tag_id: TagId,
+    pub name: String,
+}
+
+impl From<TagRow> for Tag {
+    fn from(value: TagRow) -> Self {
+        let TagRow { tag_id, name } = value;
+        Tag { id: tag_id, name }
+    }
+}
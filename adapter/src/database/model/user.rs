@@ -8,6 +8,7 @@ pub struct UserRow {
     pub name: String,
     pub email: String,
     pub role_name: String,
+    pub version: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -20,6 +21,7 @@ impl TryFrom<UserRow> for User {
             name,
             email,
             role_name,
+            version,
             ..
         } = value;
         Ok(User {
@@ -28,6 +30,7 @@ impl TryFrom<UserRow> for User {
             email,
             role: Role::from_str(role_name.as_str())
                 .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            version,
         })
     }
 }
@@ -0,0 +1,27 @@
+use shared::error::{AppError, AppResult};
+
+// 楽観的排他制御のため、ロックした行のバージョンが呼び出し元の想定と一致するかを確認する
+// expected が None の場合は確認しない
+pub fn ensure_version(current: i64, expected: Option<i64>) -> AppResult<()> {
+    match expected {
+        Some(expected) if expected != current => Err(AppError::PreconditionFailed(format!(
+            "他の操作で更新されています（現在のバージョン: {current}、指定されたバージョン: {expected}）"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_version() {
+        assert!(ensure_version(3, None).is_ok());
+        assert!(ensure_version(3, Some(3)).is_ok());
+        assert!(matches!(
+            ensure_version(3, Some(2)),
+            Err(AppError::PreconditionFailed(_))
+        ));
+    }
+}
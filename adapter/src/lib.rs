@@ -1,3 +1,5 @@
 pub mod database;
+pub mod metadata;
 pub mod redis;
 pub mod repository;
+pub mod storage;
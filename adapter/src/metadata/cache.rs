@@ -0,0 +1,83 @@
+use crate::redis::{
+    model::{RedisKey, RedisValue},
+    RedisClient,
+};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::book::{isbn::Isbn, metadata::BookMetadata},
+    repository::book_metadata::BookMetadataProvider,
+};
+use shared::error::{AppError, AppResult};
+use std::sync::Arc;
+
+// 書誌情報の検索結果を Redis にキャッシュする
+// 書誌情報が見つからなかったことも ttl の間はキャッシュし、同じ ISBN で外部のサービスを何度も呼び出さないようにする
+#[derive(new)]
+pub struct CachedBookMetadataProvider {
+    inner: Arc<dyn BookMetadataProvider>,
+    kv: Arc<RedisClient>,
+    ttl: u64,
+}
+
+#[async_trait]
+impl BookMetadataProvider for CachedBookMetadataProvider {
+    async fn find_by_isbn(&self, isbn: &Isbn) -> AppResult<Option<BookMetadata>> {
+        let key = BookMetadataKey::from(isbn);
+        if let Some(cached) = self.kv.get(&key).await? {
+            return cached.decode();
+        }
+
+        let metadata = self.inner.find_by_isbn(isbn).await?;
+        self.kv
+            .set_ex(&key, &CachedBookMetadata::encode(&metadata)?, self.ttl)
+            .await?;
+        Ok(metadata)
+    }
+}
+
+pub struct BookMetadataKey(String);
+
+impl From<&Isbn> for BookMetadataKey {
+    fn from(isbn: &Isbn) -> Self {
+        Self(format!("book_metadata:{}", isbn.as_str()))
+    }
+}
+
+impl RedisKey for BookMetadataKey {
+    type Value = CachedBookMetadata;
+
+    fn inner(&self) -> String {
+        self.0.clone()
+    }
+}
+
+// キャッシュする検索結果を JSON で表した値
+// 書誌情報が見つからなかった場合は null になる
+pub struct CachedBookMetadata(String);
+
+impl CachedBookMetadata {
+    fn encode(metadata: &Option<BookMetadata>) -> AppResult<Self> {
+        serde_json::to_string(metadata)
+            .map(Self)
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))
+    }
+
+    fn decode(self) -> AppResult<Option<BookMetadata>> {
+        serde_json::from_str(&self.0).map_err(|e| AppError::ConversionEntityError(e.to_string()))
+    }
+}
+
+impl RedisValue for CachedBookMetadata {
+    fn inner(&self) -> String {
+        self.0.clone()
+    }
+}
+
+impl TryFrom<String> for CachedBookMetadata {
+    type Error = AppError;
+
+    fn try_from(s: String) -> AppResult<Self> {
+        Ok(Self(s))
+    }
+}
@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::book::{isbn::Isbn, metadata::BookMetadata},
+    repository::book_metadata::BookMetadataProvider,
+};
+use shared::error::{AppError, AppResult};
+use std::{collections::HashMap, path::PathBuf};
+
+// JSON ファイルに記載した書誌情報から取得する
+// ファイルは ISBN をキー、書誌情報（title・author・description、いずれも省略可）を値とするオブジェクトとする
+// ISBN はハイフンを含んでいてもよく、ISBN-10 でもよい
+// 外部のサービスに接続できない環境での動作確認やテストに使う
+// ファイルは検索のたびに読み込むため、サーバーを再起動せずに内容を差し替えられる
+#[derive(new)]
+pub struct FileBookMetadataProvider {
+    path: PathBuf,
+}
+
+#[async_trait]
+impl BookMetadataProvider for FileBookMetadataProvider {
+    async fn find_by_isbn(&self, isbn: &Isbn) -> AppResult<Option<BookMetadata>> {
+        let input = tokio::fs::read_to_string(&self.path).await.map_err(|e| {
+            AppError::ExternalServiceError(format!("{}: {}", self.path.display(), e))
+        })?;
+        let books: HashMap<String, BookMetadata> = serde_json::from_str(&input).map_err(|e| {
+            AppError::ExternalServiceError(format!("{}: {}", self.path.display(), e))
+        })?;
+
+        for (key, metadata) in books {
+            let key = key.parse::<Isbn>().map_err(|e| {
+                AppError::ExternalServiceError(format!("{}: {}", self.path.display(), e))
+            })?;
+            if key == *isbn {
+                return Ok(Some(metadata));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_find_by_isbn() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("book_metadata_{}.json", std::process::id()));
+        tokio::fs::write(
+            &path,
+            r#"{
+                "4-06-536957-6": {"title": "Rust の本", "author": "著者 一郎"},
+                "9784798061702": {"title": "もう一冊", "description": "説明文"}
+            }"#,
+        )
+        .await?;
+        let provider = FileBookMetadataProvider::new(path.clone());
+
+        // ISBN-10 で記載したものも ISBN-13 で検索できる
+        let metadata = provider.find_by_isbn(&"978-4-06-536957-9".parse()?).await?;
+        assert_eq!(
+            metadata,
+            Some(BookMetadata {
+                title: Some("Rust の本".into()),
+                author: Some("著者 一郎".into()),
+                description: None,
+            })
+        );
+
+        // 記載のない ISBN は None になる
+        let metadata = provider.find_by_isbn(&"978-4-297-14190-5".parse()?).await?;
+        assert!(metadata.is_none());
+
+        tokio::fs::remove_file(&path).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,3 @@
+pub mod cache;
+pub mod file;
+pub mod open_library;
@@ -0,0 +1,203 @@
+use async_trait::async_trait;
+use kernel::{
+    model::book::{isbn::Isbn, metadata::BookMetadata},
+    repository::book_metadata::BookMetadataProvider,
+};
+use serde::Deserialize;
+use shared::error::{AppError, AppResult};
+use std::{collections::HashMap, time::Duration};
+
+// Open Library の Books API（/api/books）と同じ形式の API から書誌情報を取得する
+// base_url を差し替えることで、互換のある API やテスト用のスタブサーバーも使える
+pub struct OpenLibraryBookMetadataProvider {
+    client: reqwest::Client,
+    base_url: String,
+    timeout: Duration,
+}
+
+impl OpenLibraryBookMetadataProvider {
+    pub fn new(base_url: String, timeout: Duration) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url,
+            timeout,
+        }
+    }
+}
+
+#[async_trait]
+impl BookMetadataProvider for OpenLibraryBookMetadataProvider {
+    async fn find_by_isbn(&self, isbn: &Isbn) -> AppResult<Option<BookMetadata>> {
+        let bib_key = format!("ISBN:{}", isbn.as_str());
+        // 該当する書誌情報がない場合は空のオブジェクトが返る
+        let mut books: HashMap<String, OpenLibraryBook> = self
+            .client
+            .get(format!("{}/api/books", self.base_url.trim_end_matches('/')))
+            .query(&[
+                ("bibkeys", bib_key.as_str()),
+                ("format", "json"),
+                ("jscmd", "details"),
+            ])
+            .timeout(self.timeout)
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| AppError::ExternalServiceError(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| AppError::ExternalServiceError(e.to_string()))?;
+
+        Ok(books
+            .remove(&bib_key)
+            .map(|book| BookMetadata::from(book.details)))
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenLibraryBook {
+    details: OpenLibraryDetails,
+}
+
+#[derive(Deserialize)]
+struct OpenLibraryDetails {
+    title: Option<String>,
+    subtitle: Option<String>,
+    #[serde(default)]
+    authors: Vec<OpenLibraryAuthor>,
+    // 著者の情報がない場合に、著者名の代わりに使う責任表示
+    by_statement: Option<String>,
+    description: Option<OpenLibraryText>,
+}
+
+#[derive(Deserialize)]
+struct OpenLibraryAuthor {
+    name: Option<String>,
+}
+
+// 説明文は文字列か、{"type": "/type/text", "value": "..."} の形式で返る
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OpenLibraryText {
+    Plain(String),
+    Typed { value: String },
+}
+
+impl From<OpenLibraryDetails> for BookMetadata {
+    fn from(value: OpenLibraryDetails) -> Self {
+        let OpenLibraryDetails {
+            title,
+            subtitle,
+            authors,
+            by_statement,
+            description,
+        } = value;
+        // 副題は MARC の取り込みと同じく " : " でつなぐ
+        let title = match (title, subtitle) {
+            (Some(title), Some(subtitle)) => Some(format!("{title} : {subtitle}")),
+            (title, _) => title,
+        };
+        let authors = authors
+            .into_iter()
+            .filter_map(|author| author.name)
+            .collect::<Vec<_>>();
+        let author = if authors.is_empty() {
+            by_statement
+        } else {
+            Some(authors.join(", "))
+        };
+        let description = description.map(|text| match text {
+            OpenLibraryText::Plain(value) | OpenLibraryText::Typed { value } => value,
+        });
+        Self {
+            title: non_empty(title),
+            author: non_empty(author),
+            description: non_empty(description),
+        }
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    // 1 回だけリクエストを受け付け、body を返すスタブサーバーを起動する
+    // 受け取ったリクエストの先頭行（例: GET /api/books?... HTTP/1.1）を返す
+    async fn spawn_stub_server(
+        body: &'static str,
+    ) -> anyhow::Result<(String, tokio::task::JoinHandle<anyhow::Result<String>>)> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let base_url = format!("http://{}", listener.local_addr()?);
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await?;
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = stream.read(&mut buf).await?;
+                anyhow::ensure!(n > 0, "connection closed");
+                request.extend_from_slice(&buf[..n]);
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await?;
+            let request = String::from_utf8(request)?;
+            Ok(request.lines().next().unwrap_or_default().to_string())
+        });
+        Ok((base_url, handle))
+    }
+
+    #[tokio::test]
+    async fn test_find_by_isbn() -> anyhow::Result<()> {
+        let (base_url, server) = spawn_stub_server(
+            r#"{"ISBN:9784065369579": {"bib_key": "ISBN:9784065369579", "details": {
+                "title": "Rust の本", "subtitle": "入門編",
+                "authors": [{"key": "/authors/OL1A", "name": "著者 一郎"}, {"key": "/authors/OL2A", "name": "著者 二郎"}],
+                "description": {"type": "/type/text", "value": "Rust の入門書"}
+            }}}"#,
+        )
+        .await?;
+        let provider = OpenLibraryBookMetadataProvider::new(base_url, Duration::from_secs(5));
+
+        let isbn: Isbn = "978-4-06-536957-9".parse()?;
+        let metadata = provider.find_by_isbn(&isbn).await?;
+        assert_eq!(
+            metadata,
+            Some(BookMetadata {
+                title: Some("Rust の本 : 入門編".into()),
+                author: Some("著者 一郎, 著者 二郎".into()),
+                description: Some("Rust の入門書".into()),
+            })
+        );
+
+        let request_line = server.await??;
+        assert!(request_line.starts_with("GET /api/books?"));
+        assert!(request_line.contains("bibkeys=ISBN%3A9784065369579"));
+        assert!(request_line.contains("jscmd=details"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_by_isbn_not_found() -> anyhow::Result<()> {
+        let (base_url, server) = spawn_stub_server("{}").await?;
+        let provider = OpenLibraryBookMetadataProvider::new(base_url, Duration::from_secs(5));
+
+        let isbn: Isbn = "9784798061702".parse()?;
+        assert_eq!(provider.find_by_isbn(&isbn).await?, None);
+        server.await??;
+
+        Ok(())
+    }
+}
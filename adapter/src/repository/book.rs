@@ -1,108 +1,285 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use derive_new::new;
 // 新たに定義した型を追加で use する
-use crate::database::model::book::BookCheckoutRow;
-use crate::database::model::book::{BookRow, PaginatedBookRow};
+use crate::database::cursor::{
+    paginate_by_cursor, push_keyset_condition, push_keyset_order_by, Cursor, CursorDirection,
+};
+use crate::database::model::book::{
+    BookCheckoutRow, BookCopyCountRow, BookCursorRow, BookHistoryChangeRow, BookHistoryRow,
+    BookRow, BookTagRow, LockedBookRow, PaginatedBookRow, PurgeStateRow,
+};
+use crate::database::version::ensure_version;
 use crate::database::ConnectionPool;
 use kernel::model::book::Checkout;
 use kernel::model::{
-    id::{BookId, UserId},
+    id::{BookHistoryId, BookId, BranchId, CopyId, ShelfId, TagId, UserId},
+    list::{CursorPaginatedList, SortOrder},
+    tag::Tag,
     {book::event::DeleteBook, list::PaginatedList},
 };
 use kernel::{
     model::book::{
-        event::{CreateBook, UpdateBook},
-        Book, BookListOptions,
+        event::{
+            CreateBook, PatchBook, PurgeBook, RestoreBook, UpdateBook, UpdateBookCover,
+            UpdateBookLocation, UpdateBookTags,
+        },
+        history::{BookField, BookFieldChange, BookFieldValues, BookHistory, BookHistoryAction},
+        isbn::Isbn,
+        Book, BookCopyCounts, BookCursorListOptions, BookListFilter, BookListOptions, BookListSort,
+        BookSortKey, BookTagFilter, IsbnConflict, IsbnConflictKind, TagMatchMode,
     },
     repository::book::BookRepository,
 };
-use shared::error::{AppError, AppResult};
-use std::collections::HashMap;
+use shared::{
+    config::IsbnUniqueness,
+    error::{AppError, AppResult},
+};
+use sqlx::{MySql, MySqlConnection, QueryBuilder};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::str::FromStr;
+// 一括登録で 1 回の INSERT にまとめる件数
+const CREATE_MANY_CHUNK_SIZE: usize = 500;
+
 #[derive(new)]
 pub struct BookRepositoryImpl {
     db: ConnectionPool,
+    isbn_uniqueness: IsbnUniqueness,
 }
 
 #[async_trait]
 impl BookRepository for BookRepositoryImpl {
     async fn create(&self, event: CreateBook, user_id: UserId) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        self.ensure_unique_isbn(&mut tx, &event.isbn, user_id, None)
+            .await?;
+
+        let book_id = BookId::new();
         sqlx::query!(
             r#"
-                INSERT INTO books (title, author, isbn, description, user_id)
-                VALUES (?, ?, ?, ?, ?)  // ★★★ 修正: ? に変更 ★★★
+                INSERT INTO books (book_id, title, author, isbn, description, user_id)
+                VALUES (?, ?, ?, ?, ?, ?)
             "#,
+            book_id as _,
             event.title,
             event.author,
-            event.isbn,
+            event.isbn.as_str(),
             event.description,
             user_id as _
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
+        .await
+        .map_err(map_isbn_write_error)?;
+
+        // 登録した蔵書をすぐに貸し出せるように、所蔵資料を 1 冊あわせて登録する
+        // バーコードなどの情報は、所蔵資料の更新で後から設定する
+        sqlx::query!(
+            r#"
+                INSERT INTO book_copies (copy_id, book_id)
+                VALUES (?, ?)
+            "#,
+            CopyId::new() as _,
+            book_id as _
+        )
+        .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
+
+        let history = BookHistoryRecord::created(book_id, user_id, &event);
+        insert_book_history(&mut tx, &[history]).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
         Ok(())
     }
 
+    async fn create_many(
+        &self,
+        events: Vec<CreateBook>,
+        user_id: UserId,
+    ) -> AppResult<Vec<IsbnConflict>> {
+        if events.is_empty() {
+            return Ok(vec![]);
+        }
+        let isbns = events.iter().map(|e| &e.isbn).collect::<Vec<_>>();
+        let mut tx = self.db.begin().await?;
+        let conflicts = self.isbn_conflicts(&mut tx, &isbns, user_id, true).await?;
+
+        // ISBN が重複する蔵書は登録せず、残りの蔵書だけを登録する
+        let conflicted = conflicts.iter().map(|c| c.index).collect::<HashSet<_>>();
+        let events = events
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !conflicted.contains(i))
+            .map(|(_, event)| event)
+            .collect::<Vec<_>>();
+
+        // プレースホルダの数が上限を超えないよう、一定の件数ごとに分けて INSERT する
+        for chunk in events.chunks(CREATE_MANY_CHUNK_SIZE) {
+            let book_ids = chunk.iter().map(|_| BookId::new()).collect::<Vec<_>>();
+
+            let mut query = QueryBuilder::<MySql>::new(
+                "INSERT INTO books (book_id, title, author, isbn, description, user_id) ",
+            );
+            query.push_values(chunk.iter().zip(&book_ids), |mut row, (event, book_id)| {
+                row.push_bind(*book_id)
+                    .push_bind(&event.title)
+                    .push_bind(&event.author)
+                    .push_bind(event.isbn.as_str())
+                    .push_bind(&event.description)
+                    .push_bind(user_id);
+            });
+            query
+                .build()
+                .execute(&mut *tx)
+                .await
+                .map_err(map_isbn_write_error)?;
+
+            // create と同じく、蔵書ごとに所蔵資料を 1 冊あわせて登録する
+            let mut query =
+                QueryBuilder::<MySql>::new("INSERT INTO book_copies (copy_id, book_id) ");
+            query.push_values(&book_ids, |mut row, book_id| {
+                row.push_bind(CopyId::new()).push_bind(*book_id);
+            });
+            query
+                .build()
+                .execute(&mut *tx)
+                .await
+                .map_err(AppError::SpecificOperationError)?;
+
+            let histories = chunk
+                .iter()
+                .zip(&book_ids)
+                .map(|(event, book_id)| BookHistoryRecord::created(*book_id, user_id, event))
+                .collect::<Vec<_>>();
+            insert_book_history(&mut tx, &histories).await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(conflicts)
+    }
+
+    async fn find_isbn_conflicts(
+        &self,
+        isbns: &[Isbn],
+        user_id: UserId,
+    ) -> AppResult<Vec<IsbnConflict>> {
+        let mut conn = self
+            .db
+            .inner_ref()
+            .acquire()
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+        let isbns = isbns.iter().collect::<Vec<_>>();
+        self.isbn_conflicts(&mut conn, &isbns, user_id, false).await
+    }
+
     async fn find_all(&self, options: BookListOptions) -> AppResult<PaginatedList<Book>> {
-        let BookListOptions { limit, offset } = options;
-        let rows: Vec<PaginatedBookRow> = sqlx::query_as!(
-            PaginatedBookRow,
+        let BookListOptions {
+            limit,
+            offset,
+            filter,
+            sort,
+        } = options;
+        // 絞り込み条件と並び順はリクエストごとに変わるため、QueryBuilder で組み立てる
+        // COUNT(*) OVER() は WHERE 句の適用後に評価されるため、
+        // total は絞り込み後の件数になる
+        let mut query = QueryBuilder::new(
             r#"
                 SELECT
-                COUNT(*) OVER() AS "total!",
+                COUNT(*) OVER() AS total,
                 b.book_id AS id
                 FROM books AS b
-                ORDER BY b.created_at DESC
-                LIMIT ?
-                OFFSET ? // ★★★ 修正: ? に変更 ★★★
             "#,
-            limit,
-            offset
-        )
-        .fetch_all(self.db.inner_ref())
-        .await
-        .map_err(AppError::SpecificOperationError)?;
+        );
+        push_book_filter(&mut query, &filter);
+        push_book_order_by(&mut query, sort);
+        query
+            .push(" LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+        let rows: Vec<PaginatedBookRow> = query
+            .build_query_as()
+            .fetch_all(self.db.inner_ref())
+            .await
+            .map_err(AppError::SpecificOperationError)?;
 
         let total = rows.first().map(|r| r.total).unwrap_or_default();
         let book_ids = rows.into_iter().map(|r| r.id).collect::<Vec<BookId>>();
+        let items = self.find_by_ids(&book_ids).await?;
 
-        let rows: Vec<BookRow> = sqlx::query_as!(
-            BookRow,
+        Ok(PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        })
+    }
+
+    async fn find_all_by_cursor(
+        &self,
+        options: BookCursorListOptions,
+    ) -> AppResult<CursorPaginatedList<Book>> {
+        let BookCursorListOptions {
+            limit,
+            cursor,
+            filter,
+            sort,
+        } = options;
+        let cursor = cursor.as_deref().map(Cursor::decode).transpose()?;
+        let direction = cursor
+            .as_ref()
+            .map(|c| c.direction)
+            .unwrap_or(CursorDirection::Next);
+        let columns = (book_sort_column(sort.key), "b.book_id");
+
+        let mut query = QueryBuilder::new(
             r#"
                 SELECT
-                    b.book_id AS book_id,
-                    b.title AS title,
-                    b.author AS author,
-                    b.isbn AS isbn,
-                    b.description AS description,
-                    u.user_id AS owned_by,
-                    u.name AS owner_name
+                b.book_id,
+                b.title,
+                b.author,
+                b.isbn,
+                b.created_at,
+                b.updated_at
                 FROM books AS b
-                INNER JOIN users AS u USING(user_id)
-                WHERE b.book_id IN (?)  // ★★★ 修正: IN (?) に変更 ★★★
-                ORDER BY b.created_at DESC
             "#,
-            &book_ids as _
-        )
-        .fetch_all(self.db.inner_ref())
-        .await
-        .map_err(AppError::SpecificOperationError)?;
+        );
+        push_book_filter(&mut query, &filter);
+        if let Some(cursor) = &cursor {
+            let (key, book_id) = decode_book_cursor(cursor, sort)?;
+            match key {
+                BookCursorKey::Text(key) => {
+                    push_keyset_condition(&mut query, columns, sort.order, direction, key, book_id)
+                }
+                BookCursorKey::Timestamp(key) => {
+                    push_keyset_condition(&mut query, columns, sort.order, direction, key, book_id)
+                }
+            }
+        }
+        push_keyset_order_by(&mut query, columns, sort.order, direction);
+        // 次のページがあるかどうかを判定するため 1 件多く取得する
+        query.push(" LIMIT ").push_bind(limit.saturating_add(1));
+        let rows: Vec<BookCursorRow> = query
+            .build_query_as()
+            .fetch_all(self.db.inner_ref())
+            .await
+            .map_err(AppError::SpecificOperationError)?;
 
-        let book_ids = rows.iter().map(|book| book.book_id).collect::<Vec<_>>();
-        let mut checkouts = self.find_checkouts(&book_ids).await?;
-        let items = rows
-            .into_iter()
-            .map(|row| {
-                let checkout = checkouts.remove(&row.book_id);
-                row.into_book(checkout)
-            })
-            .collect();
+        let (rows, next_cursor, prev_cursor) =
+            paginate_by_cursor(rows, limit, cursor.as_ref(), |row| {
+                book_cursor_values(sort, row)
+            });
+        let book_ids = rows.into_iter().map(|r| r.book_id).collect::<Vec<_>>();
+        let items = self.find_by_ids(&book_ids).await?;
 
-        Ok(PaginatedList {
-            total,
+        Ok(CursorPaginatedList {
             limit,
-            offset,
             items,
+            next_cursor,
+            prev_cursor,
         })
     }
 
@@ -117,10 +294,20 @@ impl BookRepository for BookRepositoryImpl {
                     b.isbn AS isbn,
                     b.description AS description,
                     u.user_id AS owned_by,
-                    u.name AS owner_name
+                    u.name AS owner_name,
+                    b.cover_content_type AS cover_content_type,
+                    b.cover_updated_at AS cover_updated_at,
+                    s.shelf_id AS "shelf_id?: ShelfId",
+                    s.name AS "shelf_name?",
+                    br.branch_id AS "branch_id?: BranchId",
+                    br.name AS "branch_name?",
+                    b.version AS version
                 FROM books AS b
                 INNER JOIN users AS u USING(user_id)
+                LEFT OUTER JOIN shelves AS s ON s.shelf_id = b.shelf_id
+                LEFT OUTER JOIN branches AS br ON br.branch_id = s.branch_id
                 WHERE b.book_id = ? // ★★★ 修正: ? に変更 ★★★
+                AND b.deleted_at IS NULL
             "#,
             book_id as _
         )
@@ -130,108 +317,1253 @@ impl BookRepository for BookRepositoryImpl {
 
         match row {
             Some(r) => {
-                let checkout = self.find_checkouts(&[r.book_id]).await?.remove(&r.book_id);
-                Ok(Some(r.into_book(checkout)))
+                let copies = self
+                    .find_copy_counts(&[r.book_id])
+                    .await?
+                    .remove(&r.book_id)
+                    .unwrap_or_default();
+                let checkouts = self
+                    .find_checkouts(&[r.book_id])
+                    .await?
+                    .remove(&r.book_id)
+                    .unwrap_or_default();
+                let tags = self
+                    .find_tags(&[r.book_id])
+                    .await?
+                    .remove(&r.book_id)
+                    .unwrap_or_default();
+                Ok(Some(r.into_book(copies, checkouts, tags)))
             }
             None => Ok(None),
         }
     }
 
+    async fn find_history(&self, book_id: BookId) -> AppResult<Option<BookHistory>> {
+        // 削除済みの蔵書の履歴も取得できるよう、削除済みかどうかは問わない
+        // 完全に削除された蔵書の場合は、所有者なしで変更履歴だけを返す
+        let owned_by = sqlx::query_scalar!(
+            r#"
+                SELECT user_id AS "user_id: UserId"
+                FROM books
+                WHERE book_id = ?
+            "#,
+            book_id as _
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let rows = sqlx::query_as!(
+            BookHistoryRow,
+            r#"
+                SELECT
+                    book_history_id AS "book_history_id: BookHistoryId",
+                    action,
+                    changed_by AS "changed_by?: UserId",
+                    changed_at
+                FROM book_history
+                WHERE book_id = ?
+                ORDER BY changed_at DESC, book_history_id DESC
+            "#,
+            book_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if owned_by.is_none() && rows.is_empty() {
+            return Ok(None);
+        }
+
+        let change_rows = sqlx::query_as!(
+            BookHistoryChangeRow,
+            r#"
+                SELECT
+                    c.book_history_id AS "book_history_id: BookHistoryId",
+                    c.field_name,
+                    c.before_value,
+                    c.after_value
+                FROM book_history_changes AS c
+                INNER JOIN book_history AS h USING(book_history_id)
+                WHERE h.book_id = ?
+            "#,
+            book_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        let mut changes: HashMap<BookHistoryId, Vec<BookFieldChange>> = HashMap::new();
+        for row in change_rows {
+            let history_id = row.book_history_id;
+            changes
+                .entry(history_id)
+                .or_default()
+                .push(BookFieldChange::try_from(row)?);
+        }
+
+        let entries = rows
+            .into_iter()
+            .map(|row| {
+                let mut entry_changes = changes.remove(&row.book_history_id).unwrap_or_default();
+                entry_changes.sort_by_key(|change| change.field);
+                row.into_entry(entry_changes)
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+
+        Ok(Some(BookHistory {
+            book_id,
+            owned_by,
+            entries,
+        }))
+    }
+
     async fn update(&self, event: UpdateBook) -> AppResult<()> {
-        let res = sqlx::query!(
+        let mut tx = self.db.begin().await?;
+        let (version, before) = self
+            .lock_book(&mut tx, event.book_id, event.requested_user)
+            .await?;
+        ensure_version(version, event.expected_version)?;
+        self.ensure_unique_isbn(
+            &mut tx,
+            &event.isbn,
+            event.requested_user,
+            Some(event.book_id),
+        )
+        .await?;
+
+        sqlx::query!(
             r#"
                 UPDATE books
                 SET
                     title = ?,
                     author = ?,
                     isbn = ?,
-                    description = ?
-                WHERE book_id = ? // ★★★ 修正: ? に変更 ★★★
-                AND user_id = ? // ★★★ 修正: ? に変更 ★★★
+                    description = ?,
+                    version = version + 1
+                WHERE book_id = ?
             "#,
             event.title,
             event.author,
-            event.isbn,
+            event.isbn.as_str(),
             event.description,
+            event.book_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(map_isbn_write_error)?;
+
+        let after = BookFieldValues {
+            title: event.title,
+            author: event.author,
+            isbn: event.isbn,
+            description: event.description,
+        };
+        // 値が変わった項目がなければ、変更履歴は記録しない
+        if let Some(history) =
+            BookHistoryRecord::updated(event.book_id, event.requested_user, &before, &after)
+        {
+            insert_book_history(&mut tx, &[history]).await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn patch(&self, event: PatchBook) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        let (version, before) = self
+            .lock_book(&mut tx, event.book_id, event.requested_user)
+            .await?;
+        ensure_version(version, event.expected_version)?;
+        if let Some(isbn) = &event.isbn {
+            self.ensure_unique_isbn(&mut tx, isbn, event.requested_user, Some(event.book_id))
+                .await?;
+        }
+
+        // 更新する項目がなければ、蔵書の存在とバージョンの確認だけを行う
+        if event.title.is_none()
+            && event.author.is_none()
+            && event.isbn.is_none()
+            && event.description.is_none()
+        {
+            return Ok(());
+        }
+
+        // 指定された項目の列だけを更新する
+        let mut query = QueryBuilder::<MySql>::new("UPDATE books SET ");
+        let mut columns = query.separated(", ");
+        if let Some(title) = &event.title {
+            columns.push("title = ").push_bind_unseparated(title);
+        }
+        if let Some(author) = &event.author {
+            columns.push("author = ").push_bind_unseparated(author);
+        }
+        if let Some(isbn) = &event.isbn {
+            columns.push("isbn = ").push_bind_unseparated(isbn.as_str());
+        }
+        if let Some(description) = &event.description {
+            columns
+                .push("description = ")
+                .push_bind_unseparated(description);
+        }
+        columns.push("version = version + 1");
+        query.push(" WHERE book_id = ").push_bind(event.book_id);
+        query
+            .build()
+            .execute(&mut *tx)
+            .await
+            .map_err(map_isbn_write_error)?;
+
+        let after = BookFieldValues {
+            title: event.title.clone().unwrap_or_else(|| before.title.clone()),
+            author: event
+                .author
+                .clone()
+                .unwrap_or_else(|| before.author.clone()),
+            isbn: event.isbn.clone().unwrap_or_else(|| before.isbn.clone()),
+            description: event
+                .description
+                .clone()
+                .unwrap_or_else(|| before.description.clone()),
+        };
+        // 値が変わった項目がなければ、変更履歴は記録しない
+        if let Some(history) =
+            BookHistoryRecord::updated(event.book_id, event.requested_user, &before, &after)
+        {
+            insert_book_history(&mut tx, &[history]).await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn update_cover(&self, event: UpdateBookCover) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // 変更履歴に記録するため、蔵書の行をロックして変更前の形式を取得する
+        let before = sqlx::query_scalar!(
+            r#"
+                SELECT cover_content_type
+                FROM books
+                WHERE book_id = ?
+                AND (user_id = ? OR ?)
+                AND deleted_at IS NULL
+                FOR UPDATE
+            "#,
             event.book_id as _,
-            event.requested_user as _
+            event.requested_user as _,
+            event.by_admin
         )
-        .execute(self.db.inner_ref())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| AppError::EntityNotFound("specified book not found".into()))?;
+
+        sqlx::query!(
+            r#"
+                UPDATE books
+                SET
+                    cover_content_type = ?,
+                    cover_updated_at = CURRENT_TIMESTAMP(3),
+                    version = version + 1
+                WHERE book_id = ?
+            "#,
+            event.content_type,
+            event.book_id as _
+        )
+        .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
-        if res.rows_affected() < 1 {
+
+        // 同じ形式の画像に差し替えた場合も、画像そのものは変わるため記録する
+        let history = BookHistoryRecord::field_updated(
+            event.book_id,
+            event.requested_user,
+            BookFieldChange {
+                field: BookField::Cover,
+                before,
+                after: Some(event.content_type),
+            },
+        );
+        insert_book_history(&mut tx, &[history]).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn update_tags(&self, event: UpdateBookTags) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // タグを付け替えている間に蔵書が削除されないよう、蔵書の行をロックする
+        let book_id = sqlx::query_scalar!(
+            r#"
+                SELECT book_id AS "book_id: BookId"
+                FROM books
+                WHERE book_id = ?
+                AND (user_id = ? OR ?)
+                AND deleted_at IS NULL
+                FOR UPDATE
+            "#,
+            event.book_id as _,
+            event.requested_user as _,
+            event.by_admin
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if book_id.is_none() {
             return Err(AppError::EntityNotFound("specified book not found".into()));
         }
 
+        let before = sqlx::query_scalar!(
+            r#"
+                SELECT tag_id AS "tag_id: TagId" FROM book_tags WHERE book_id = ?
+            "#,
+            event.book_id as _
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .collect::<HashSet<TagId>>();
+
+        sqlx::query!(
+            r#"
+                UPDATE books SET version = version + 1 WHERE book_id = ?
+            "#,
+            event.book_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        sqlx::query!(
+            r#"
+                DELETE FROM book_tags WHERE book_id = ?
+            "#,
+            event.book_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let tag_ids = event.tag_ids.into_iter().collect::<HashSet<TagId>>();
+        if !tag_ids.is_empty() {
+            // 存在しないタグは外部キー制約で弾かれるため、エラーの内容を置き換える
+            let mut query = QueryBuilder::<MySql>::new("INSERT INTO book_tags (book_id, tag_id) ");
+            query.push_values(&tag_ids, |mut row, tag_id| {
+                row.push_bind(event.book_id).push_bind(*tag_id);
+            });
+            query
+                .build()
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| match e.as_database_error() {
+                    Some(db_error) if db_error.is_foreign_key_violation() => {
+                        AppError::UnprocessableEntity("存在しないタグが指定されています".into())
+                    }
+                    _ => AppError::SpecificOperationError(e),
+                })?;
+        }
+
+        // タグの組み合わせが変わらなければ、変更履歴は記録しない
+        if before != tag_ids {
+            let history = BookHistoryRecord::field_updated(
+                event.book_id,
+                event.requested_user,
+                BookFieldChange {
+                    field: BookField::Tags,
+                    before: tag_ids_value(&before),
+                    after: tag_ids_value(&tag_ids),
+                },
+            );
+            insert_book_history(&mut tx, &[history]).await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn update_location(&self, event: UpdateBookLocation) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // MySQL では配架場所が変わらない場合に変更行数が 0 になるため、存在の確認は別に行う
+        // 変更履歴に記録するため、蔵書の行をロックして変更前の書架を取得する
+        let before = sqlx::query_scalar!(
+            r#"
+                SELECT shelf_id AS "shelf_id: ShelfId"
+                FROM books
+                WHERE book_id = ? AND (user_id = ? OR ?) AND deleted_at IS NULL
+                FOR UPDATE
+            "#,
+            event.book_id as _,
+            event.requested_user as _,
+            event.by_admin
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| AppError::EntityNotFound("specified book not found".into()))?;
+
+        // 存在しない書架は外部キー制約で弾かれるため、エラーの内容を置き換える
+        sqlx::query!(
+            r#"
+                UPDATE books
+                SET shelf_id = ?, version = version + 1
+                WHERE book_id = ?
+            "#,
+            event.shelf_id as _,
+            event.book_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(db_error) if db_error.is_foreign_key_violation() => {
+                AppError::UnprocessableEntity("存在しない書架が指定されています".into())
+            }
+            _ => AppError::SpecificOperationError(e),
+        })?;
+
+        // 配架場所が変わらなければ、変更履歴は記録しない
+        if before != event.shelf_id {
+            let history = BookHistoryRecord::field_updated(
+                event.book_id,
+                event.requested_user,
+                BookFieldChange {
+                    field: BookField::Shelf,
+                    before: before.map(|shelf_id| shelf_id.to_string()),
+                    after: event.shelf_id.map(|shelf_id| shelf_id.to_string()),
+                },
+            );
+            insert_book_history(&mut tx, &[history]).await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
         Ok(())
     }
 
     async fn delete(&self, event: DeleteBook) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        let (version, _) = self
+            .lock_book(&mut tx, event.book_id, event.requested_user)
+            .await?;
+        ensure_version(version, event.expected_version)?;
+
+        // 行を削除すると貸出のレコードも一緒に削除されてしまうため、削除済みの印を付けるだけにする
+        sqlx::query!(
+            r#"
+                UPDATE books
+                SET deleted_at = CURRENT_TIMESTAMP(3), version = version + 1
+                WHERE book_id = ?
+            "#,
+            event.book_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let history = BookHistoryRecord::new(
+            event.book_id,
+            BookHistoryAction::Delete,
+            event.requested_user,
+        );
+        insert_book_history(&mut tx, &[history]).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn restore(&self, event: RestoreBook) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
         let res = sqlx::query!(
             r#"
-                DELETE FROM books
-                WHERE book_id = ? // ★★★ 修正: ? に変更 ★★★
-                AND user_id = ? // ★★★ 修正: ? に変更 ★★★
+                UPDATE books
+                SET deleted_at = NULL, version = version + 1
+                WHERE book_id = ?
+                AND deleted_at IS NOT NULL
             "#,
-            event.book_id as _,
-            event.requested_user as _
+            event.book_id as _
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
 
         if res.rows_affected() < 1 {
-            return Err(AppError::EntityNotFound("specified book not found".into()));
+            return Err(AppError::EntityNotFound(
+                "specified deleted book not found".into(),
+            ));
         }
 
+        let history = BookHistoryRecord::new(
+            event.book_id,
+            BookHistoryAction::Restore,
+            event.requested_user,
+        );
+        insert_book_history(&mut tx, &[history]).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
         Ok(())
     }
-}
 
-impl BookRepositoryImpl {
-    async fn find_checkouts(&self, book_ids: &[BookId]) -> AppResult<HashMap<BookId, Checkout>> {
-        let res = sqlx::query_as!(
-            BookCheckoutRow,
+    async fn purge(&self, event: PurgeBook) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // 確認している間に貸し出されたり復元されたりしないよう、蔵書の行をロックする
+        let state = sqlx::query_as!(
+            PurgeStateRow,
             r#"
                 SELECT
-                c.checkout_id,
-                c.book_id,
-                u.user_id,
-                u.name AS user_name,
-                c.checked_out_at
-                FROM checkouts AS c
-                INNER JOIN users AS u USING(user_id)
-                WHERE c.book_id IN (?) // ★★★ 修正: IN (?) に変更 ★★★
-                ;
+                    b.deleted_at IS NOT NULL AS "deleted: bool",
+                    EXISTS(
+                        SELECT 1 FROM checkouts AS c WHERE c.book_id = b.book_id
+                    ) AS "checked_out: bool",
+                    b.title,
+                    b.author,
+                    b.isbn,
+                    b.description
+                FROM books AS b
+                WHERE b.book_id = ?
+                FOR UPDATE
             "#,
-            book_ids as _
+            event.book_id as _
         )
-        .fetch_all(self.db.inner_ref())
+        .fetch_optional(&mut *tx)
         .await
-        .map_err(AppError::SpecificOperationError)?
-        .into_iter()
-        .map(|checkout| (checkout.book_id, Checkout::from(checkout)))
-        .collect();
+        .map_err(AppError::SpecificOperationError)?;
 
-        Ok(res)
-    }
-}
+        let state = match state {
+            None => return Err(AppError::EntityNotFound("specified book not found".into())),
+            Some(PurgeStateRow { deleted: false, .. }) => {
+                return Err(AppError::UnprocessableEntity(format!(
+                    "書籍（{}）は削除済みでないため完全に削除できません。",
+                    event.book_id
+                )))
+            }
+            Some(PurgeStateRow {
+                checked_out: true, ..
+            }) => {
+                return Err(AppError::UnprocessableEntity(format!(
+                    "書籍（{}）は貸出中の所蔵資料があるため完全に削除できません。",
+                    event.book_id
+                )))
+            }
+            Some(state) => state,
+        };
+
+        // 返却済みの貸出履歴は蔵書を参照する外部キーを持たないため、蔵書を削除しても残る
+        // 蔵書を削除した後も貸出履歴に表示できるよう、蔵書の情報を記録しておく
+        sqlx::query!(
+            r#"
+                UPDATE returned_checkouts AS r
+                INNER JOIN books AS b USING(book_id)
+                SET
+                    r.book_title = b.title,
+                    r.book_author = b.author,
+                    r.book_isbn = b.isbn
+                WHERE r.book_id = ?
+            "#,
+            event.book_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        // 所蔵資料・予約・タグの付与は外部キー制約により蔵書とあわせて削除される
+        // 変更履歴は蔵書を参照する外部キーを持たないため、蔵書を削除しても残る
+        sqlx::query!(
+            r#"
+                DELETE FROM books WHERE book_id = ?
+            "#,
+            event.book_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let history =
+            BookHistoryRecord::purged(event.book_id, event.requested_user, &state.into_fields());
+        insert_book_history(&mut tx, &[history]).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+}
+
+impl BookRepositoryImpl {
+    // 指定した ID の蔵書を、貸出情報とあわせて ID の並び順のとおりに取得する
+    async fn find_by_ids(&self, book_ids: &[BookId]) -> AppResult<Vec<Book>> {
+        if book_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // IN 句の要素数は引数の ID の数で変わるため、QueryBuilder で組み立てる
+        let mut query = QueryBuilder::<MySql>::new(
+            r#"
+                SELECT
+                    b.book_id AS book_id,
+                    b.title AS title,
+                    b.author AS author,
+                    b.isbn AS isbn,
+                    b.description AS description,
+                    u.user_id AS owned_by,
+                    u.name AS owner_name,
+                    b.cover_content_type AS cover_content_type,
+                    b.cover_updated_at AS cover_updated_at,
+                    s.shelf_id AS shelf_id,
+                    s.name AS shelf_name,
+                    br.branch_id AS branch_id,
+                    br.name AS branch_name,
+                    b.version AS version
+                FROM books AS b
+                INNER JOIN users AS u USING(user_id)
+                LEFT OUTER JOIN shelves AS s ON s.shelf_id = b.shelf_id
+                LEFT OUTER JOIN branches AS br ON br.branch_id = s.branch_id
+                WHERE b.book_id IN (
+            "#,
+        );
+        let mut separated = query.separated(", ");
+        for book_id in book_ids {
+            separated.push_bind(*book_id);
+        }
+        separated.push_unseparated(")");
+
+        let rows: Vec<BookRow> = query
+            .build_query_as()
+            .fetch_all(self.db.inner_ref())
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+        let mut rows: HashMap<BookId, BookRow> =
+            rows.into_iter().map(|row| (row.book_id, row)).collect();
+
+        let mut copies = self.find_copy_counts(book_ids).await?;
+        let mut checkouts = self.find_checkouts(book_ids).await?;
+        let mut tags = self.find_tags(book_ids).await?;
+        // 引数で渡された ID の並び順のとおりに蔵書を並べる
+        let books = book_ids
+            .iter()
+            .filter_map(|book_id| rows.remove(book_id))
+            .map(|row| {
+                let book_copies = copies.remove(&row.book_id).unwrap_or_default();
+                let book_checkouts = checkouts.remove(&row.book_id).unwrap_or_default();
+                let book_tags = tags.remove(&row.book_id).unwrap_or_default();
+                row.into_book(book_copies, book_checkouts, book_tags)
+            })
+            .collect();
+
+        Ok(books)
+    }
+
+    // バージョンを確認してから更新・削除するまでの間に他の操作で更新されないよう、
+    // 所有者の削除済みでない蔵書の行をロックして、現在のバージョンと変更履歴に記録する変更前の値を取得する
+    async fn lock_book(
+        &self,
+        tx: &mut sqlx::Transaction<'_, MySql>,
+        book_id: BookId,
+        requested_user: UserId,
+    ) -> AppResult<(i64, BookFieldValues)> {
+        sqlx::query_as!(
+            LockedBookRow,
+            r#"
+                SELECT version, title, author, isbn, description
+                FROM books
+                WHERE book_id = ?
+                AND user_id = ?
+                AND deleted_at IS NULL
+                FOR UPDATE
+            "#,
+            book_id as _,
+            requested_user as _
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| AppError::EntityNotFound("specified book not found".into()))
+        .map(LockedBookRow::into_fields)
+    }
+
+    // 設定に応じて、同じ ISBN の蔵書がすでに登録されていないかを確認する
+    // 更新時は exclude に更新対象の蔵書 ID を渡し、その蔵書自身は確認の対象から外す
+    // 確認してから登録・更新するまでの間に同じ ISBN の蔵書が登録されないよう、
+    // 登録・更新と同じトランザクションの中でロック付きで読み取る
+    async fn ensure_unique_isbn(
+        &self,
+        tx: &mut sqlx::Transaction<'_, MySql>,
+        isbn: &Isbn,
+        owner: UserId,
+        exclude: Option<BookId>,
+    ) -> AppResult<()> {
+        let mut query = QueryBuilder::<MySql>::new("SELECT COUNT(*) FROM books WHERE isbn = ");
+        query.push_bind(isbn.to_string());
+        match self.isbn_uniqueness {
+            IsbnUniqueness::Disabled => return Ok(()),
+            IsbnUniqueness::PerOwner => {
+                query.push(" AND user_id = ").push_bind(owner);
+            }
+            IsbnUniqueness::Global => {}
+        }
+        if let Some(book_id) = exclude {
+            query.push(" AND book_id <> ").push_bind(book_id);
+        }
+        query.push(" FOR UPDATE");
+
+        let count: i64 = query
+            .build_query_scalar()
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+        if count > 0 {
+            return Err(AppError::Conflict(format!(
+                "ISBN {} の蔵書はすでに登録されています",
+                isbn.hyphenated()
+            )));
+        }
+
+        Ok(())
+    }
+
+    // 一括登録する蔵書のうち、ISBN が登録済みの蔵書や一括登録する他の蔵書と重複するものを調べる
+    // 登録済みの蔵書と重複するものはすべて、一括登録する蔵書の間で重複するものは 2 件目以降を重複とする
+    // lock が true の場合は、ensure_unique_isbn と同じく登録と同じトランザクションの中でロック付きで読み取る
+    async fn isbn_conflicts(
+        &self,
+        conn: &mut MySqlConnection,
+        isbns: &[&Isbn],
+        owner: UserId,
+        lock: bool,
+    ) -> AppResult<Vec<IsbnConflict>> {
+        if self.isbn_uniqueness == IsbnUniqueness::Disabled {
+            return Ok(vec![]);
+        }
+
+        let mut registered = HashSet::new();
+        for chunk in isbns.chunks(CREATE_MANY_CHUNK_SIZE) {
+            let mut query =
+                QueryBuilder::<MySql>::new("SELECT DISTINCT isbn FROM books WHERE isbn IN (");
+            let mut separated = query.separated(", ");
+            for isbn in chunk {
+                separated.push_bind(isbn.as_str());
+            }
+            separated.push_unseparated(")");
+            if self.isbn_uniqueness == IsbnUniqueness::PerOwner {
+                query.push(" AND user_id = ").push_bind(owner);
+            }
+            if lock {
+                query.push(" FOR UPDATE");
+            }
+
+            let rows: Vec<String> = query
+                .build_query_scalar()
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(AppError::SpecificOperationError)?;
+            registered.extend(rows);
+        }
+
+        let mut seen = HashSet::new();
+        let conflicts = isbns
+            .iter()
+            .enumerate()
+            .filter_map(|(index, isbn)| {
+                let kind = if registered.contains(isbn.as_str()) {
+                    IsbnConflictKind::Registered
+                } else if !seen.insert(isbn.as_str()) {
+                    IsbnConflictKind::Batch
+                } else {
+                    return None;
+                };
+                Some(IsbnConflict {
+                    index,
+                    isbn: (*isbn).clone(),
+                    kind,
+                })
+            })
+            .collect();
+
+        Ok(conflicts)
+    }
+
+    // 蔵書ごとの所蔵資料の数と、そのうち貸出中でないものの数を集計する
+    async fn find_copy_counts(
+        &self,
+        book_ids: &[BookId],
+    ) -> AppResult<HashMap<BookId, BookCopyCounts>> {
+        if book_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut query = QueryBuilder::<MySql>::new(
+            r#"
+                SELECT
+                bc.book_id,
+                COUNT(*) AS total,
+                CAST(SUM(c.checkout_id IS NULL) AS SIGNED) AS available
+                FROM book_copies AS bc
+                LEFT OUTER JOIN checkouts AS c ON c.copy_id = bc.copy_id
+                WHERE bc.copy_condition <> 'Lost'
+                AND bc.book_id IN (
+            "#,
+        );
+        let mut separated = query.separated(", ");
+        for book_id in book_ids {
+            separated.push_bind(*book_id);
+        }
+        separated.push_unseparated(") GROUP BY bc.book_id");
+
+        let rows: Vec<BookCopyCountRow> = query
+            .build_query_as()
+            .fetch_all(self.db.inner_ref())
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.book_id, BookCopyCounts::from(row)))
+            .collect())
+    }
+
+    // 蔵書ごとの貸出中の貸出情報を、貸出日の古い順に取得する
+    async fn find_checkouts(
+        &self,
+        book_ids: &[BookId],
+    ) -> AppResult<HashMap<BookId, Vec<Checkout>>> {
+        if book_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut query = QueryBuilder::<MySql>::new(
+            r#"
+                SELECT
+                c.checkout_id,
+                c.book_id,
+                c.copy_id,
+                u.user_id,
+                u.name AS user_name,
+                c.checked_out_at,
+                c.due_at
+                FROM checkouts AS c
+                INNER JOIN users AS u USING(user_id)
+                WHERE c.book_id IN (
+            "#,
+        );
+        let mut separated = query.separated(", ");
+        for book_id in book_ids {
+            separated.push_bind(*book_id);
+        }
+        separated.push_unseparated(") ORDER BY c.checked_out_at ASC, c.checkout_id ASC");
+
+        let rows: Vec<BookCheckoutRow> = query
+            .build_query_as()
+            .fetch_all(self.db.inner_ref())
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+
+        let mut res: HashMap<BookId, Vec<Checkout>> = HashMap::new();
+        for row in rows {
+            res.entry(row.book_id)
+                .or_default()
+                .push(Checkout::from(row));
+        }
+
+        Ok(res)
+    }
+
+    // 蔵書ごとに付けられたタグを、名前の順に取得する
+    async fn find_tags(&self, book_ids: &[BookId]) -> AppResult<HashMap<BookId, Vec<Tag>>> {
+        if book_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut query = QueryBuilder::<MySql>::new(
+            r#"
+                SELECT
+                bt.book_id,
+                t.tag_id,
+                t.name
+                FROM book_tags AS bt
+                INNER JOIN tags AS t USING(tag_id)
+                WHERE bt.book_id IN (
+            "#,
+        );
+        let mut separated = query.separated(", ");
+        for book_id in book_ids {
+            separated.push_bind(*book_id);
+        }
+        separated.push_unseparated(") ORDER BY t.name ASC");
+
+        let rows: Vec<BookTagRow> = query
+            .build_query_as()
+            .fetch_all(self.db.inner_ref())
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+
+        let mut res: HashMap<BookId, Vec<Tag>> = HashMap::new();
+        for row in rows {
+            res.entry(row.book_id).or_default().push(Tag::from(row));
+        }
+
+        Ok(res)
+    }
+}
+
+// 蔵書の変更履歴として記録する内容
+struct BookHistoryRecord {
+    book_id: BookId,
+    action: BookHistoryAction,
+    changed_by: UserId,
+    changes: Vec<BookFieldChange>,
+}
+
+impl BookHistoryRecord {
+    fn new(book_id: BookId, action: BookHistoryAction, changed_by: UserId) -> Self {
+        Self {
+            book_id,
+            action,
+            changed_by,
+            changes: vec![],
+        }
+    }
+
+    // 登録時は、すべての項目を変更前の値なしで記録する
+    fn created(book_id: BookId, changed_by: UserId, event: &CreateBook) -> Self {
+        let fields = BookFieldValues {
+            title: event.title.clone(),
+            author: event.author.clone(),
+            isbn: event.isbn.clone(),
+            description: event.description.clone(),
+        };
+        Self {
+            changes: fields.changes_from(None),
+            ..Self::new(book_id, BookHistoryAction::Create, changed_by)
+        }
+    }
+
+    // 完全に削除した時は、削除した時点のすべての項目を変更後の値なしで記録する
+    fn purged(book_id: BookId, changed_by: UserId, before: &BookFieldValues) -> Self {
+        Self {
+            changes: before.removed(),
+            ..Self::new(book_id, BookHistoryAction::Purge, changed_by)
+        }
+    }
+
+    // 表紙画像・タグ・配架場所のように、1 つの項目だけを変更した時に記録する
+    fn field_updated(book_id: BookId, changed_by: UserId, change: BookFieldChange) -> Self {
+        Self {
+            changes: vec![change],
+            ..Self::new(book_id, BookHistoryAction::Update, changed_by)
+        }
+    }
+
+    // 値が変わった項目がなければ None を返す
+    fn updated(
+        book_id: BookId,
+        changed_by: UserId,
+        before: &BookFieldValues,
+        after: &BookFieldValues,
+    ) -> Option<Self> {
+        let changes = after.changes_from(Some(before));
+        (!changes.is_empty()).then(|| Self {
+            changes,
+            ..Self::new(book_id, BookHistoryAction::Update, changed_by)
+        })
+    }
+}
+
+// タグの組み合わせを変更履歴の値として記録するため、ID の順に並べてカンマ区切りにする
+// タグが 1 つもない場合は None にする
+fn tag_ids_value(tag_ids: &HashSet<TagId>) -> Option<String> {
+    let mut ids = tag_ids.iter().map(TagId::to_string).collect::<Vec<_>>();
+    ids.sort();
+    (!ids.is_empty()).then(|| ids.join(","))
+}
+
+// 蔵書の変更履歴を、呼び出し元のトランザクションの中でまとめて記録する
+async fn insert_book_history(
+    tx: &mut sqlx::Transaction<'_, MySql>,
+    histories: &[BookHistoryRecord],
+) -> AppResult<()> {
+    let histories = histories
+        .iter()
+        .map(|history| (BookHistoryId::new(), history))
+        .collect::<Vec<_>>();
+
+    // プレースホルダの数が上限を超えないよう、一定の件数ごとに分けて INSERT する
+    for chunk in histories.chunks(CREATE_MANY_CHUNK_SIZE) {
+        let mut query = QueryBuilder::<MySql>::new(
+            "INSERT INTO book_history (book_history_id, book_id, action, changed_by) ",
+        );
+        query.push_values(chunk, |mut row, (history_id, history)| {
+            row.push_bind(*history_id)
+                .push_bind(history.book_id)
+                .push_bind(history.action.as_ref())
+                .push_bind(history.changed_by);
+        });
+        query
+            .build()
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+    }
+
+    let changes = histories
+        .iter()
+        .flat_map(|(history_id, history)| history.changes.iter().map(|c| (*history_id, c)))
+        .collect::<Vec<_>>();
+    for chunk in changes.chunks(CREATE_MANY_CHUNK_SIZE) {
+        let mut query = QueryBuilder::<MySql>::new(
+            "INSERT INTO book_history_changes (book_history_id, field_name, before_value, after_value) ",
+        );
+        query.push_values(chunk, |mut row, (history_id, change)| {
+            row.push_bind(*history_id)
+                .push_bind(change.field.as_ref())
+                .push_bind(change.before.as_deref())
+                .push_bind(change.after.as_deref());
+        });
+        query
+            .build()
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+    }
+
+    Ok(())
+}
+
+// ISBN の重複を確認するロック付きの読み取りは、同じ範囲への他のトランザクションの登録を待たせる
+// 蔵書が同時に登録・更新されてデッドロックになった場合は、
+// ロールバックされた側を競合として扱い、再試行を促す
+fn map_isbn_write_error(e: sqlx::Error) -> AppError {
+    match e.as_database_error() {
+        Some(db_error) if db_error.code().as_deref() == Some("40001") => {
+            AppError::Conflict("他の蔵書の登録・更新と競合しました。もう一度お試しください".into())
+        }
+        _ => AppError::SpecificOperationError(e),
+    }
+}
+
+// 値を含む文字列に一致する LIKE のパターンを作る
+// 入力された \ % _ は、ワイルドカードではなくその文字自体として一致させるためエスケープする
+fn contains_pattern(value: &str) -> String {
+    let mut pattern = String::with_capacity(value.len() + 2);
+    pattern.push('%');
+    for c in value.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+// 蔵書一覧の絞り込み条件を WHERE 句として追加する
+// 値はすべて push_bind でバインドし、SQL 文字列には埋め込まない
+fn push_book_filter(query: &mut QueryBuilder<'_, MySql>, filter: &BookListFilter) {
+    let BookListFilter {
+        keyword,
+        author,
+        isbn,
+        owner,
+        checked_out,
+        tags,
+        branch,
+        shelf,
+        deleted,
+    } = filter;
+
+    query.push(if *deleted {
+        " WHERE b.deleted_at IS NOT NULL"
+    } else {
+        " WHERE b.deleted_at IS NULL"
+    });
+    if let Some(keyword) = keyword {
+        let pattern = contains_pattern(keyword);
+        query
+            .push(" AND (b.title LIKE ")
+            .push_bind(pattern.clone())
+            .push(" ESCAPE '\\\\' OR b.author LIKE ")
+            .push_bind(pattern.clone())
+            .push(" ESCAPE '\\\\' OR b.isbn LIKE ")
+            .push_bind(pattern.clone())
+            .push(" ESCAPE '\\\\' OR b.description LIKE ")
+            .push_bind(pattern)
+            .push(" ESCAPE '\\\\')");
+    }
+    if let Some(author) = author {
+        query
+            .push(" AND b.author LIKE ")
+            .push_bind(contains_pattern(author))
+            .push(" ESCAPE '\\\\'");
+    }
+    if let Some(isbn) = isbn {
+        query.push(" AND b.isbn = ").push_bind(isbn.to_string());
+    }
+    if let Some(owner) = owner {
+        query.push(" AND b.user_id = ").push_bind(*owner);
+    }
+    if let Some(branch) = branch {
+        query
+            .push(" AND b.shelf_id IN (SELECT shelf_id FROM shelves WHERE branch_id = ")
+            .push_bind(*branch)
+            .push(")");
+    }
+    if let Some(shelf) = shelf {
+        query.push(" AND b.shelf_id = ").push_bind(*shelf);
+    }
+    // 貸出可能な所蔵資料（貸出中でも紛失中でもない所蔵資料）があるかどうかで絞り込む
+    // 所蔵資料のない蔵書は、どちらの条件にも含めない
+    const AVAILABLE_COPY_EXISTS: &str = "EXISTS(SELECT 1 FROM book_copies AS bc \
+        WHERE bc.book_id = b.book_id \
+        AND bc.copy_condition <> 'Lost' \
+        AND NOT EXISTS(SELECT 1 FROM checkouts AS c WHERE c.copy_id = bc.copy_id))";
+    match checked_out {
+        Some(true) => {
+            query
+                .push(" AND NOT ")
+                .push(AVAILABLE_COPY_EXISTS)
+                .push(" AND EXISTS(SELECT 1 FROM checkouts AS c WHERE c.book_id = b.book_id)");
+        }
+        Some(false) => {
+            query.push(" AND ").push(AVAILABLE_COPY_EXISTS);
+        }
+        None => {}
+    }
+    if let Some(tags) = tags {
+        push_book_tag_filter(query, tags);
+    }
+}
+
+// 指定した名前のタグが付いているかどうかで絞り込む
+// All の場合は、指定した名前のタグのうち蔵書に付いているものの数が、指定した名前の数と一致する蔵書に絞り込む
+fn push_book_tag_filter(query: &mut QueryBuilder<'_, MySql>, filter: &BookTagFilter) {
+    let names = filter.names.iter().collect::<BTreeSet<_>>();
+    if names.is_empty() {
+        return;
+    }
+
+    query.push(match filter.mode {
+        TagMatchMode::Any => " AND EXISTS(SELECT 1",
+        TagMatchMode::All => " AND (SELECT COUNT(*)",
+    });
+    query.push(
+        " FROM book_tags AS bt INNER JOIN tags AS t USING(tag_id) \
+        WHERE bt.book_id = b.book_id AND t.name IN (",
+    );
+    let mut separated = query.separated(", ");
+    for name in &names {
+        separated.push_bind((*name).clone());
+    }
+    separated.push_unseparated(")");
+    query.push(")");
+    if filter.mode == TagMatchMode::All {
+        query.push(" = ").push_bind(names.len() as i64);
+    }
+}
+
+fn book_sort_column(key: BookSortKey) -> &'static str {
+    match key {
+        BookSortKey::Title => "b.title",
+        BookSortKey::Author => "b.author",
+        BookSortKey::CreatedAt => "b.created_at",
+        BookSortKey::UpdatedAt => "b.updated_at",
+        BookSortKey::Isbn => "b.isbn",
+    }
+}
+
+// 蔵書一覧の並び順を ORDER BY 句として追加する
+// 並び替えのキーが同じ値の蔵書同士でもページをまたいで順序が変わらないように、
+// 最後に book_id で並べる
+fn push_book_order_by(query: &mut QueryBuilder<'_, MySql>, sort: BookListSort) {
+    let column = book_sort_column(sort.key);
+    let order = match sort.order {
+        SortOrder::Asc => "ASC",
+        SortOrder::Desc => "DESC",
+    };
+    query.push(format!(" ORDER BY {column} {order}, b.book_id {order}"));
+}
+
+// 蔵書一覧のカーソルに格納する並び替えのキーの値
+enum BookCursorKey {
+    Text(String),
+    Timestamp(DateTime<Utc>),
+}
+
+// 蔵書一覧のカーソルには、並び替えの条件・キーの値・蔵書 ID の順に値を格納する
+// 並び替えの条件が異なるリクエストでカーソルが使われた場合はエラーとする
+fn book_cursor_values(sort: BookListSort, row: &BookCursorRow) -> Vec<String> {
+    let key = match sort.key {
+        BookSortKey::Title => row.title.clone(),
+        BookSortKey::Author => row.author.clone(),
+        BookSortKey::Isbn => row.isbn.clone(),
+        BookSortKey::CreatedAt => row.created_at.timestamp_micros().to_string(),
+        BookSortKey::UpdatedAt => row.updated_at.timestamp_micros().to_string(),
+    };
+    vec![
+        format!("{:?}:{:?}", sort.key, sort.order),
+        key,
+        row.book_id.to_string(),
+    ]
+}
+
+fn decode_book_cursor(cursor: &Cursor, sort: BookListSort) -> AppResult<(BookCursorKey, BookId)> {
+    let invalid = || AppError::InvalidCursor("cursor does not match the list options".into());
+    let [sort_name, key, book_id] = cursor.values.as_slice() else {
+        return Err(invalid());
+    };
+    if *sort_name != format!("{:?}:{:?}", sort.key, sort.order) {
+        return Err(invalid());
+    }
+    let key = match sort.key {
+        BookSortKey::Title | BookSortKey::Author | BookSortKey::Isbn => {
+            BookCursorKey::Text(key.clone())
+        }
+        BookSortKey::CreatedAt | BookSortKey::UpdatedAt => key
+            .parse()
+            .ok()
+            .and_then(DateTime::from_timestamp_micros)
+            .map(BookCursorKey::Timestamp)
+            .ok_or_else(invalid)?,
+    };
+    let book_id = BookId::from_str(book_id).map_err(|_| invalid())?;
+    Ok((key, book_id))
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::repository::{
-        book::BookRepositoryImpl, checkout::CheckoutRepositoryImpl, user::UserRepositoryImpl,
+        book::BookRepositoryImpl, checkout::CheckoutRepositoryImpl,
+        location::LocationRepositoryImpl, tag::TagRepositoryImpl, user::UserRepositoryImpl,
     };
     use chrono::Utc;
     use kernel::{
         model::{
-            checkout::event::{CreateCheckout, UpdateReturned},
+            book::history::BookField,
+            checkout::{
+                event::{CreateCheckout, UpdateReturned},
+                ReturnOutcome,
+            },
             id::UserId,
+            location::{
+                event::{CreateBranch, CreateShelf, DeleteShelf},
+                BookLocation,
+            },
+            tag::event::CreateTag,
             user::event::CreateUser,
         },
-        repository::{checkout::CheckoutRepository, user::UserRepository},
+        repository::{
+            checkout::CheckoutRepository, location::LocationRepository, tag::TagRepository,
+            user::UserRepository,
+        },
     };
+    use shared::config::{CheckoutConfig, FineConfig, ReservationConfig};
     use std::str::FromStr;
 
     #[sqlx::test]
@@ -244,7 +1576,8 @@ mod tests {
             .execute(&pool)
             .await?;
         let user_repo = UserRepositoryImpl::new(ConnectionPool::new(pool.clone()));
-        let repo = BookRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Disabled);
         let user = user_repo
             .create(CreateUser {
                 name: "Test User".into(),
@@ -255,7 +1588,7 @@ mod tests {
         let book = CreateBook {
             title: "Test Title".into(),
             author: "Test Author".into(),
-            isbn: "Test ISBN".into(),
+            isbn: "978-4-06-536957-9".parse()?,
             description: "Test Description".into(),
         };
         repo.create(book, user.id).await?;
@@ -263,6 +1596,7 @@ mod tests {
         let options = BookListOptions {
             limit: 20,
             offset: 0,
+            ..Default::default()
         };
         let res = repo.find_all(options).await?;
         assert_eq!(res.items.len(), 1);
@@ -276,20 +1610,25 @@ mod tests {
             isbn,
             description,
             owner,
+            copies,
             ..
         } = res.unwrap();
         assert_eq!(id, book_id);
         assert_eq!(title, "Test Title");
         assert_eq!(author, "Test Author");
-        assert_eq!(isbn, "Test ISBN");
+        assert_eq!(isbn.as_str(), "9784065369579");
         assert_eq!(description, "Test Description");
         assert_eq!(owner.name, "Test User");
+        // 蔵書の登録時に所蔵資料が 1 冊登録される
+        assert_eq!(copies.total, 1);
+        assert_eq!(copies.available, 1);
         Ok(())
     }
 
     #[sqlx::test(fixtures("common", "book"))]
     async fn test_update_book(pool: sqlx::MySqlPool) -> anyhow::Result<()> { // ★★★ 修正: MySqlPool に変更 ★★★
-        let repo = BookRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Disabled);
         // 2. fixtures/book.sql で作成済みの書籍を取得
         let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b").unwrap();
         let book = repo.find_by_id(book_id).await?.unwrap();
@@ -297,43 +1636,315 @@ mod tests {
         assert_ne!(book.author, NEW_AUTHOR);
 
         // 3. 書籍の更新用のパラメータを作成し、更新を行う
-        let update_book = UpdateBook {
+        let version = book.version;
+        let update_book = |expected_version| UpdateBook {
             book_id: book.id,
-            title: book.title,
+            title: book.title.clone(),
             author: NEW_AUTHOR.into(), // ここが差分
-            isbn: book.isbn,
-            description: book.description,
+            isbn: book.isbn.clone(),
+            description: book.description.clone(),
             requested_user: UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c").unwrap(),
+            expected_version,
         };
-        repo.update(update_book).await.unwrap();
+        repo.update(update_book(Some(version))).await.unwrap();
 
         // 4. 更新後の書籍を取得し、期待通りに更新されていることを検証する
+        let updated = repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(updated.author, NEW_AUTHOR);
+        assert_eq!(updated.version, version + 1);
+
+        // 5. 更新前のバージョンを指定した場合は更新できない
+        let res = repo.update(update_book(Some(version))).await;
+        assert!(matches!(res, Err(AppError::PreconditionFailed(_))));
+        let res = repo
+            .delete(DeleteBook {
+                book_id,
+                requested_user: UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?,
+                expected_version: Some(version),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::PreconditionFailed(_))));
+        assert!(repo.find_by_id(book_id).await?.is_some());
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_patch_book(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Disabled);
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let owner_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
         let book = repo.find_by_id(book_id).await?.unwrap();
-        assert_eq!(book.author, NEW_AUTHOR);
+        let patch_book = |description: &str, requested_user, expected_version| PatchBook {
+            book_id,
+            title: None,
+            author: None,
+            isbn: None,
+            description: Some(description.into()),
+            requested_user,
+            expected_version,
+        };
+
+        // 指定した項目だけが更新され、それ以外の項目は変わらない
+        repo.patch(patch_book("更新後の説明", owner_id, Some(book.version)))
+            .await?;
+        let patched = repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(patched.description, "更新後の説明");
+        assert_eq!(patched.title, book.title);
+        assert_eq!(patched.author, book.author);
+        assert_eq!(patched.isbn, book.isbn);
+        assert_eq!(patched.version, book.version + 1);
+
+        // 更新前のバージョンを指定した場合は更新できない
+        let res = repo
+            .patch(patch_book("古い説明", owner_id, Some(book.version)))
+            .await;
+        assert!(matches!(res, Err(AppError::PreconditionFailed(_))));
+
+        // 所有者でなければ更新できない
+        let res = repo
+            .patch(patch_book("他人の説明", UserId::new(), None))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        let book = repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.description, "更新後の説明");
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_book_history(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Disabled);
+        let owner_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let isbn: Isbn = "9784065369579".parse()?;
+
+        repo.create(
+            CreateBook {
+                title: "タイトル".into(),
+                author: "著者".into(),
+                isbn: isbn.clone(),
+                description: "".into(),
+            },
+            owner_id,
+        )
+        .await?;
+        let book = repo
+            .find_all(BookListOptions {
+                limit: 10,
+                offset: 0,
+                filter: BookListFilter {
+                    isbn: Some(isbn.clone()),
+                    ..Default::default()
+                },
+                sort: BookListSort::default(),
+            })
+            .await?
+            .items
+            .remove(0);
+
+        // 値が変わらない項目は記録されない
+        repo.update(UpdateBook {
+            book_id: book.id,
+            title: "新しいタイトル".into(),
+            author: book.author.clone(),
+            isbn: book.isbn.clone(),
+            description: book.description.clone(),
+            requested_user: owner_id,
+            expected_version: None,
+        })
+        .await?;
+        repo.patch(PatchBook {
+            book_id: book.id,
+            title: None,
+            author: None,
+            isbn: None,
+            description: Some("説明".into()),
+            requested_user: owner_id,
+            expected_version: None,
+        })
+        .await?;
+        // 値が変わらない更新は記録されない
+        repo.patch(PatchBook {
+            book_id: book.id,
+            title: Some("新しいタイトル".into()),
+            author: None,
+            isbn: None,
+            description: None,
+            requested_user: owner_id,
+            expected_version: None,
+        })
+        .await?;
+
+        let history = repo.find_history(book.id).await?.unwrap();
+        assert_eq!(history.owned_by, Some(owner_id));
+        assert_eq!(history.entries.len(), 3);
+        assert!(history
+            .entries
+            .iter()
+            .all(|entry| entry.changed_by == Some(owner_id)));
+
+        // 登録時はすべての項目が記録される
+        let created = history
+            .entries
+            .iter()
+            .find(|entry| entry.action == BookHistoryAction::Create)
+            .unwrap();
+        assert_eq!(created.changes.len(), 4);
+        assert_eq!(
+            created.changes[2],
+            BookFieldChange {
+                field: BookField::Isbn,
+                before: None,
+                after: Some(isbn.as_str().into()),
+            }
+        );
+
+        let mut updates = history
+            .entries
+            .iter()
+            .filter(|entry| entry.action == BookHistoryAction::Update)
+            .flat_map(|entry| entry.changes.clone())
+            .collect::<Vec<_>>();
+        updates.sort_by_key(|change| change.field);
+        assert_eq!(
+            updates,
+            vec![
+                BookFieldChange {
+                    field: BookField::Title,
+                    before: Some("タイトル".into()),
+                    after: Some("新しいタイトル".into()),
+                },
+                BookFieldChange {
+                    field: BookField::Description,
+                    before: Some("".into()),
+                    after: Some("説明".into()),
+                },
+            ]
+        );
+
+        // 存在しない蔵書の変更履歴は取得できない
+        assert!(repo.find_history(BookId::new()).await?.is_none());
 
         Ok(())
     }
 
     #[sqlx::test(fixtures("common", "book"))]
     async fn test_delete_book(pool: sqlx::MySqlPool) -> anyhow::Result<()> { // ★★★ 修正: MySqlPool に変更 ★★★
-        let repo = BookRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Disabled);
         let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
 
         repo.delete(DeleteBook {
             book_id,
             requested_user: UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?,
+            expected_version: None,
         })
         .await?;
         let book = repo.find_by_id(book_id).await?;
 
         assert!(book.is_none());
 
+        // 削除済みの蔵書は、削除済みの蔵書の一覧でのみ取得できる
+        let find_ids = |deleted| {
+            let options = BookListOptions {
+                limit: 10,
+                offset: 0,
+                filter: BookListFilter {
+                    deleted,
+                    ..Default::default()
+                },
+                sort: BookListSort::default(),
+            };
+            let repo = &repo;
+            async move {
+                let res = repo.find_all(options).await?;
+                anyhow::Ok(res.items.into_iter().map(|b| b.id).collect::<Vec<_>>())
+            }
+        };
+        assert!(find_ids(false).await?.is_empty());
+        assert_eq!(find_ids(true).await?, vec![book_id]);
+
+        // 元に戻すと、再び取得できる
+        let restore_book = || RestoreBook {
+            book_id,
+            requested_user: UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c").unwrap(),
+        };
+        repo.restore(restore_book()).await?;
+        assert!(repo.find_by_id(book_id).await?.is_some());
+        assert!(find_ids(true).await?.is_empty());
+
+        // 削除済みでない蔵書は元に戻せない
+        let res = repo.restore(restore_book()).await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        // 削除と復元が変更履歴に記録される
+        let history = repo.find_history(book_id).await?.unwrap();
+        let actions = history
+            .entries
+            .iter()
+            .map(|entry| entry.action)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actions,
+            vec![BookHistoryAction::Restore, BookHistoryAction::Delete]
+        );
+
+        // 削除済みでない蔵書は完全に削除できない
+        let purge_book = || PurgeBook {
+            book_id,
+            requested_user: UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c").unwrap(),
+        };
+        let res = repo.purge(purge_book()).await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 完全に削除すると、削除済みの蔵書の一覧からも消える
+        repo.delete(DeleteBook {
+            book_id,
+            requested_user: UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?,
+            expected_version: None,
+        })
+        .await?;
+        repo.purge(purge_book()).await?;
+        assert!(repo.find_by_id(book_id).await?.is_none());
+        assert!(find_ids(true).await?.is_empty());
+
+        // 変更履歴は残り、削除した時点の値とともに完全な削除が記録される
+        let history = repo.find_history(book_id).await?.unwrap();
+        assert_eq!(history.owned_by, None);
+        let actions = history
+            .entries
+            .iter()
+            .map(|entry| entry.action)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actions,
+            vec![
+                BookHistoryAction::Purge,
+                BookHistoryAction::Delete,
+                BookHistoryAction::Restore,
+                BookHistoryAction::Delete
+            ]
+        );
+        let purged = &history.entries[0];
+        assert_eq!(purged.changes.len(), 4);
+        assert!(purged
+            .changes
+            .iter()
+            .all(|change| change.before.is_some() && change.after.is_none()));
+
+        let res = repo.purge(purge_book()).await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
         Ok(())
     }
 
     #[sqlx::test(fixtures("common", "book_list"))]
     async fn test_list_filters(pool: sqlx::MySqlPool) -> anyhow::Result<()> { // ★★★ 修正: MySqlPool に変更 ★★★
-        let repo = BookRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Disabled);
 
         const LEN: i64 = 50; // 50 is the number of records of fixtures "book_list"
 
@@ -341,6 +1952,7 @@ mod tests {
             .find_all(BookListOptions {
                 limit: 10,
                 offset: 0,
+                ..Default::default()
             })
             .await?;
         assert_eq!(res.total, LEN);
@@ -352,6 +1964,7 @@ mod tests {
             .find_all(BookListOptions {
                 limit: 10,
                 offset: 10,
+                ..Default::default()
             })
             .await?;
         assert_eq!(res.total, LEN);
@@ -363,6 +1976,7 @@ mod tests {
             .find_all(BookListOptions {
                 limit: 10,
                 offset: 100,
+                ..Default::default()
             })
             .await?;
         assert_eq!(res.total, 0); // offsetがtotalを超える場合は0になる
@@ -372,10 +1986,711 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test(fixtures("common", "book_list"))]
+    async fn test_list_search(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Disabled);
+
+        // キーワードはタイトル・著者名・ISBN・説明文のいずれかに部分一致する
+        let res = repo
+            .find_all(BookListOptions {
+                limit: 20,
+                offset: 0,
+                filter: BookListFilter {
+                    keyword: Some("title00".into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(res.total, 9);
+        assert_eq!(res.items[0].title, "title009");
+
+        let res = repo
+            .find_all(BookListOptions {
+                limit: 20,
+                offset: 0,
+                filter: BookListFilter {
+                    author: Some("author01".into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(res.total, 10);
+
+        let res = repo
+            .find_all(BookListOptions {
+                limit: 20,
+                offset: 0,
+                filter: BookListFilter {
+                    isbn: Some("9784000000253".parse()?),
+                    owner: Some(UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(res.total, 1);
+        assert_eq!(res.items[0].isbn.as_str(), "9784000000253");
+
+        // fixtures "book_list" には貸出中の蔵書はない
+        let res = repo
+            .find_all(BookListOptions {
+                limit: 20,
+                offset: 0,
+                filter: BookListFilter {
+                    checked_out: Some(true),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(res.total, 0);
+
+        let res = repo
+            .find_all(BookListOptions {
+                limit: 20,
+                offset: 0,
+                filter: BookListFilter {
+                    checked_out: Some(false),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(res.total, 50);
+
+        // % と _ はワイルドカードではなく、その文字自体として検索する
+        let owner_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        for (title, author) in [
+            ("100% Rust", "C_Programming"),
+            ("1000 Rust", "CxProgramming"),
+        ] {
+            repo.create(
+                CreateBook {
+                    title: title.into(),
+                    author: author.into(),
+                    isbn: "9784065369579".parse()?,
+                    description: "".into(),
+                },
+                owner_id,
+            )
+            .await?;
+        }
+        let count = |keyword: Option<&str>, author: Option<&str>| {
+            let options = BookListOptions {
+                limit: 20,
+                offset: 0,
+                filter: BookListFilter {
+                    keyword: keyword.map(String::from),
+                    author: author.map(String::from),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let repo = &repo;
+            async move { anyhow::Ok(repo.find_all(options).await?.total) }
+        };
+        assert_eq!(count(Some("100%"), None).await?, 1);
+        assert_eq!(count(Some("%"), None).await?, 1);
+        assert_eq!(count(Some("title_0"), None).await?, 0);
+        assert_eq!(count(None, Some("C_P")).await?, 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book_list"))]
+    async fn test_list_sort(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Disabled);
+
+        let res = repo
+            .find_all(BookListOptions {
+                limit: 10,
+                offset: 0,
+                sort: BookListSort {
+                    key: BookSortKey::Title,
+                    order: SortOrder::Asc,
+                },
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(res.items[0].title, "title001");
+        assert_eq!(res.items[9].title, "title010");
+
+        let res = repo
+            .find_all(BookListOptions {
+                limit: 10,
+                offset: 10,
+                sort: BookListSort {
+                    key: BookSortKey::Isbn,
+                    order: SortOrder::Desc,
+                },
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(res.items[0].isbn.as_str(), "9784000000406");
+
+        // 絞り込みと並び替えを組み合わせる
+        let res = repo
+            .find_all(BookListOptions {
+                limit: 10,
+                offset: 0,
+                filter: BookListFilter {
+                    author: Some("author02".into()),
+                    ..Default::default()
+                },
+                sort: BookListSort {
+                    key: BookSortKey::UpdatedAt,
+                    order: SortOrder::Asc,
+                },
+            })
+            .await?;
+        assert_eq!(res.total, 10);
+        assert_eq!(res.items[0].author, "author020");
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book_list"))]
+    async fn test_list_by_cursor(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Disabled);
+
+        // 先頭のページ（登録日時の新しい順）
+        let first = repo
+            .find_all_by_cursor(BookCursorListOptions {
+                limit: 20,
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(first.items.len(), 20);
+        assert_eq!(first.items[0].title, "title050");
+        assert!(first.prev_cursor.is_none());
+
+        // 次のページ
+        let second = repo
+            .find_all_by_cursor(BookCursorListOptions {
+                limit: 20,
+                cursor: first.next_cursor,
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(second.items[0].title, "title030");
+        assert!(second.prev_cursor.is_some());
+
+        // 最後のページ
+        let third = repo
+            .find_all_by_cursor(BookCursorListOptions {
+                limit: 20,
+                cursor: second.next_cursor,
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(third.items.len(), 10);
+        assert_eq!(third.items[9].title, "title001");
+        assert!(third.next_cursor.is_none());
+
+        // 前のページに戻る
+        let back = repo
+            .find_all_by_cursor(BookCursorListOptions {
+                limit: 20,
+                cursor: third.prev_cursor,
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(back.items[0].title, "title030");
+        assert_eq!(back.items[19].title, "title011");
+
+        // ページの取得中に蔵書が追加されても、重複や取りこぼしが起きない
+        let new_book = CreateBook {
+            title: "title051".into(),
+            author: "author051".into(),
+            isbn: "9784000000512".parse()?,
+            description: "description051".into(),
+        };
+        repo.create(
+            new_book,
+            UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?,
+        )
+        .await?;
+        let res = repo
+            .find_all_by_cursor(BookCursorListOptions {
+                limit: 20,
+                cursor: back.next_cursor,
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(res.items[0].title, "title010");
+
+        // 並び替えの条件が異なる場合はカーソルを使えない
+        let res = repo
+            .find_all_by_cursor(BookCursorListOptions {
+                limit: 20,
+                cursor: res.prev_cursor,
+                sort: BookListSort {
+                    key: BookSortKey::Title,
+                    order: SortOrder::Asc,
+                },
+                ..Default::default()
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::InvalidCursor(_))));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book_checkout"))]
+    async fn test_isbn_uniqueness(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        // fixtures/book_checkout.sql で作成済みの蔵書の所有者と ISBN
+        let owner_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let other_id = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let isbn: Isbn = "978-4-7980-6170-2".parse()?;
+        let new_book = || CreateBook {
+            title: "Test Title".into(),
+            author: "Test Author".into(),
+            isbn: isbn.clone(),
+            description: "Test Description".into(),
+        };
+
+        // 全体で重複を禁止する場合は、所有者が異なっていても登録できない
+        let repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Global);
+        let res = repo.create(new_book(), other_id).await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        // 蔵書自身の ISBN のままであれば更新できる
+        let book = repo.find_by_id(book_id).await?.unwrap();
+        repo.update(UpdateBook {
+            book_id,
+            title: book.title,
+            author: book.author,
+            isbn: book.isbn,
+            description: book.description,
+            requested_user: owner_id,
+            expected_version: None,
+        })
+        .await?;
+
+        // 所有者ごとに重複を禁止する場合は、所有者が異なれば登録できる
+        let repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::PerOwner);
+        let res = repo.create(new_book(), owner_id).await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+        repo.create(new_book(), other_id).await?;
+        let res = repo.create(new_book(), other_id).await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        // 重複を許容する場合は何件でも登録できる
+        let repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Disabled);
+        repo.create(new_book(), owner_id).await?;
+
+        let res = repo
+            .find_all(BookListOptions {
+                limit: 20,
+                offset: 0,
+                filter: BookListFilter {
+                    isbn: Some(isbn.clone()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(res.total, 3);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book_checkout"))]
+    async fn test_create_many(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        // fixtures/book_checkout.sql で作成済みの蔵書（ISBN 978-4-7980-6170-2）の所有者
+        let owner_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let new_book = |title: &str, isbn: &str| -> anyhow::Result<CreateBook> {
+            Ok(CreateBook {
+                title: title.into(),
+                author: "Test Author".into(),
+                isbn: isbn.parse()?,
+                description: "".into(),
+            })
+        };
+        let all = || BookListOptions {
+            limit: 20,
+            offset: 0,
+            ..Default::default()
+        };
+        let repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::PerOwner);
+        let before = repo.find_all(all()).await?.total;
+
+        // 登録済みの蔵書と ISBN が重複するものや、一括登録する蔵書の間で ISBN が重複するものは調べられる
+        let books = vec![
+            new_book("Book 1", "978-4-06-536957-9")?,
+            new_book("Book 2", "978-4-7980-6170-2")?,
+            new_book("Book 3", "4-06-536957-6")?,
+            new_book("Book 4", "978-4-297-14190-5")?,
+        ];
+        let expected = vec![
+            IsbnConflict {
+                index: 1,
+                isbn: "978-4-7980-6170-2".parse()?,
+                kind: IsbnConflictKind::Registered,
+            },
+            IsbnConflict {
+                index: 2,
+                isbn: "978-4-06-536957-9".parse()?,
+                kind: IsbnConflictKind::Batch,
+            },
+        ];
+        let isbns = books.iter().map(|b| b.isbn.clone()).collect::<Vec<_>>();
+        let conflicts = repo.find_isbn_conflicts(&isbns, owner_id).await?;
+        assert_eq!(conflicts, expected);
+        assert_eq!(repo.find_all(all()).await?.total, before);
+
+        // 重複するものは登録せず、残りの蔵書を登録する
+        // 蔵書ごとに所蔵資料が 1 冊登録される
+        let conflicts = repo.create_many(books, owner_id).await?;
+        assert_eq!(conflicts, expected);
+        assert_eq!(repo.find_all(all()).await?.total, before + 2);
+        let res = repo
+            .find_all(BookListOptions {
+                limit: 20,
+                offset: 0,
+                filter: BookListFilter {
+                    isbn: Some("978-4-06-536957-9".parse()?),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(res.items.len(), 1);
+        assert_eq!(res.items[0].title, "Book 1");
+        assert_eq!(res.items[0].copies.total, 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book_checkout"))]
+    async fn test_update_cover(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        // fixtures/book_checkout.sql で作成済みの蔵書と、その所有者・所有者以外のユーザー
+        let owner_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let other_id = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Disabled);
+
+        // 表紙画像を登録するまでは None
+        let book = repo.find_by_id(book_id).await?.unwrap();
+        assert!(book.cover.is_none());
+
+        // 所有者は表紙画像を登録できる
+        repo.update_cover(UpdateBookCover::new(book_id, "image/png".into(), owner_id))
+            .await?;
+        let cover = repo.find_by_id(book_id).await?.unwrap().cover.unwrap();
+        assert_eq!(cover.content_type, "image/png");
+
+        // 所有者以外は差し替えられない
+        let res = repo
+            .update_cover(UpdateBookCover::new(book_id, "image/jpeg".into(), other_id))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        // 管理者であれば所有者以外でも差し替えられる
+        let mut event = UpdateBookCover::new(book_id, "image/jpeg".into(), other_id);
+        event.by_admin = true;
+        repo.update_cover(event).await?;
+        let updated = repo.find_by_id(book_id).await?.unwrap().cover.unwrap();
+        assert_eq!(updated.content_type, "image/jpeg");
+        assert!(updated.updated_at >= cover.updated_at);
+
+        // 差し替えた利用者と変更前後の形式が変更履歴に記録される
+        let history = repo.find_history(book_id).await?.unwrap();
+        let changes = history
+            .entries
+            .iter()
+            .map(|entry| (entry.changed_by, entry.changes.clone()))
+            .collect::<Vec<_>>();
+        let cover_change = |before: Option<&str>, after: &str| {
+            vec![BookFieldChange {
+                field: BookField::Cover,
+                before: before.map(String::from),
+                after: Some(after.into()),
+            }]
+        };
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    Some(other_id),
+                    cover_change(Some("image/png"), "image/jpeg")
+                ),
+                (Some(owner_id), cover_change(None, "image/png")),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book_list"))]
+    async fn test_update_tags(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        // fixtures/book_list.sql で作成済みの蔵書（title001・title002）と、その所有者
+        let owner_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book1 = BookId::from_str("51E949EE-1B64-4CD7-A49A-7A57BDADE4DF")?;
+        let book2 = BookId::from_str("EB18DE8F-1947-4610-AA4B-4384C3ED41F1")?;
+        let repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Disabled);
+        let tag_repo = TagRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let mut tags = vec![];
+        for name in ["Rust", "データベース", "設計"] {
+            tags.push(tag_repo.create(CreateTag { name: name.into() }).await?);
+        }
+        let [rust, db, design] = [&tags[0], &tags[1], &tags[2]];
+
+        repo.update_tags(UpdateBookTags::new(book1, vec![rust.id, db.id], owner_id))
+            .await?;
+        repo.update_tags(UpdateBookTags::new(book2, vec![rust.id], owner_id))
+            .await?;
+        let book = repo.find_by_id(book1).await?.unwrap();
+        assert_eq!(book.tags, vec![rust.clone(), db.clone()]);
+
+        // 同じタグの組み合わせに付け替えた場合は、変更履歴に記録しない
+        repo.update_tags(UpdateBookTags::new(book1, vec![db.id, rust.id], owner_id))
+            .await?;
+        let history = repo.find_history(book1).await?.unwrap();
+        assert_eq!(history.entries.len(), 1);
+        let mut tag_ids = [rust.id.to_string(), db.id.to_string()];
+        tag_ids.sort();
+        assert_eq!(
+            history.entries[0].changes,
+            vec![BookFieldChange {
+                field: BookField::Tags,
+                before: None,
+                after: Some(tag_ids.join(",")),
+            }]
+        );
+
+        // 存在しないタグが含まれている場合は、タグを変更しない
+        let res = repo
+            .update_tags(UpdateBookTags::new(
+                book1,
+                vec![design.id, TagId::new()],
+                owner_id,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        assert_eq!(repo.find_by_id(book1).await?.unwrap().tags.len(), 2);
+
+        // 所有者以外は付け替えられない
+        let res = repo
+            .update_tags(UpdateBookTags::new(book1, vec![], UserId::new()))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        // いずれかのタグが付いている蔵書と、すべてのタグが付いている蔵書で絞り込む
+        let find_titles = |names: &[&str], mode| {
+            let options = BookListOptions {
+                limit: 10,
+                offset: 0,
+                filter: BookListFilter {
+                    tags: Some(BookTagFilter {
+                        names: names.iter().map(|n| n.to_string()).collect(),
+                        mode,
+                    }),
+                    ..Default::default()
+                },
+                sort: BookListSort {
+                    key: BookSortKey::Title,
+                    order: SortOrder::Asc,
+                },
+            };
+            let repo = &repo;
+            async move {
+                let res = repo.find_all(options).await?;
+                anyhow::Ok(res.items.into_iter().map(|b| b.title).collect::<Vec<_>>())
+            }
+        };
+        assert_eq!(
+            find_titles(&["Rust"], TagMatchMode::Any).await?,
+            vec!["title001", "title002"]
+        );
+        assert_eq!(
+            find_titles(&["データベース", "設計"], TagMatchMode::Any).await?,
+            vec!["title001"]
+        );
+        assert_eq!(
+            find_titles(&["Rust", "データベース"], TagMatchMode::All).await?,
+            vec!["title001"]
+        );
+        assert!(find_titles(&["Rust", "設計"], TagMatchMode::All)
+            .await?
+            .is_empty());
+
+        // タグを外すと、一覧の蔵書にもタグが含まれなくなる
+        repo.update_tags(UpdateBookTags::new(book1, vec![], owner_id))
+            .await?;
+        let books = repo.find_by_ids(&[book1, book2]).await?;
+        assert!(books[0].tags.is_empty());
+        assert_eq!(books[1].tags, vec![rust.clone()]);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book_list"))]
+    async fn test_update_location(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        // fixtures/book_list.sql で作成済みの蔵書（title001・title002）と、その所有者
+        let owner_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book1 = BookId::from_str("51E949EE-1B64-4CD7-A49A-7A57BDADE4DF")?;
+        let book2 = BookId::from_str("EB18DE8F-1947-4610-AA4B-4384C3ED41F1")?;
+        let repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Disabled);
+        let location_repo = LocationRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let main = location_repo
+            .create_branch(CreateBranch {
+                name: "本館".into(),
+            })
+            .await?;
+        let annex = location_repo
+            .create_branch(CreateBranch {
+                name: "別館".into(),
+            })
+            .await?;
+        let main_shelf = location_repo
+            .create_shelf(CreateShelf {
+                branch_id: main.id,
+                name: "A-1".into(),
+            })
+            .await?;
+        let annex_shelf = location_repo
+            .create_shelf(CreateShelf {
+                branch_id: annex.id,
+                name: "B-1".into(),
+            })
+            .await?;
+
+        // 配置前の蔵書には所在がない
+        assert!(repo.find_by_id(book1).await?.unwrap().location.is_none());
+
+        repo.update_location(UpdateBookLocation::new(
+            book1,
+            Some(main_shelf.id),
+            owner_id,
+        ))
+        .await?;
+        repo.update_location(UpdateBookLocation::new(
+            book2,
+            Some(annex_shelf.id),
+            owner_id,
+        ))
+        .await?;
+        let book = repo.find_by_id(book1).await?.unwrap();
+        assert_eq!(
+            book.location,
+            Some(BookLocation {
+                branch: main.clone(),
+                shelf: main_shelf.clone(),
+            })
+        );
+
+        // 配置した書架が変更履歴に記録される
+        let history = repo.find_history(book1).await?.unwrap();
+        assert_eq!(history.entries.len(), 1);
+        assert_eq!(history.entries[0].changed_by, Some(owner_id));
+        assert_eq!(
+            history.entries[0].changes,
+            vec![BookFieldChange {
+                field: BookField::Shelf,
+                before: None,
+                after: Some(main_shelf.id.to_string()),
+            }]
+        );
+
+        // 存在しない書架には配置できない
+        let res = repo
+            .update_location(UpdateBookLocation::new(
+                book1,
+                Some(ShelfId::new()),
+                owner_id,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 所有者以外は配置を変えられない
+        let res = repo
+            .update_location(UpdateBookLocation::new(book1, None, UserId::new()))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        // 館・書架で絞り込む
+        let find_titles = |branch, shelf| {
+            let options = BookListOptions {
+                limit: 10,
+                offset: 0,
+                filter: BookListFilter {
+                    branch,
+                    shelf,
+                    ..Default::default()
+                },
+                sort: BookListSort {
+                    key: BookSortKey::Title,
+                    order: SortOrder::Asc,
+                },
+            };
+            let repo = &repo;
+            async move {
+                let res = repo.find_all(options).await?;
+                anyhow::Ok(res.items.into_iter().map(|b| b.title).collect::<Vec<_>>())
+            }
+        };
+        assert_eq!(find_titles(Some(main.id), None).await?, vec!["title001"]);
+        assert_eq!(
+            find_titles(None, Some(annex_shelf.id)).await?,
+            vec!["title002"]
+        );
+        assert!(find_titles(Some(main.id), Some(annex_shelf.id))
+            .await?
+            .is_empty());
+
+        // 書架を削除すると、配置されていた蔵書は所在なしになる
+        location_repo
+            .delete_shelf(DeleteShelf {
+                shelf_id: main_shelf.id,
+                branch_id: main.id,
+            })
+            .await?;
+        assert!(repo.find_by_id(book1).await?.unwrap().location.is_none());
+
+        // 所在を外す
+        repo.update_location(UpdateBookLocation::new(book2, None, owner_id))
+            .await?;
+        assert!(repo.find_by_id(book2).await?.unwrap().location.is_none());
+        let history = repo.find_history(book2).await?.unwrap();
+        assert_eq!(
+            history.entries[0].changes,
+            vec![BookFieldChange {
+                field: BookField::Shelf,
+                before: Some(annex_shelf.id.to_string()),
+                after: None,
+            }]
+        );
+
+        Ok(())
+    }
+
     #[sqlx::test(fixtures("common", "book_checkout"))]
     async fn test_book_checkout(pool: sqlx::MySqlPool) -> anyhow::Result<()> { // ★★★ 修正: MySqlPool に変更 ★★★
-        let book_repo = BookRepositoryImpl::new(ConnectionPool::new(pool.clone()));
-        let checkout_repo = CheckoutRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let book_repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Disabled);
+        let checkout_repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            CheckoutConfig::default(),
+            ReservationConfig {
+                pickup_window_hours: 72,
+            },
+            FineConfig::default(),
+        );
 
         // 事前登録したユーザーのID（fixtures/book_checkout.sql参照）
         let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b").unwrap();
@@ -385,30 +2700,42 @@ mod tests {
             .find_all(BookListOptions {
                 limit: 20,
                 offset: 0,
+                ..Default::default()
             })
             .await?
             .into_inner()
             .pop()
             .unwrap();
 
-        // 初期の貸し出し状態がNoneであることを確認
-        assert!(book.checkout.is_none());
+        // 初期の貸し出し状態が空であることを確認
+        assert!(book.checkouts.is_empty());
+        assert_eq!(
+            book.copies,
+            BookCopyCounts {
+                total: 1,
+                available: 1
+            }
+        );
 
         // 1回目の貸し出し（user_id1）の蔵書の戻り値のテスト
         {
             checkout_repo
                 .create(CreateCheckout {
                     book_id: book.id,
+                    copy_id: None,
                     checked_out_by: user_id1,
                     checked_out_at: Utc::now(),
+                    processed_by: None,
+                    branch_id: None,
                 })
                 .await?;
 
             // 貸し出しがある状態での蔵書の戻り値
-            // -> Book#checkoutが存在し、貸し出し時に指定したユーザーIDになっている
-            let book_co = book_repo.find_by_id(book.id).await?.unwrap();
-            assert!(book_co.checkout.is_some());
-            let co = book_co.checkout.unwrap();
+            // -> Book#checkoutsが存在し、貸し出し時に指定したユーザーIDになっている
+            let mut book_co = book_repo.find_by_id(book.id).await?.unwrap();
+            assert_eq!(book_co.checkouts.len(), 1);
+            assert_eq!(book_co.copies.available, 0);
+            let co = book_co.checkouts.pop().unwrap();
             assert_eq!(co.checked_out_by.id, user_id1);
 
             // 返却を実行
@@ -418,13 +2745,18 @@ mod tests {
                     book_id: book_co.id,
                     returned_by: user_id1,
                     returned_at: Utc::now(),
+                    outcome: ReturnOutcome::Ok,
+                    note: None,
+                    by_admin: false,
+                    branch_id: None,
                 })
                 .await?;
 
             // 返却後の蔵書の戻り値
-            // -> Book#checkoutが存在し、貸し出し時に指定したユーザーIDになっている
+            // -> Book#checkoutsが空になり、所蔵資料が貸出可能に戻っている
             let book_re = book_repo.find_by_id(book.id).await?.unwrap();
-            assert!(book_re.checkout.is_none());
+            assert!(book_re.checkouts.is_empty());
+            assert_eq!(book_re.copies.available, 1);
         }
 
         // 2回目の貸し出し（user_id2）の蔵書の戻り値のテスト
@@ -433,16 +2765,20 @@ mod tests {
             checkout_repo
                 .create(CreateCheckout {
                     book_id: book.id,
+                    copy_id: None,
                     checked_out_by: user_id2,
                     checked_out_at: Utc::now(),
+                    processed_by: None,
+                    branch_id: None,
                 })
                 .await?;
 
             // 貸し出しがある状態での蔵書の戻り値
-            // -> Book#checkoutが存在し、貸し出し時に指定したユーザーIDになっている
-            let book_co = book_repo.find_by_id(book.id).await?.unwrap();
-            assert!(book_co.checkout.is_some());
-            let co = book_co.checkout.unwrap();
+            // -> Book#checkoutsが存在し、貸し出し時に指定したユーザーIDになっている
+            let mut book_co = book_repo.find_by_id(book.id).await?.unwrap();
+            assert_eq!(book_co.checkouts.len(), 1);
+            assert_eq!(book_co.copies.available, 0);
+            let co = book_co.checkouts.pop().unwrap();
             assert_eq!(co.checked_out_by.id, user_id2);
 
             // 返却を実行
@@ -452,13 +2788,18 @@ mod tests {
                     book_id: book_co.id,
                     returned_by: user_id2,
                     returned_at: Utc::now(),
+                    outcome: ReturnOutcome::Ok,
+                    note: None,
+                    by_admin: false,
+                    branch_id: None,
                 })
                 .await?;
 
             // 返却後の蔵書の戻り値
-            // -> Book#checkoutが存在し、貸し出し時に指定したユーザーIDになっている
+            // -> Book#checkoutsが空になり、所蔵資料が貸出可能に戻っている
             let book_re = book_repo.find_by_id(book.id).await?.unwrap();
-            assert!(book_re.checkout.is_none());
+            assert!(book_re.checkouts.is_empty());
+            assert_eq!(book_re.copies.available, 1);
         }
 
         Ok(())
@@ -1,21 +1,42 @@
 use crate::database::{
-    model::checkout::{CheckoutRow, CheckoutStateRow, ReturnedCheckoutRow},
+    cursor::{
+        paginate_by_cursor, push_keyset_condition, push_keyset_order_by, Cursor, CursorDirection,
+    },
+    model::checkout::{
+        CheckoutHistoryRow, CheckoutRow, CheckoutStateRow, LoanerStateRow, RenewalStateRow,
+    },
     ConnectionPool,
 };
+use crate::repository::fine::{create_charge, fetch_fine_balance};
+use crate::repository::reservation::assign_holds;
 use async_trait::async_trait;
 
+use chrono::{DateTime, Duration, Utc};
 use derive_new::new;
 use kernel::model::checkout::{
-    event::{CreateCheckout, UpdateReturned},
-    Checkout,
+    event::{CreateCheckout, RenewCheckout, UpdateReturned},
+    policy, Checkout, CheckoutHistoryOptions, ReturnOutcome,
 };
-use kernel::model::id::{BookId, CheckoutId, UserId};
+use kernel::model::copy::CopyCondition;
+use kernel::model::fine::{self, FineEntryKind};
+use kernel::model::id::{BookId, BranchId, CheckoutId, CopyId, UserId};
+use kernel::model::list::{CursorListOptions, CursorPaginatedList, SortOrder};
+use kernel::model::role::Role;
 use kernel::repository::checkout::CheckoutRepository;
-use shared::error::{AppError, AppResult};
+use shared::config::{CheckoutConfig, FineConfig, LoanPolicy, ReservationConfig};
+use shared::error::{AppError, AppResult, LoanPolicyViolation};
+use sqlx::QueryBuilder;
+use std::str::FromStr;
 
 #[derive(new)]
 pub struct CheckoutRepositoryImpl {
     db: ConnectionPool,
+    // ロールごとの貸出ポリシーの設定
+    config: CheckoutConfig,
+    // 予約の取り置き期間の設定
+    reservation_config: ReservationConfig,
+    // 延滞料金の設定
+    fine_config: FineConfig,
 }
 
 #[async_trait]
@@ -29,65 +50,149 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
 
         // 事前のチェックとして、以下を調べる。
         // - 指定の蔵書 ID をもつ蔵書が存在するか
-        // - 存在した場合、この蔵書は貸出中ではないか
+        // - 存在した場合、貸出中ではない所蔵資料があるか（紛失した所蔵資料は対象外）
+        //   （所蔵資料の指定がある場合は、その所蔵資料が貸出中ではないか）
         //
-        // 上記の両方が Yes だった場合、このブロック以降の処理に進む
-        {
+        // 上記の両方が Yes だった場合、貸し出す所蔵資料を決めてこのブロック以降の処理に進む
+        let (copy_id, policy) = {
             let res = sqlx::query_as!(
                 CheckoutStateRow,
                 r#"
                     SELECT
                     b.book_id,
+                    bc.copy_id AS "copy_id?: CopyId",
                     c.checkout_id AS "checkout_id?: CheckoutId",
                     NULL AS "user_id?: UserId"
                     FROM books AS b
-                    LEFT OUTER JOIN checkouts AS c USING(book_id)
-                    WHERE book_id = ?; /* ★修正: $1 を ? に置換 */
+                    LEFT OUTER JOIN book_copies AS bc
+                    ON bc.book_id = b.book_id AND bc.copy_condition <> 'Lost'
+                    LEFT OUTER JOIN checkouts AS c ON c.copy_id = bc.copy_id
+                    WHERE b.book_id = ?
+                    AND b.deleted_at IS NULL
+                    ORDER BY bc.acquired_on ASC, bc.copy_id ASC
                 "#,
                 event.book_id as _
             )
-            .fetch_optional(&mut *tx)
+            .fetch_all(&mut *tx)
             .await
             .map_err(AppError::SpecificOperationError)?;
 
-            match res {
-                // 指定した書籍が存在しない場合
-                None => {
-                    return Err(AppError::EntityNotFound(format!(
-                        " 書籍（{}）が見つかりませんでした。",
-                        event.book_id
-                    )))
-                }
-                // 指定した書籍が存在するが貸出中の場合
-                Some(CheckoutStateRow {
-                    checkout_id: Some(_),
-                    ..
-                }) => {
-                    return Err(AppError::UnprocessableEntity(format!(
-                        " 書籍（{}）に対する貸出が既に存在します。",
-                        event.book_id
-                    )))
-                }
-                _ => {} // それ以外は処理続行
+            // 指定した書籍が存在しない場合
+            if res.is_empty() {
+                return Err(AppError::EntityNotFound(format!(
+                    " 書籍（{}）が見つかりませんでした。",
+                    event.book_id
+                )));
+            }
+
+            // 借りるユーザーのロールの貸出ポリシーに違反していないかを調べる
+            let (policy, state) = self
+                .fetch_loan_policy(&mut tx, event.checked_out_by, event.checked_out_at)
+                .await?;
+            policy::check_checkout(&policy, state.loans, state.overdue_loans)
+                .map_err(AppError::LoanPolicyViolation)?;
+
+            // 未払いの延滞料金が上限を超えている場合は貸し出さない
+            let balance = fetch_fine_balance(&mut tx, event.checked_out_by).await?;
+            if balance > self.fine_config.max_outstanding_balance {
+                return Err(AppError::LoanPolicyViolation(
+                    LoanPolicyViolation::OutstandingFines {
+                        balance,
+                        limit: self.fine_config.max_outstanding_balance,
+                    },
+                ));
             }
-        }
+
+            // 予約のために取り置き中の所蔵資料は、取り置かれたユーザー以外には貸し出さない
+            // 取り置かれていない所蔵資料が残っていなければ貸し出せない
+            let hold = assign_holds(
+                &mut tx,
+                event.book_id,
+                event.checked_out_at,
+                &self.reservation_config,
+            )
+            .await?;
+            let held_for_me = sqlx::query_scalar!(
+                r#"
+                    SELECT EXISTS(
+                        SELECT 1 FROM reservations
+                        WHERE book_id = ? AND user_id = ? AND ready_until IS NOT NULL
+                    ) AS "held_for_me: bool"
+                "#,
+                event.book_id as _,
+                event.checked_out_by as _
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+            if !held_for_me && hold.available_copies <= hold.ready_reservations {
+                return Err(AppError::UnprocessableEntity(format!(
+                    " 書籍（{}）は予約者のために取り置き中のため貸し出せません。",
+                    event.book_id
+                )));
+            }
+
+            let copy_id = match event.copy_id {
+                Some(copy_id) => match res.iter().find(|r| r.copy_id == Some(copy_id)) {
+                    // 指定した所蔵資料がこの書籍のものではない場合
+                    None => {
+                        return Err(AppError::EntityNotFound(format!(
+                            " 書籍（{}）の所蔵資料（{}）が見つかりませんでした。",
+                            event.book_id, copy_id
+                        )))
+                    }
+                    // 指定した所蔵資料が貸出中の場合
+                    Some(CheckoutStateRow {
+                        checkout_id: Some(_),
+                        ..
+                    }) => {
+                        return Err(AppError::UnprocessableEntity(format!(
+                            " 所蔵資料（{}）に対する貸出が既に存在します。",
+                            copy_id
+                        )))
+                    }
+                    Some(_) => copy_id,
+                },
+                // 所蔵資料の指定がない場合は、受け入れ日の古いものから貸し出す
+                None => res
+                    .iter()
+                    .filter(|r| r.checkout_id.is_none())
+                    .find_map(|r| r.copy_id)
+                    .ok_or_else(|| {
+                        AppError::UnprocessableEntity(format!(
+                            " 書籍（{}）に貸出可能な所蔵資料がありません。",
+                            event.book_id
+                        ))
+                    })?,
+            };
+            (copy_id, policy)
+        };
 
         // 貸し出し処理を行う、すなわち checkouts テーブルにレコードを追加する
+        // 返却期限は貸出日に、借りるユーザーのロールの貸出期間を足した日時とする
         let checkout_id = CheckoutId::new();
+        let due_at = event.checked_out_at + Duration::days(policy.loan_period_days);
         let res = sqlx::query!(
             r#"
                 INSERT INTO checkouts
-                (checkout_id, book_id, user_id, checked_out_at)
-                VALUES (?, ?, ?, ?); /* ★修正: $1, $2, $3, $4 を ?, ?, ?, ? に置換 */
+                (
+                    checkout_id, book_id, copy_id, user_id, checkout_processed_by,
+                    checkout_branch_id, checked_out_at, due_at
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?);
             "#,
             checkout_id as _,
             event.book_id as _,
+            copy_id as _,
             event.checked_out_by as _,
+            event.processed_by.unwrap_or(event.checked_out_by) as _,
+            event.branch_id as _,
             event.checked_out_at,
+            due_at,
         )
         .execute(&mut *tx)
         .await
-        .map_err(AppError::SpecificOperationError)?;
+        .map_err(map_branch_not_found)?;
 
         if res.rows_affected() < 1 {
             return Err(AppError::NoRowsAffectedError(
@@ -95,6 +200,18 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
             ));
         }
 
+        // 予約していた蔵書を借りた場合は、その予約を完了として削除する
+        sqlx::query!(
+            r#"
+                DELETE FROM reservations WHERE book_id = ? AND user_id = ?
+            "#,
+            event.book_id as _,
+            event.checked_out_by as _,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
         tx.commit().await.map_err(AppError::TransactionError)?;
 
         Ok(())
@@ -110,25 +227,28 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
         // 返却操作時は事前のチェックとして、以下を調べる。
         // - 指定の蔵書 ID をもつ蔵書が存在するか
         // - 存在した場合、
-        // - この蔵書は貸出中であり
-        // - かつ、借りたユーザーが指定のユーザーと同じか
+        // - 指定の貸出 ID の貸出がこの蔵書の所蔵資料に対して存在し
+        // - かつ、借りたユーザーが指定のユーザーと同じか（管理者による返却の場合は問わない）
         //
-        // 上記の両方が Yes だった場合、このブロック以降の処理に進む
+        // 上記の両方が Yes だった場合、借りたユーザーを取り出してこのブロック以降の処理に進む
         // なお、ブロックの使用は意図的である。こうすることで、
         // res 変数がシャドーイングで上書きされるのを防ぐなどの
         // メリットがある。
-        {
+        let borrower = {
             let res = sqlx::query_as!(
                 CheckoutStateRow,
                 r#"
                     SELECT
                     b.book_id,
+                    c.copy_id AS "copy_id?: CopyId",
                     c.checkout_id AS "checkout_id?: CheckoutId",
                     c.user_id AS "user_id?: UserId"
                     FROM books AS b
-                    LEFT OUTER JOIN checkouts AS c USING(book_id)
-                    WHERE book_id = ?; /* ★修正: $1 を ? に置換 */
+                    LEFT OUTER JOIN checkouts AS c
+                    ON c.book_id = b.book_id AND c.checkout_id = ?
+                    WHERE b.book_id = ?;
                 "#,
+                event.checkout_id as _,
                 event.book_id as _,
             )
             .fetch_optional(&mut *tx)
@@ -143,39 +263,60 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
                         event.book_id
                     )))
                 }
-                // 指定した書籍が貸出中であり、貸出 ID または借りたユーザーが異なる場合
                 Some(CheckoutStateRow {
-                    checkout_id: Some(c),
-                    user_id: Some(u),
+                    user_id: Some(user_id),
                     ..
-                }) if (c, u) != (event.checkout_id, event.returned_by) => {
+                }) if event.by_admin || user_id == event.returned_by => user_id,
+                // 指定した貸出が存在しない、または借りたユーザーが異なる場合
+                Some(_) => {
                     return Err(AppError::UnprocessableEntity(format!(
                         " 指定の貸出（ID（{}）, ユーザー（{}）, 書籍（{}））は返却できません。",
                         event.checkout_id, event.returned_by, event.book_id
                     )))
                 }
-                _ => {} // それ以外は処理続行
             }
-        }
+        };
+
+        // 延滞料金を計算するために、返却前に返却期限を取り出しておく
+        let due_at = sqlx::query_scalar!(
+            r#"
+                SELECT due_at FROM checkouts WHERE checkout_id = ?
+            "#,
+            event.checkout_id as _,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
 
         // データベース上の返却操作として、
         // checkouts テーブルにある該当貸出 ID のレコードを、
-        // returned_at を追加して returned_checkouts テーブルに INSERT する
+        // returned_at と返却時の状態を追加して returned_checkouts テーブルに INSERT する
         let res = sqlx::query!(
             r#"
                 INSERT INTO returned_checkouts
-                (checkout_id, book_id, user_id, checked_out_at, returned_at)
-                SELECT checkout_id, book_id, user_id, checked_out_at, ? /* ★修正: $2 を ? に置換 */
+                (
+                    checkout_id, book_id, copy_id, user_id, checkout_processed_by,
+                    checkout_branch_id, checked_out_at, due_at, renewal_count, returned_at,
+                    return_outcome, return_note, return_processed_by, return_branch_id
+                )
+                SELECT
+                checkout_id, book_id, copy_id, user_id, checkout_processed_by,
+                checkout_branch_id, checked_out_at, due_at, renewal_count, ?,
+                ?, ?, ?, ?
                 FROM checkouts
-                WHERE checkout_id = ? /* ★修正: $1 を ? に置換 */
+                WHERE checkout_id = ?
                 ;
             "#,
-            event.checkout_id as _,
             event.returned_at,
+            event.outcome.as_ref(),
+            event.note,
+            event.returned_by as _,
+            event.branch_id as _,
+            event.checkout_id as _,
         )
         .execute(&mut *tx)
         .await
-        .map_err(AppError::SpecificOperationError)?;
+        .map_err(map_branch_not_found)?;
 
         if res.rows_affected() < 1 {
             return Err(AppError::NoRowsAffectedError(
@@ -183,6 +324,29 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
             ));
         }
 
+        // 破損・紛失の場合は所蔵資料の状態を更新する
+        // 紛失した所蔵資料は、以降の貸出や予約の取り置きの対象にならない
+        let condition = match event.outcome {
+            ReturnOutcome::Ok => None,
+            ReturnOutcome::Damaged => Some(CopyCondition::Damaged),
+            ReturnOutcome::Lost => Some(CopyCondition::Lost),
+        };
+        if let Some(condition) = condition {
+            sqlx::query!(
+                r#"
+                    UPDATE book_copies AS bc
+                    INNER JOIN checkouts AS c ON c.copy_id = bc.copy_id
+                    SET bc.copy_condition = ?
+                    WHERE c.checkout_id = ?
+                "#,
+                condition.as_ref(),
+                event.checkout_id as _,
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+        }
+
         // 上記処理が成功したら checkouts テーブルから該当貸出 ID のレコードを削除する
         let res = sqlx::query!(
             r#"
@@ -200,37 +364,168 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
             ));
         }
 
+        // 返却期限を過ぎていた場合は、借りたユーザーの延滞料金を台帳に記録する
+        let fine = fine::overdue_fine(&self.fine_config, due_at, event.returned_at);
+        if fine > 0 {
+            create_charge(
+                &mut tx,
+                borrower,
+                event.checkout_id,
+                FineEntryKind::Fine,
+                fine,
+                None,
+                event.returned_at,
+            )
+            .await?;
+        }
+        // 紛失した場合は、弁償金を借りたユーザーに請求する
+        if event.outcome == ReturnOutcome::Lost && self.fine_config.replacement_charge > 0 {
+            create_charge(
+                &mut tx,
+                borrower,
+                event.checkout_id,
+                FineEntryKind::Replacement,
+                self.fine_config.replacement_charge,
+                event.note.as_deref(),
+                event.returned_at,
+            )
+            .await?;
+        }
+
+        // 返却された所蔵資料を、予約の先頭のユーザーのために取り置く
+        assign_holds(
+            &mut tx,
+            event.book_id,
+            event.returned_at,
+            &self.reservation_config,
+        )
+        .await?;
+
         tx.commit().await.map_err(AppError::TransactionError)?;
 
         Ok(())
     }
 
-    // すべての未返却の貸出情報を取得する
-    async fn find_unreturned_all(&self) -> AppResult<Vec<Checkout>> {
-        // checkouts テーブルにあるレコードを全件抽出する
-        // books テーブルと INNER JOIN し、蔵書の情報も一緒に抽出する
-        // 出力するレコードは、貸出日の古い順に並べる
-        sqlx::query_as!(
-            CheckoutRow,
+    // 貸出の延長操作を行う
+    async fn renew(&self, event: RenewCheckout) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // トランザクション分離レベルを SERIALIZABLE に設定する
+        self.set_transaction_serializable(&mut tx).await?;
+
+        // 延長操作時は事前のチェックとして、以下を調べる。
+        // - 指定の蔵書 ID をもつ蔵書が存在するか
+        // - 存在した場合、
+        // - 指定の貸出 ID の貸出がこの蔵書に対して存在し
+        // - かつ、借りたユーザーが指定のユーザーと同じか
+        // - 他のユーザーがこの蔵書を予約していないか
+        //
+        // すべてが Yes だった場合、延長回数を取り出してこのブロック以降の処理に進む
+        let renewal_count = {
+            let res = sqlx::query_as!(
+                RenewalStateRow,
+                r#"
+                    SELECT
+                    b.book_id,
+                    c.checkout_id AS "checkout_id?: CheckoutId",
+                    c.user_id AS "user_id?: UserId",
+                    c.renewal_count AS "renewal_count?",
+                    EXISTS(
+                        SELECT 1 FROM reservations AS r
+                        WHERE r.book_id = b.book_id AND r.user_id <> ?
+                    ) AS "reserved_by_others: bool"
+                    FROM books AS b
+                    LEFT OUTER JOIN checkouts AS c
+                    ON c.book_id = b.book_id AND c.checkout_id = ?
+                    WHERE b.book_id = ?;
+                "#,
+                event.renewed_by as _,
+                event.checkout_id as _,
+                event.book_id as _,
+            )
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+
+            match res {
+                // 指定した書籍がそもそも存在しない場合
+                None => {
+                    return Err(AppError::EntityNotFound(format!(
+                        " 書籍（{}）が見つかりませんでした。",
+                        event.book_id
+                    )))
+                }
+                // 指定した貸出が存在しない、または借りたユーザーが異なる場合
+                Some(RenewalStateRow { user_id, .. }) if user_id != Some(event.renewed_by) => {
+                    return Err(AppError::UnprocessableEntity(format!(
+                        " 指定の貸出（ID（{}）, ユーザー（{}）, 書籍（{}））は延長できません。",
+                        event.checkout_id, event.renewed_by, event.book_id
+                    )))
+                }
+                // 他のユーザーが予約している場合
+                Some(RenewalStateRow {
+                    reserved_by_others: true,
+                    ..
+                }) => {
+                    return Err(AppError::UnprocessableEntity(format!(
+                        " 書籍（{}）は他のユーザーが予約しているため延長できません。",
+                        event.book_id
+                    )))
+                }
+                // それ以外は処理続行
+                Some(RenewalStateRow { renewal_count, .. }) => renewal_count.unwrap_or_default(),
+            }
+        };
+
+        // 延長回数の上限は、借りたユーザーのロールの貸出ポリシーで決まる
+        let (policy, _) = self
+            .fetch_loan_policy(&mut tx, event.renewed_by, event.renewed_at)
+            .await?;
+        policy::check_renewal(&policy, renewal_count).map_err(AppError::LoanPolicyViolation)?;
+
+        // 返却期限を延長操作の日時から貸出期間分後ろにずらし、延長回数を増やす
+        let due_at = event.renewed_at + Duration::days(policy.loan_period_days);
+        let res = sqlx::query!(
             r#"
-                SELECT
-                c.checkout_id,
-                c.book_id,
-                c.user_id,
-                c.checked_out_at,
-                b.title,
-                b.author,
-                b.isbn
-                FROM checkouts AS c
-                INNER JOIN books AS b USING(book_id)
-                ORDER BY c.checked_out_at ASC
-                ;
+                UPDATE checkouts
+                SET
+                    due_at = ?,
+                    renewal_count = renewal_count + 1
+                WHERE checkout_id = ?;
             "#,
+            due_at,
+            event.checkout_id as _,
         )
-        .fetch_all(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
-        .map(|rows| rows.into_iter().map(Checkout::from).collect())
-        .map_err(AppError::SpecificOperationError)
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::NoRowsAffectedError(
+                "No checkout record has been renewed".into(),
+            ));
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    // すべての未返却の貸出情報を取得する
+    async fn find_unreturned_all(
+        &self,
+        options: CursorListOptions,
+    ) -> AppResult<CursorPaginatedList<Checkout>> {
+        self.find_unreturned(None, options).await
+    }
+
+    // 返却期限を過ぎた未返却の貸出情報を取得する
+    async fn find_overdue_all(
+        &self,
+        now: DateTime<Utc>,
+        options: CursorListOptions,
+    ) -> AppResult<CursorPaginatedList<Checkout>> {
+        self.find_unreturned(Some(now), options).await
     }
 
     // ユーザー ID に紐づく未返却の貸出情報を取得する
@@ -243,8 +538,13 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
                 SELECT
                 c.checkout_id,
                 c.book_id,
+                c.copy_id,
                 c.user_id,
+                c.checkout_processed_by,
+                c.checkout_branch_id AS "checkout_branch_id?: BranchId",
                 c.checked_out_at,
+                c.due_at,
+                c.renewal_count,
                 b.title,
                 b.author,
                 b.isbn
@@ -263,104 +563,345 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
     }
 
     // 蔵書の貸し出し履歴（返却済みも含む）を取得する
-    async fn find_history_by_book_id(&self, book_id: BookId) -> AppResult<Vec<Checkout>> {
-        // このメソッドでは、貸出中・返却済みの両方を取得して
-        // 蔵書に対する貸出履歴の一覧として返す必要がある。
-        // そのため、未返却の貸出情報と返却済みの貸出情報をそれぞれ取得し、
-        // 未返却の貸出情報があれば Vec に挿入して返す、という実装とする。
-        // 未返却の貸出情報を取得
-        let checkout: Option<Checkout> = self.find_unreturned_by_book_id(book_id).await?;
-        // 返却済みの貸出情報を取得
-        let mut checkout_histories: Vec<Checkout> = sqlx::query_as!(
-            ReturnedCheckoutRow,
-            r#"
-                SELECT
-                rc.checkout_id,
-                rc.book_id,
-                rc.user_id,
-                rc.checked_out_at,
-                rc.returned_at,
-                b.title,
-                b.author,
-                b.isbn
-                FROM returned_checkouts AS rc
-                INNER JOIN books AS b USING(book_id)
-                WHERE rc.book_id = ? /* ★修正: $1 を ? に置換 */
-                ORDER BY rc.checked_out_at DESC
-            "#,
-            book_id as _
+    async fn find_history_by_book_id(
+        &self,
+        book_id: BookId,
+        options: CursorListOptions,
+    ) -> AppResult<CursorPaginatedList<Checkout>> {
+        let CursorListOptions { limit, cursor } = options;
+        self.find_history(
+            CheckoutHistoryTarget::Book(book_id),
+            CheckoutHistoryOptions {
+                limit,
+                cursor,
+                ..Default::default()
+            },
         )
-        .fetch_all(self.db.inner_ref())
         .await
-        .map_err(AppError::SpecificOperationError)?
-        .into_iter()
-        .map(Checkout::from)
-        .collect();
-
-        // 貸出中である場合は返却済みの履歴の先頭に追加する
-        if let Some(co) = checkout {
-            checkout_histories.insert(0, co);
-        }
+    }
 
-        Ok(checkout_histories)
+    async fn find_history_by_user_id(
+        &self,
+        user_id: UserId,
+        options: CheckoutHistoryOptions,
+    ) -> AppResult<CursorPaginatedList<Checkout>> {
+        self.find_history(CheckoutHistoryTarget::User(user_id), options)
+            .await
     }
 }
 
 impl CheckoutRepositoryImpl {
-    // create, update_returned メソッドでのトランザクションを利用するにあたり
-    // トランザクション分離レベルを SERIALIZABLE にするために
-    // 内部的に使うメソッド
-    async fn set_transaction_serializable(
+    // 貸出中・返却済みの両方を含む貸し出し履歴を、貸出日の新しい順にカーソル方式で取得する
+    async fn find_history(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::MySql>, /* ★修正: sqlx::Postgres を sqlx::MySql に置換 */
-    ) -> AppResult<()> {
-        sqlx::query!("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
-            .execute(&mut **tx)
+        target: CheckoutHistoryTarget,
+        options: CheckoutHistoryOptions,
+    ) -> AppResult<CursorPaginatedList<Checkout>> {
+        // このメソッドでは、貸出中・返却済みの両方を取得して
+        // 蔵書またはユーザーに対する貸出履歴の一覧として返す必要がある。
+        // ページをまたいでも順序が崩れないように、checkouts テーブルと
+        // returned_checkouts テーブルを UNION ALL でまとめてから、
+        // 貸出日の新しい順に並べてカーソルの位置から取得する。
+        // 貸出中の貸出情報は最も新しいので、先頭のページの先頭に来る。
+        // 完全に削除された蔵書の返却済みの貸出は、削除した時点で記録した蔵書の情報を使う。
+        let CheckoutHistoryOptions {
+            limit,
+            cursor,
+            checked_out_from,
+            checked_out_to,
+        } = options;
+        let cursor = cursor.as_deref().map(Cursor::decode).transpose()?;
+        let direction = cursor
+            .as_ref()
+            .map(|c| c.direction)
+            .unwrap_or(CursorDirection::Next);
+        let columns = ("h.checked_out_at", "h.checkout_id");
+
+        let mut query = QueryBuilder::new(
+            r#"
+                SELECT
+                h.checkout_id,
+                h.book_id,
+                h.copy_id,
+                h.user_id,
+                h.checkout_processed_by,
+                h.checkout_branch_id,
+                h.checked_out_at,
+                h.due_at,
+                h.renewal_count,
+                h.returned_at,
+                h.return_outcome,
+                h.return_note,
+                h.return_processed_by,
+                h.return_branch_id,
+                COALESCE(b.title, h.book_title) AS title,
+                COALESCE(b.author, h.book_author) AS author,
+                COALESCE(b.isbn, h.book_isbn) AS isbn
+                FROM (
+                    SELECT
+                    checkout_id, book_id, copy_id, user_id, checkout_processed_by,
+                    checkout_branch_id, checked_out_at, due_at, renewal_count, NULL AS returned_at,
+                    NULL AS return_outcome, NULL AS return_note, NULL AS return_processed_by,
+                    NULL AS return_branch_id, NULL AS book_title, NULL AS book_author,
+                    NULL AS book_isbn
+                    FROM checkouts
+                    UNION ALL
+                    SELECT
+                    checkout_id, book_id, copy_id, user_id, checkout_processed_by,
+                    checkout_branch_id, checked_out_at, due_at, renewal_count, returned_at,
+                    return_outcome, return_note, return_processed_by, return_branch_id,
+                    book_title, book_author, book_isbn
+                    FROM returned_checkouts
+                ) AS h
+                LEFT OUTER JOIN books AS b USING(book_id)
+                WHERE TRUE
+            "#,
+        );
+        match target {
+            CheckoutHistoryTarget::Book(book_id) => {
+                query.push(" AND h.book_id = ").push_bind(book_id);
+            }
+            CheckoutHistoryTarget::User(user_id) => {
+                query.push(" AND h.user_id = ").push_bind(user_id);
+            }
+        }
+        if let Some(from) = checked_out_from {
+            query.push(" AND h.checked_out_at >= ").push_bind(from);
+        }
+        if let Some(to) = checked_out_to {
+            query.push(" AND h.checked_out_at < ").push_bind(to);
+        }
+        if let Some(cursor) = &cursor {
+            let (checked_out_at, checkout_id) = decode_checkout_cursor(cursor)?;
+            push_keyset_condition(
+                &mut query,
+                columns,
+                SortOrder::Desc,
+                direction,
+                checked_out_at,
+                checkout_id,
+            );
+        }
+        push_keyset_order_by(&mut query, columns, SortOrder::Desc, direction);
+        if let Some(limit) = limit {
+            query.push(" LIMIT ").push_bind(limit.saturating_add(1));
+        }
+        let rows: Vec<CheckoutHistoryRow> = query
+            .build_query_as()
+            .fetch_all(self.db.inner_ref())
             .await
             .map_err(AppError::SpecificOperationError)?;
-        Ok(())
+
+        // limit を指定しない場合は取得した全件を 1 ページとして扱う
+        let limit = limit.unwrap_or(rows.len() as i64);
+        let (rows, next_cursor, prev_cursor) =
+            paginate_by_cursor(rows, limit, cursor.as_ref(), |row| {
+                checkout_cursor_values(row.checked_out_at, row.checkout_id)
+            });
+        Ok(CursorPaginatedList {
+            limit,
+            items: rows
+                .into_iter()
+                .map(Checkout::try_from)
+                .collect::<AppResult<Vec<_>>>()?,
+            next_cursor,
+            prev_cursor,
+        })
     }
 
-    // find_history_by_book_id で未返却の貸出情報を取得するために
-    // 内部的に使うメソッド
-    async fn find_unreturned_by_book_id(&self, book_id: BookId) -> AppResult<Option<Checkout>> {
-        let res = sqlx::query_as!(
-            CheckoutRow,
+    // 未返却の貸出情報を、貸出日の古い順にカーソル方式で取得する
+    // overdue_at を指定した場合は、その日時の時点で返却期限を過ぎているものに絞り込む
+    async fn find_unreturned(
+        &self,
+        overdue_at: Option<DateTime<Utc>>,
+        options: CursorListOptions,
+    ) -> AppResult<CursorPaginatedList<Checkout>> {
+        // checkouts テーブルにあるレコードをカーソルの位置から抽出する
+        // books テーブルと INNER JOIN し、蔵書の情報も一緒に抽出する
+        // 出力するレコードは、貸出日の古い順に並べる
+        let CursorListOptions { limit, cursor } = options;
+        let cursor = cursor.as_deref().map(Cursor::decode).transpose()?;
+        let direction = cursor
+            .as_ref()
+            .map(|c| c.direction)
+            .unwrap_or(CursorDirection::Next);
+        let columns = ("c.checked_out_at", "c.checkout_id");
+
+        let mut query = QueryBuilder::new(
             r#"
                 SELECT
                 c.checkout_id,
                 c.book_id,
+                c.copy_id,
                 c.user_id,
+                c.checkout_processed_by,
+                c.checkout_branch_id,
                 c.checked_out_at,
+                c.due_at,
+                c.renewal_count,
                 b.title,
                 b.author,
                 b.isbn
                 FROM checkouts AS c
                 INNER JOIN books AS b USING(book_id)
-                WHERE c.book_id = ? /* ★修正: $1 を ? に置換 */
+                WHERE TRUE
+            "#,
+        );
+        if let Some(now) = overdue_at {
+            query.push(" AND c.due_at < ").push_bind(now);
+        }
+        if let Some(cursor) = &cursor {
+            let (checked_out_at, checkout_id) = decode_checkout_cursor(cursor)?;
+            push_keyset_condition(
+                &mut query,
+                columns,
+                SortOrder::Asc,
+                direction,
+                checked_out_at,
+                checkout_id,
+            );
+        }
+        push_keyset_order_by(&mut query, columns, SortOrder::Asc, direction);
+        if let Some(limit) = limit {
+            query.push(" LIMIT ").push_bind(limit.saturating_add(1));
+        }
+        let rows: Vec<CheckoutRow> = query
+            .build_query_as()
+            .fetch_all(self.db.inner_ref())
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+
+        // limit を指定しない場合は取得した全件を 1 ページとして扱う
+        let limit = limit.unwrap_or(rows.len() as i64);
+        let (rows, next_cursor, prev_cursor) =
+            paginate_by_cursor(rows, limit, cursor.as_ref(), |row| {
+                checkout_cursor_values(row.checked_out_at, row.checkout_id)
+            });
+        Ok(CursorPaginatedList {
+            limit,
+            items: rows.into_iter().map(Checkout::from).collect(),
+            next_cursor,
+            prev_cursor,
+        })
+    }
+
+    // ユーザーのロールに応じた貸出ポリシーと、貸出中の冊数を取得する
+    async fn fetch_loan_policy(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        user_id: UserId,
+        now: DateTime<Utc>,
+    ) -> AppResult<(LoanPolicy, LoanerStateRow)> {
+        let state = sqlx::query_as!(
+            LoanerStateRow,
+            r#"
+                SELECT
+                r.name AS role_name,
+                (
+                    SELECT COUNT(*) FROM checkouts AS c
+                    WHERE c.user_id = u.user_id
+                ) AS "loans!: i64",
+                (
+                    SELECT COUNT(*) FROM checkouts AS c
+                    WHERE c.user_id = u.user_id AND c.due_at < ?
+                ) AS "overdue_loans!: i64"
+                FROM users AS u
+                INNER JOIN roles AS r USING(role_id)
+                WHERE u.user_id = ?
             "#,
-            book_id as _,
+            now,
+            user_id as _
         )
-        .fetch_optional(self.db.inner_ref())
+        .fetch_optional(&mut **tx)
         .await
         .map_err(AppError::SpecificOperationError)?
-        .map(Checkout::from);
+        .ok_or_else(|| {
+            AppError::EntityNotFound(format!(" ユーザー（{}）が見つかりませんでした。", user_id))
+        })?;
+        let role = Role::from_str(&state.role_name)
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+        Ok((policy::loan_policy(&self.config, &role), state))
+    }
 
-        Ok(res)
+    // create, update_returned, renew メソッドでのトランザクションを利用するにあたり
+    // トランザクション分離レベルを SERIALIZABLE にするために
+    // 内部的に使うメソッド
+    async fn set_transaction_serializable(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::MySql>, /* ★修正: sqlx::Postgres を sqlx::MySql に置換 */
+    ) -> AppResult<()> {
+        sqlx::query!("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+}
+
+// 貸出一覧のカーソルには、貸出日時（マイクロ秒単位の UNIX 時間）と貸出 ID を格納する
+fn checkout_cursor_values(checked_out_at: DateTime<Utc>, checkout_id: CheckoutId) -> Vec<String> {
+    vec![
+        checked_out_at.timestamp_micros().to_string(),
+        checkout_id.to_string(),
+    ]
+}
+
+// 貸し出し履歴の絞り込みの対象
+enum CheckoutHistoryTarget {
+    Book(BookId),
+    User(UserId),
+}
+
+fn decode_checkout_cursor(cursor: &Cursor) -> AppResult<(DateTime<Utc>, CheckoutId)> {
+    let invalid = || AppError::InvalidCursor("malformed checkout cursor".into());
+    let [checked_out_at, checkout_id] = cursor.values.as_slice() else {
+        return Err(invalid());
+    };
+    let checked_out_at = checked_out_at
+        .parse()
+        .ok()
+        .and_then(DateTime::from_timestamp_micros)
+        .ok_or_else(invalid)?;
+    let checkout_id = CheckoutId::from_str(checkout_id).map_err(|_| invalid())?;
+    Ok((checked_out_at, checkout_id))
+}
+
+// 存在しない館は外部キー制約で弾かれるため、エラーの内容を置き換える
+fn map_branch_not_found(e: sqlx::Error) -> AppError {
+    match e.as_database_error() {
+        Some(db_error) if db_error.is_foreign_key_violation() => {
+            AppError::UnprocessableEntity("存在しない館が指定されています".into())
+        }
+        _ => AppError::SpecificOperationError(e),
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::repository::{
+        book::BookRepositoryImpl, fine::FineRepositoryImpl, location::LocationRepositoryImpl,
+    };
     use chrono::Utc;
+    use kernel::model::book::event::{DeleteBook, PurgeBook};
     use kernel::model::checkout::CheckoutBook;
-
-    use super::*;
+    use kernel::model::location::event::{CreateBranch, DeleteBranch};
+    use kernel::repository::{
+        book::BookRepository, fine::FineRepository, location::LocationRepository,
+    };
+    use shared::config::IsbnUniqueness;
     use std::str::FromStr;
 
     // ★修正: sqlx::PgPool を sqlx::MySqlPool に置換 ★
     fn init_repo(pool: sqlx::MySqlPool) -> (CheckoutRepositoryImpl, UserId, UserId, BookId) {
-        let repo = CheckoutRepositoryImpl::new(ConnectionPool::new(pool));
+        let repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            CheckoutConfig::default(),
+            ReservationConfig {
+                pickup_window_hours: 72,
+            },
+            FineConfig::default(),
+        );
 
         // 事前登録したユーザー＆蔵書のID（fixtures/checkout.sql参照）
         let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b").unwrap();
@@ -385,6 +926,10 @@ mod tests {
             book_id: book_id1,
             returned_by: user_id1,
             returned_at: Utc::now(),
+            outcome: ReturnOutcome::Ok,
+            note: None,
+            by_admin: false,
+            branch_id: None,
         })
         .await?;
         // ... (省略) ...
@@ -403,4 +948,692 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_checkout_history_by_cursor(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let (repo, user_id1, user_id2, book_id1) = init_repo(pool);
+
+        // user_id1 が借りて返却したあと、user_id2 が借りている状態にする
+        repo.create(CreateCheckout::new(book_id1, None, user_id1, Utc::now()))
+            .await?;
+        let co = repo
+            .find_unreturned_all(CursorListOptions {
+                limit: Some(20),
+                cursor: None,
+            })
+            .await?
+            .into_inner()
+            .pop()
+            .unwrap();
+        repo.update_returned(UpdateReturned::new(co.id, book_id1, user_id1, Utc::now()))
+            .await?;
+        repo.create(CreateCheckout::new(book_id1, None, user_id2, Utc::now()))
+            .await?;
+
+        // 1 件ずつ取得すると、貸出中のもの、返却済みのものの順に返る
+        let first = repo
+            .find_history_by_book_id(
+                book_id1,
+                CursorListOptions {
+                    limit: Some(1),
+                    cursor: None,
+                },
+            )
+            .await?;
+        assert_eq!(first.items.len(), 1);
+        assert_eq!(first.items[0].checked_out_by, user_id2);
+        assert!(first.items[0].returned_at.is_none());
+        assert!(first.prev_cursor.is_none());
+
+        let second = repo
+            .find_history_by_book_id(
+                book_id1,
+                CursorListOptions {
+                    limit: Some(1),
+                    cursor: first.next_cursor,
+                },
+            )
+            .await?;
+        assert_eq!(second.items.len(), 1);
+        assert_eq!(second.items[0].id, co.id);
+        assert!(second.items[0].returned_at.is_some());
+        assert!(second.next_cursor.is_none());
+
+        // 前のページに戻る
+        let back = repo
+            .find_history_by_book_id(
+                book_id1,
+                CursorListOptions {
+                    limit: Some(1),
+                    cursor: second.prev_cursor,
+                },
+            )
+            .await?;
+        assert_eq!(back.items[0].checked_out_by, user_id2);
+        assert!(back.prev_cursor.is_none());
+
+        let res = repo
+            .find_unreturned_all(CursorListOptions {
+                limit: Some(20),
+                cursor: Some("invalid".into()),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::InvalidCursor(_))));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_checkout_copies(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let (repo, user_id1, user_id2, book_id1) = init_repo(pool);
+        // 事前登録した所蔵資料の ID（fixtures/checkout.sql参照）
+        // copy_id1 のほうが受け入れ日が古い
+        let copy_id1 = CopyId::from_str("1e4b6a0c-2f5d-4c1e-9a57-0c1d3b9f2a01")?;
+        let copy_id2 = CopyId::from_str("3d7f9b21-4c6e-4a8d-b2f3-6e1a0c9d5b04")?;
+
+        // 所蔵資料を指定すると、その所蔵資料を貸し出す
+        repo.create(CreateCheckout::new(
+            book_id1,
+            Some(copy_id2),
+            user_id1,
+            Utc::now(),
+        ))
+        .await?;
+        let res = repo
+            .create(CreateCheckout::new(
+                book_id1,
+                Some(copy_id2),
+                user_id2,
+                Utc::now(),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 所蔵資料を指定しない場合は、貸出可能な所蔵資料を貸し出す
+        repo.create(CreateCheckout::new(book_id1, None, user_id2, Utc::now()))
+            .await?;
+        let checkouts = repo.find_unreturned_by_user_id(user_id2).await?;
+        assert_eq!(checkouts.len(), 1);
+        assert_eq!(checkouts[0].copy_id, copy_id1);
+
+        // すべての所蔵資料が貸出中の場合は貸し出せない
+        let res = repo
+            .create(CreateCheckout::new(book_id1, None, user_id2, Utc::now()))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 他のユーザーの貸出は返却できない
+        let res = repo
+            .update_returned(UpdateReturned::new(
+                checkouts[0].id,
+                book_id1,
+                user_id1,
+                Utc::now(),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 存在しない所蔵資料は貸し出せない
+        let res = repo
+            .create(CreateCheckout::new(
+                book_id1,
+                Some(CopyId::new()),
+                user_id1,
+                Utc::now(),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_return_damaged_and_lost(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let (repo, user_id1, user_id2, book_id1) = init_repo(pool.clone());
+        let fine_repo = FineRepositoryImpl::new(ConnectionPool::new(pool));
+        let copy_id1 = CopyId::from_str("1e4b6a0c-2f5d-4c1e-9a57-0c1d3b9f2a01")?;
+        let copy_id2 = CopyId::from_str("3d7f9b21-4c6e-4a8d-b2f3-6e1a0c9d5b04")?;
+
+        repo.create(CreateCheckout::new(
+            book_id1,
+            Some(copy_id1),
+            user_id1,
+            Utc::now(),
+        ))
+        .await?;
+        repo.create(CreateCheckout::new(
+            book_id1,
+            Some(copy_id2),
+            user_id2,
+            Utc::now(),
+        ))
+        .await?;
+
+        // user_id1 は破損、user_id2 は紛失として返却する
+        let returns = [
+            (user_id1, ReturnOutcome::Damaged, "表紙が破れている"),
+            (user_id2, ReturnOutcome::Lost, "電車に置き忘れた"),
+        ];
+        for (user_id, outcome, note) in returns {
+            let co = repo.find_unreturned_by_user_id(user_id).await?;
+            let mut event = UpdateReturned::new(co[0].id, book_id1, user_id, Utc::now());
+            event.outcome = outcome;
+            event.note = Some(note.into());
+            repo.update_returned(event).await?;
+        }
+
+        // 貸出履歴に返却時の状態とメモが残る
+        let history = repo
+            .find_history_by_book_id(
+                book_id1,
+                CursorListOptions {
+                    limit: Some(10),
+                    cursor: None,
+                },
+            )
+            .await?;
+        for (user_id, outcome, note) in returns {
+            let co = history
+                .items
+                .iter()
+                .find(|c| c.checked_out_by == user_id)
+                .unwrap();
+            assert_eq!(co.return_outcome, Some(outcome));
+            assert_eq!(co.return_note.as_deref(), Some(note));
+        }
+
+        // 紛失した場合は弁償金が請求され、破損の場合は請求されない
+        let fines = fine_repo.find_by_user_id(user_id2).await?;
+        assert_eq!(fines.balance, FineConfig::default().replacement_charge);
+        assert_eq!(fines.entries[0].kind, FineEntryKind::Replacement);
+        assert_eq!(fine_repo.find_by_user_id(user_id1).await?.balance, 0);
+
+        // 紛失した所蔵資料は貸し出せず、破損した所蔵資料は引き続き貸し出せる
+        let res = repo
+            .create(CreateCheckout::new(
+                book_id1,
+                Some(copy_id2),
+                user_id1,
+                Utc::now(),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+        repo.create(CreateCheckout::new(book_id1, None, user_id1, Utc::now()))
+            .await?;
+        let checkouts = repo.find_unreturned_by_user_id(user_id1).await?;
+        assert_eq!(checkouts[0].copy_id, copy_id1);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_checkout_and_return_by_admin(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let (repo, user_id1, user_id2, book_id1) = init_repo(pool);
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        // 管理者が user_id1 に代わって貸し出す
+        let mut event = CreateCheckout::new(book_id1, None, user_id1, Utc::now());
+        event.processed_by = Some(admin_id);
+        repo.create(event).await?;
+        let co = repo.find_unreturned_by_user_id(user_id1).await?;
+        assert_eq!(co[0].checked_out_by, user_id1);
+        assert_eq!(co[0].checkout_processed_by, admin_id);
+
+        // 管理者以外は、他のユーザーの貸出を返却できない
+        let res = repo
+            .update_returned(UpdateReturned::new(
+                co[0].id,
+                book_id1,
+                user_id2,
+                Utc::now(),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 管理者は借りたユーザーに代わって返却できる
+        let mut event = UpdateReturned::new(co[0].id, book_id1, admin_id, Utc::now());
+        event.by_admin = true;
+        repo.update_returned(event).await?;
+
+        // 貸出履歴には、借りたユーザーと手続きをしたユーザーが別々に残る
+        let history = repo
+            .find_history_by_book_id(
+                book_id1,
+                CursorListOptions {
+                    limit: Some(10),
+                    cursor: None,
+                },
+            )
+            .await?;
+        assert_eq!(history.items.len(), 1);
+        assert_eq!(history.items[0].checked_out_by, user_id1);
+        assert_eq!(history.items[0].checkout_processed_by, admin_id);
+        assert_eq!(history.items[0].return_processed_by, Some(admin_id));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_checkout_deleted_book(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let book_repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Disabled);
+        let (repo, user_id1, _, book_id1) = init_repo(pool);
+        let owner_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        repo.create(CreateCheckout::new(book_id1, None, user_id1, Utc::now()))
+            .await?;
+
+        // 貸出中でも削除済みにでき、貸出は残る
+        book_repo
+            .delete(DeleteBook {
+                book_id: book_id1,
+                requested_user: owner_id,
+                expected_version: None,
+            })
+            .await?;
+        let co = repo.find_unreturned_by_user_id(user_id1).await?;
+        assert_eq!(co.len(), 1);
+
+        // 貸出中の所蔵資料がある間は完全に削除できない
+        let res = book_repo
+            .purge(PurgeBook {
+                book_id: book_id1,
+                requested_user: owner_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 削除済みの蔵書も返却でき、貸出履歴に残る
+        repo.update_returned(UpdateReturned::new(
+            co[0].id,
+            book_id1,
+            user_id1,
+            Utc::now(),
+        ))
+        .await?;
+        let options = || CursorListOptions {
+            limit: Some(10),
+            cursor: None,
+        };
+        let history = repo.find_history_by_book_id(book_id1, options()).await?;
+        assert_eq!(history.items.len(), 1);
+
+        // 削除済みの蔵書は新たに貸し出せない
+        let res = repo
+            .create(CreateCheckout::new(book_id1, None, user_id1, Utc::now()))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        // 返却後は完全に削除でき、返却済みの貸出履歴は削除した時点の蔵書の情報とともに残る
+        let title = history.items[0].book.title.clone();
+        book_repo
+            .purge(PurgeBook {
+                book_id: book_id1,
+                requested_user: owner_id,
+            })
+            .await?;
+        let history = repo.find_history_by_book_id(book_id1, options()).await?;
+        assert_eq!(history.items.len(), 1);
+        assert_eq!(history.items[0].book.title, title);
+        let history = repo
+            .find_history_by_user_id(user_id1, Default::default())
+            .await?;
+        assert_eq!(history.items.len(), 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_checkout_and_return_at_branch(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let location_repo = LocationRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let (repo, user_id1, _, book_id1) = init_repo(pool);
+        let main = location_repo
+            .create_branch(CreateBranch {
+                name: "本館".into(),
+            })
+            .await?;
+        let annex = location_repo
+            .create_branch(CreateBranch {
+                name: "別館".into(),
+            })
+            .await?;
+
+        // 存在しない館は記録できない
+        let mut event = CreateCheckout::new(book_id1, None, user_id1, Utc::now());
+        event.branch_id = Some(BranchId::new());
+        let res = repo.create(event).await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 本館で貸し出し、別館で返却する
+        let mut event = CreateCheckout::new(book_id1, None, user_id1, Utc::now());
+        event.branch_id = Some(main.id);
+        repo.create(event).await?;
+        let co = repo.find_unreturned_by_user_id(user_id1).await?;
+        assert_eq!(co[0].checkout_branch_id, Some(main.id));
+
+        let mut event = UpdateReturned::new(co[0].id, book_id1, user_id1, Utc::now());
+        event.branch_id = Some(annex.id);
+        repo.update_returned(event).await?;
+
+        let history = repo
+            .find_history_by_book_id(
+                book_id1,
+                CursorListOptions {
+                    limit: Some(10),
+                    cursor: None,
+                },
+            )
+            .await?;
+        assert_eq!(history.items[0].checkout_branch_id, Some(main.id));
+        assert_eq!(history.items[0].return_branch_id, Some(annex.id));
+
+        // 館を削除しても貸出履歴は残り、館の記録だけが消える
+        location_repo
+            .delete_branch(DeleteBranch { branch_id: main.id })
+            .await?;
+        let history = repo
+            .find_history_by_book_id(
+                book_id1,
+                CursorListOptions {
+                    limit: Some(10),
+                    cursor: None,
+                },
+            )
+            .await?;
+        assert_eq!(history.items[0].checkout_branch_id, None);
+        assert_eq!(history.items[0].return_branch_id, Some(annex.id));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_checkout_history_by_user(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let (repo, user_id1, user_id2, book_id1) = init_repo(pool);
+        let now = Utc::now();
+
+        // user_id1 は 40 日前に借りて返却し、今日もう一度借りる
+        // user_id2 の貸出は user_id1 の履歴には含まれない
+        repo.create(CreateCheckout::new(
+            book_id1,
+            None,
+            user_id1,
+            now - Duration::days(40),
+        ))
+        .await?;
+        let co = repo.find_unreturned_by_user_id(user_id1).await?;
+        repo.update_returned(UpdateReturned::new(
+            co[0].id,
+            book_id1,
+            user_id1,
+            now - Duration::days(30),
+        ))
+        .await?;
+        repo.create(CreateCheckout::new(book_id1, None, user_id1, now))
+            .await?;
+        repo.create(CreateCheckout::new(book_id1, None, user_id2, now))
+            .await?;
+
+        let history = repo
+            .find_history_by_user_id(
+                user_id1,
+                CheckoutHistoryOptions {
+                    limit: Some(10),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(history.items.len(), 2);
+        assert!(history.items[0].returned_at.is_none());
+        assert_eq!(history.items[1].id, co[0].id);
+        assert!(history.items[1].returned_at.is_some());
+
+        // 貸出日の範囲で絞り込む
+        let history = repo
+            .find_history_by_user_id(
+                user_id1,
+                CheckoutHistoryOptions {
+                    limit: Some(10),
+                    checked_out_to: Some(now - Duration::days(1)),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(history.items.len(), 1);
+        assert_eq!(history.items[0].id, co[0].id);
+
+        // カーソルで次のページを取得する
+        let first = repo
+            .find_history_by_user_id(
+                user_id1,
+                CheckoutHistoryOptions {
+                    limit: Some(1),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        let second = repo
+            .find_history_by_user_id(
+                user_id1,
+                CheckoutHistoryOptions {
+                    limit: Some(1),
+                    cursor: first.next_cursor,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(second.items[0].id, co[0].id);
+        assert!(second.next_cursor.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_overdue_checkouts(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let (repo, user_id1, user_id2, book_id1) = init_repo(pool);
+        let now = Utc::now();
+        let options = || CursorListOptions {
+            limit: Some(20),
+            cursor: None,
+        };
+
+        // user_id1 は 30 日前に借りたので返却期限を過ぎている
+        // user_id2 は今借りたので返却期限内である
+        repo.create(CreateCheckout::new(
+            book_id1,
+            None,
+            user_id1,
+            now - Duration::days(30),
+        ))
+        .await?;
+        repo.create(CreateCheckout::new(book_id1, None, user_id2, now))
+            .await?;
+
+        // 返却期限は貸出日に貸出期間を足した日時になる
+        let checkouts = repo.find_unreturned_all(options()).await?.into_inner();
+        assert_eq!(checkouts.len(), 2);
+        for co in &checkouts {
+            assert_eq!(co.due_at - co.checked_out_at, Duration::days(14));
+        }
+
+        let overdue = repo.find_overdue_all(now, options()).await?.into_inner();
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(overdue[0].checked_out_by, user_id1);
+
+        // 返却期限の 1 日後の時点では、両方とも返却期限を過ぎている
+        let overdue = repo
+            .find_overdue_all(now + Duration::days(15), options())
+            .await?
+            .into_inner();
+        assert_eq!(overdue.len(), 2);
+
+        // 返却すると返却期限を過ぎた貸出には含まれない
+        repo.update_returned(UpdateReturned::new(
+            overdue[0].id,
+            book_id1,
+            user_id1,
+            Utc::now(),
+        ))
+        .await?;
+        let overdue = repo.find_overdue_all(now, options()).await?.into_inner();
+        assert!(overdue.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_renew_checkout(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let (repo, user_id1, user_id2, book_id1) = init_repo(pool.clone());
+        let checked_out_at = Utc::now() - Duration::days(10);
+
+        repo.create(CreateCheckout::new(
+            book_id1,
+            None,
+            user_id1,
+            checked_out_at,
+        ))
+        .await?;
+        let co = repo
+            .find_unreturned_by_user_id(user_id1)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap();
+        assert_eq!(co.renewal_count, 0);
+
+        // 借りたユーザー以外は延長できない
+        let res = repo
+            .renew(RenewCheckout::new(co.id, book_id1, user_id2, Utc::now()))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 延長すると、延長操作の日時から貸出期間分後ろが返却期限になる
+        let renewed_at = Utc::now();
+        repo.renew(RenewCheckout::new(co.id, book_id1, user_id1, renewed_at))
+            .await?;
+        let renewed = repo.find_unreturned_by_user_id(user_id1).await?;
+        assert_eq!(renewed[0].renewal_count, 1);
+        assert!(renewed[0].due_at > co.due_at);
+
+        // 延長回数の上限（2 回）を超えて延長できない
+        repo.renew(RenewCheckout::new(co.id, book_id1, user_id1, Utc::now()))
+            .await?;
+        let res = repo
+            .renew(RenewCheckout::new(co.id, book_id1, user_id1, Utc::now()))
+            .await;
+        assert!(matches!(
+            res,
+            Err(AppError::LoanPolicyViolation(
+                LoanPolicyViolation::MaxRenewalsExceeded { limit: 2 }
+            ))
+        ));
+
+        // 延長回数は返却後の履歴にも残る
+        repo.update_returned(UpdateReturned::new(co.id, book_id1, user_id1, Utc::now()))
+            .await?;
+        let history = repo
+            .find_history_by_book_id(
+                book_id1,
+                CursorListOptions {
+                    limit: Some(20),
+                    cursor: None,
+                },
+            )
+            .await?
+            .into_inner();
+        assert_eq!(history[0].renewal_count, 2);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_renew_checkout_reserved(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let (repo, user_id1, user_id2, book_id1) = init_repo(pool.clone());
+
+        repo.create(CreateCheckout::new(book_id1, None, user_id1, Utc::now()))
+            .await?;
+        let co = repo.find_unreturned_by_user_id(user_id1).await?;
+
+        // 他のユーザーが予約している蔵書は延長できない
+        sqlx::query("INSERT INTO reservations (book_id, user_id) VALUES (?, ?)")
+            .bind(book_id1)
+            .bind(user_id2)
+            .execute(&pool)
+            .await?;
+        let res = repo
+            .renew(RenewCheckout::new(co[0].id, book_id1, user_id1, Utc::now()))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_checkout_loan_policy(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        // 一般ユーザーは同時に 1 冊まで、管理者は既定の 5 冊まで借りられる
+        let repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            CheckoutConfig {
+                user: LoanPolicy {
+                    max_loans: 1,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ReservationConfig {
+                pickup_window_hours: 72,
+            },
+            FineConfig::default(),
+        );
+        let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let user_id2 = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5")?;
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book_id1 = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+
+        // 同時に借りられる冊数の上限を超えて借りられない
+        repo.create(CreateCheckout::new(book_id1, None, user_id1, Utc::now()))
+            .await?;
+        let res = repo
+            .create(CreateCheckout::new(book_id1, None, user_id1, Utc::now()))
+            .await;
+        assert!(matches!(
+            res,
+            Err(AppError::LoanPolicyViolation(
+                LoanPolicyViolation::MaxLoansExceeded { limit: 1 }
+            ))
+        ));
+        let co = repo.find_unreturned_by_user_id(user_id1).await?;
+        repo.update_returned(UpdateReturned::new(
+            co[0].id,
+            book_id1,
+            user_id1,
+            Utc::now(),
+        ))
+        .await?;
+
+        // 返却期限を過ぎた貸出がある間は借りられない
+        repo.create(CreateCheckout::new(
+            book_id1,
+            None,
+            user_id2,
+            Utc::now() - Duration::days(30),
+        ))
+        .await?;
+        let res = repo
+            .create(CreateCheckout::new(book_id1, None, user_id2, Utc::now()))
+            .await;
+        assert!(matches!(
+            res,
+            Err(AppError::LoanPolicyViolation(
+                LoanPolicyViolation::OverdueCheckouts { count: 1 }
+            ))
+        ));
+
+        // 管理者のロールには別の貸出ポリシーが適用される
+        repo.create(CreateCheckout::new(book_id1, None, admin_id, Utc::now()))
+            .await?;
+        let co = repo.find_unreturned_by_user_id(admin_id).await?;
+        assert_eq!(co[0].due_at - co[0].checked_out_at, Duration::days(14));
+
+        Ok(())
+    }
 }
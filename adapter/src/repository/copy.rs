@@ -0,0 +1,246 @@
+use crate::database::{model::copy::BookCopyRow, ConnectionPool};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::{
+    copy::{
+        event::{CreateCopy, DeleteCopy, UpdateCopy},
+        BookCopy,
+    },
+    id::{BookId, CheckoutId, CopyId, UserId},
+};
+use kernel::repository::copy::BookCopyRepository;
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct BookCopyRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl BookCopyRepository for BookCopyRepositoryImpl {
+    async fn create(&self, event: CreateCopy) -> AppResult<()> {
+        // 蔵書の所有者のみ所蔵資料を追加できる
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO book_copies
+                (copy_id, book_id, barcode, acquired_on, copy_condition, shelf_location)
+                SELECT ?, book_id, ?, ?, ?, ?
+                FROM books
+                WHERE book_id = ? AND user_id = ? AND deleted_at IS NULL
+            "#,
+            CopyId::new() as _,
+            event.barcode,
+            event.acquired_on,
+            event.condition.as_ref(),
+            event.shelf_location,
+            event.book_id as _,
+            event.requested_user as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(map_barcode_conflict)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound("specified book not found".into()));
+        }
+
+        Ok(())
+    }
+
+    async fn find_by_book_id(&self, book_id: BookId) -> AppResult<Vec<BookCopy>> {
+        sqlx::query_as!(
+            BookCopyRow,
+            r#"
+                SELECT
+                bc.copy_id,
+                bc.book_id,
+                bc.barcode,
+                bc.acquired_on,
+                bc.copy_condition,
+                bc.shelf_location,
+                c.checkout_id AS "checkout_id?: CheckoutId",
+                u.user_id AS "user_id?: UserId",
+                u.name AS "user_name?",
+                c.checked_out_at AS "checked_out_at?",
+                c.due_at AS "due_at?"
+                FROM book_copies AS bc
+                LEFT OUTER JOIN checkouts AS c ON c.copy_id = bc.copy_id
+                LEFT OUTER JOIN users AS u ON u.user_id = c.user_id
+                WHERE bc.book_id = ?
+                ORDER BY bc.acquired_on ASC, bc.copy_id ASC
+            "#,
+            book_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(BookCopy::try_from)
+        .collect()
+    }
+
+    async fn update(&self, event: UpdateCopy) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                UPDATE book_copies AS bc
+                INNER JOIN books AS b USING(book_id)
+                SET
+                    bc.barcode = ?,
+                    bc.acquired_on = ?,
+                    bc.copy_condition = ?,
+                    bc.shelf_location = ?
+                WHERE bc.copy_id = ?
+                AND bc.book_id = ?
+                AND b.user_id = ?
+            "#,
+            event.barcode,
+            event.acquired_on,
+            event.condition.as_ref(),
+            event.shelf_location,
+            event.copy_id as _,
+            event.book_id as _,
+            event.requested_user as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(map_barcode_conflict)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound("specified copy not found".into()));
+        }
+
+        Ok(())
+    }
+
+    async fn delete(&self, event: DeleteCopy) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // 貸出中の所蔵資料は削除できない
+        // 削除すると貸出のレコードも一緒に削除されてしまうため
+        let checkout_id = sqlx::query_scalar!(
+            r#"
+                SELECT c.checkout_id AS "checkout_id?: CheckoutId"
+                FROM book_copies AS bc
+                INNER JOIN books AS b USING(book_id)
+                LEFT OUTER JOIN checkouts AS c ON c.copy_id = bc.copy_id
+                WHERE bc.copy_id = ?
+                AND bc.book_id = ?
+                AND b.user_id = ?
+                FOR UPDATE
+            "#,
+            event.copy_id as _,
+            event.book_id as _,
+            event.requested_user as _
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        match checkout_id {
+            None => return Err(AppError::EntityNotFound("specified copy not found".into())),
+            Some(Some(_)) => {
+                return Err(AppError::UnprocessableEntity(format!(
+                    "所蔵資料（{}）は貸出中のため削除できません。",
+                    event.copy_id
+                )))
+            }
+            Some(None) => {}
+        }
+
+        sqlx::query!(
+            r#"
+                DELETE FROM book_copies WHERE copy_id = ?
+            "#,
+            event.copy_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+}
+
+// バーコードの一意制約に違反した場合は、重複エラーとして返す
+fn map_barcode_conflict(e: sqlx::Error) -> AppError {
+    match e.as_database_error() {
+        Some(db_error) if db_error.is_unique_violation() => {
+            AppError::Conflict("同じバーコードの所蔵資料がすでに登録されています".into())
+        }
+        _ => AppError::SpecificOperationError(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use kernel::model::copy::CopyCondition;
+    use std::str::FromStr;
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_register_copy(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let repo = BookCopyRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        // fixtures/book.sql で作成済みの蔵書とその所有者
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let owner_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let acquired_on = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+
+        repo.create(CreateCopy {
+            book_id,
+            barcode: Some("BC1001".into()),
+            acquired_on,
+            condition: CopyCondition::New,
+            shelf_location: Some("A-1".into()),
+            requested_user: owner_id,
+        })
+        .await?;
+
+        let copies = repo.find_by_book_id(book_id).await?;
+        assert_eq!(copies.len(), 2);
+        let copy = copies.into_iter().last().unwrap();
+        assert_eq!(copy.barcode.as_deref(), Some("BC1001"));
+        assert_eq!(copy.acquired_on, acquired_on);
+        assert_eq!(copy.condition, CopyCondition::New);
+        assert!(copy.checkout.is_none());
+
+        // 同じバーコードの所蔵資料は登録できない
+        let res = repo
+            .create(CreateCopy {
+                book_id,
+                barcode: Some("BC1001".into()),
+                acquired_on,
+                condition: CopyCondition::Good,
+                shelf_location: None,
+                requested_user: owner_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        // 蔵書の所有者以外は更新できない
+        let update = |requested_user| UpdateCopy {
+            copy_id: copy.id,
+            book_id,
+            barcode: Some("BC1001".into()),
+            acquired_on,
+            condition: CopyCondition::Damaged,
+            shelf_location: Some("A-2".into()),
+            requested_user,
+        };
+        let res = repo.update(update(UserId::new())).await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+        repo.update(update(owner_id)).await?;
+
+        repo.delete(DeleteCopy {
+            copy_id: copy.id,
+            book_id,
+            requested_user: owner_id,
+        })
+        .await?;
+        assert_eq!(repo.find_by_book_id(book_id).await?.len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,269 @@
+use crate::database::{model::fine::FineEntryRow, ConnectionPool};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use kernel::model::{
+    fine::{event::CreateFineCredit, FineEntry, FineEntryKind, UserFines},
+    id::{CheckoutId, FineEntryId, UserId},
+};
+use kernel::repository::fine::FineRepository;
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct FineRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl FineRepository for FineRepositoryImpl {
+    // ユーザーの延滞料金の残高と、台帳の記録を新しい順に取得する
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<UserFines> {
+        // 残高と記録の一覧が食い違わないよう、同じトランザクション内で取得する
+        let mut tx = self.db.begin().await?;
+
+        let balance = fetch_fine_balance(&mut tx, user_id).await?;
+        let entries = sqlx::query_as!(
+            FineEntryRow,
+            r#"
+                SELECT
+                fine_entry_id AS "fine_entry_id: FineEntryId",
+                user_id AS "user_id: UserId",
+                checkout_id AS "checkout_id?: CheckoutId",
+                entry_kind,
+                amount,
+                note,
+                recorded_by AS "recorded_by?: UserId",
+                recorded_at
+                FROM fine_entries
+                WHERE user_id = ?
+                ORDER BY recorded_at DESC, fine_entry_id DESC
+            "#,
+            user_id as _
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(FineEntry::try_from)
+        .collect::<AppResult<Vec<_>>>()?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(UserFines {
+            user_id,
+            balance,
+            entries,
+        })
+    }
+
+    // 延滞料金の支払いまたは免除を記録する
+    async fn create_credit(&self, event: CreateFineCredit) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // 残高を超える支払い・免除は記録できない
+        let balance = fetch_fine_balance(&mut tx, event.user_id).await?;
+        if event.amount > balance {
+            return Err(AppError::UnprocessableEntity(format!(
+                " 金額（{} 円）が延滞料金の残高（{} 円）を超えています。",
+                event.amount, balance
+            )));
+        }
+
+        let kind = FineEntryKind::from(event.kind);
+        sqlx::query!(
+            r#"
+                INSERT INTO fine_entries
+                (fine_entry_id, user_id, entry_kind, amount, note, recorded_by, recorded_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+            FineEntryId::new() as _,
+            event.user_id as _,
+            kind.as_ref(),
+            event.amount,
+            event.note,
+            event.recorded_by as _,
+            event.recorded_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+}
+
+// ユーザーの延滞料金の残高（未払いの金額）を取得する
+// 弁償金も延滞料金と同じく残高に含める
+// ユーザーが存在しない場合は EntityNotFound を返す
+pub(crate) async fn fetch_fine_balance(
+    tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    user_id: UserId,
+) -> AppResult<i64> {
+    sqlx::query_scalar!(
+        r#"
+            SELECT
+            CAST(COALESCE((
+                SELECT SUM(
+                    CASE WHEN f.entry_kind IN ('Payment', 'Waiver') THEN -f.amount ELSE f.amount END
+                )
+                FROM fine_entries AS f
+                WHERE f.user_id = u.user_id
+            ), 0) AS SIGNED) AS "balance!: i64"
+            FROM users AS u
+            WHERE u.user_id = ?
+        "#,
+        user_id as _
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?
+    .ok_or_else(|| {
+        AppError::EntityNotFound(format!(" ユーザー（{}）が見つかりませんでした。", user_id))
+    })
+}
+
+// 返却時に発生した延滞料金や弁償金を台帳に記録する
+pub(crate) async fn create_charge(
+    tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    user_id: UserId,
+    checkout_id: CheckoutId,
+    kind: FineEntryKind,
+    amount: i64,
+    note: Option<&str>,
+    recorded_at: DateTime<Utc>,
+) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+            INSERT INTO fine_entries
+            (fine_entry_id, user_id, checkout_id, entry_kind, amount, note, recorded_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+        FineEntryId::new() as _,
+        user_id as _,
+        checkout_id as _,
+        kind.as_ref(),
+        amount,
+        note,
+        recorded_at
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::checkout::CheckoutRepositoryImpl;
+    use chrono::Duration;
+    use kernel::model::{
+        checkout::event::{CreateCheckout, UpdateReturned},
+        fine::event::FineCreditKind,
+        id::BookId,
+    };
+    use kernel::repository::checkout::CheckoutRepository;
+    use shared::config::{CheckoutConfig, FineConfig, ReservationConfig};
+    use shared::error::LoanPolicyViolation;
+    use std::str::FromStr;
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_overdue_fines(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let repo = FineRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        // 1 日 10 円、上限 100 円。残高が 50 円を超えると借りられない
+        let checkout_repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            CheckoutConfig::default(),
+            ReservationConfig {
+                pickup_window_hours: 72,
+            },
+            FineConfig {
+                daily_rate: 10,
+                cap: 100,
+                max_outstanding_balance: 50,
+                ..Default::default()
+            },
+        );
+        let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book_id1 = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+
+        // 返却期限の 3 日後に返却すると 30 円、20 日後に返却すると上限の 100 円
+        let checked_out_at = Utc::now() - Duration::days(40);
+        for overdue_days in [3, 20] {
+            checkout_repo
+                .create(CreateCheckout::new(
+                    book_id1,
+                    None,
+                    user_id1,
+                    checked_out_at,
+                ))
+                .await?;
+            let co = checkout_repo.find_unreturned_by_user_id(user_id1).await?;
+            checkout_repo
+                .update_returned(UpdateReturned::new(
+                    co[0].id,
+                    book_id1,
+                    user_id1,
+                    co[0].due_at + Duration::days(overdue_days),
+                ))
+                .await?;
+        }
+
+        let fines = repo.find_by_user_id(user_id1).await?;
+        assert_eq!(fines.balance, 130);
+        assert_eq!(fines.entries.len(), 2);
+        assert!(fines
+            .entries
+            .iter()
+            .all(|e| e.kind == FineEntryKind::Fine && e.checkout_id.is_some()));
+
+        // 残高が上限を超えている間は借りられない
+        let res = checkout_repo
+            .create(CreateCheckout::new(book_id1, None, user_id1, Utc::now()))
+            .await;
+        assert!(matches!(
+            res,
+            Err(AppError::LoanPolicyViolation(
+                LoanPolicyViolation::OutstandingFines {
+                    balance: 130,
+                    limit: 50
+                }
+            ))
+        ));
+
+        // 残高を超える支払いは記録できない
+        let credit = |kind, amount| CreateFineCredit {
+            user_id: user_id1,
+            kind,
+            amount,
+            note: None,
+            recorded_by: admin_id,
+            recorded_at: Utc::now(),
+        };
+        let res = repo
+            .create_credit(credit(FineCreditKind::Payment, 200))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 支払いと免除で残高が減ると、また借りられるようになる
+        repo.create_credit(credit(FineCreditKind::Payment, 50))
+            .await?;
+        repo.create_credit(credit(FineCreditKind::Waiver, 30))
+            .await?;
+        let fines = repo.find_by_user_id(user_id1).await?;
+        assert_eq!(fines.balance, 50);
+        assert_eq!(fines.entries.len(), 4);
+        checkout_repo
+            .create(CreateCheckout::new(book_id1, None, user_id1, Utc::now()))
+            .await?;
+
+        // 存在しないユーザー
+        let res = repo.find_by_user_id(UserId::new()).await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        Ok(())
+    }
+}
@@ -14,7 +14,7 @@ VALUES
     '51E949EE-1B64-4CD7-A49A-7A57BDADE4DF',
     'title001',
     'author001',
-    'isbn001',
+    '9784000000017',
     'description001',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:01.000',
@@ -24,7 +24,7 @@ VALUES
     'EB18DE8F-1947-4610-AA4B-4384C3ED41F1',
     'title002',
     'author002',
-    'isbn002',
+    '9784000000024',
     'description002',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:02.000',
@@ -34,7 +34,7 @@ VALUES
     '531B2760-7957-4B55-96FA-313328C4EA2B',
     'title003',
     'author003',
-    'isbn003',
+    '9784000000031',
     'description003',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:03.000',
@@ -44,7 +44,7 @@ VALUES
     '37BAC8F3-53FA-4FAB-92F8-6E3594064C22',
     'title004',
     'author004',
-    'isbn004',
+    '9784000000048',
     'description004',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:04.000',
@@ -54,7 +54,7 @@ VALUES
     'D3C6F78A-5488-45A7-98A0-0916DDA98F85',
     'title005',
     'author005',
-    'isbn005',
+    '9784000000055',
     'description005',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:05.000',
@@ -64,7 +64,7 @@ VALUES
     'AD2CB843-A32F-4E66-985E-C3B705DF8785',
     'title006',
     'author006',
-    'isbn006',
+    '9784000000062',
     'description006',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:06.000',
@@ -74,7 +74,7 @@ VALUES
     '1C203D70-3C0F-49BB-A402-36EDC1D07BA7',
     'title007',
     'author007',
-    'isbn007',
+    '9784000000079',
     'description007',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:07.000',
@@ -84,7 +84,7 @@ VALUES
     '26839014-3C6A-4154-BE86-086028257B3D',
     'title008',
     'author008',
-    'isbn008',
+    '9784000000086',
     'description008',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:08.000',
@@ -94,7 +94,7 @@ VALUES
     'F9B00E0A-9A05-412A-B4B2-3ACF833909DB',
     'title009',
     'author009',
-    'isbn009',
+    '9784000000093',
     'description009',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:09.000',
@@ -104,7 +104,7 @@ VALUES
     '20C60205-77B4-4BC2-9DA7-0EAF7328FBC5',
     'title010',
     'author010',
-    'isbn010',
+    '9784000000109',
     'description010',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:10.000',
@@ -114,7 +114,7 @@ VALUES
     '1259F7C3-F32D-4FDE-A00A-C18652461268',
     'title011',
     'author011',
-    'isbn011',
+    '9784000000116',
     'description011',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:11.000',
@@ -124,7 +124,7 @@ VALUES
     '75629657-8A2E-443E-82C4-4011EDCACD3F',
     'title012',
     'author012',
-    'isbn012',
+    '9784000000123',
     'description012',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:12.000',
@@ -134,7 +134,7 @@ VALUES
     '4E7C6AA0-C92F-4E89-AA9D-F06EEC1E9DC3',
     'title013',
     'author013',
-    'isbn013',
+    '9784000000130',
     'description013',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:13.000',
@@ -144,7 +144,7 @@ VALUES
     '322492F3-F2EA-4440-81D8-6CF7B33BCDDA',
     'title014',
     'author014',
-    'isbn014',
+    '9784000000147',
     'description014',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:14.000',
@@ -154,7 +154,7 @@ VALUES
     '4221CEF3-6F05-4378-A47D-BC278C676DFD',
     'title015',
     'author015',
-    'isbn015',
+    '9784000000154',
     'description015',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:15.000',
@@ -164,7 +164,7 @@ VALUES
     '1A793705-9FB0-436B-A789-63810DE3949D',
     'title016',
     'author016',
-    'isbn016',
+    '9784000000161',
     'description016',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:16.000',
@@ -174,7 +174,7 @@ VALUES
     '71F040CF-D25A-456E-AEA0-BF07F723B59D',
     'title017',
     'author017',
-    'isbn017',
+    '9784000000178',
     'description017',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:17.000',
@@ -184,7 +184,7 @@ VALUES
     '8E7667C1-D7A8-4BD4-A3B4-0211D3E33D79',
     'title018',
     'author018',
-    'isbn018',
+    '9784000000185',
     'description018',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:18.000',
@@ -194,7 +194,7 @@ VALUES
     '5E84B5ED-A9BB-4363-B752-AA95C513D98F',
     'title019',
     'author019',
-    'isbn019',
+    '9784000000192',
     'description019',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:19.000',
@@ -204,7 +204,7 @@ VALUES
     '564EA26A-92BC-4BF0-A788-F57040D58A10',
     'title020',
     'author020',
-    'isbn020',
+    '9784000000208',
     'description020',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:20.000',
@@ -214,7 +214,7 @@ VALUES
     '60CA7CF2-653B-48C0-B34B-754F077DD122',
     'title021',
     'author021',
-    'isbn021',
+    '9784000000215',
     'description021',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:21.000',
@@ -224,7 +224,7 @@ VALUES
     '0F7FE629-9951-4050-B6CB-3B6C93BBC78E',
     'title022',
     'author022',
-    'isbn022',
+    '9784000000222',
     'description022',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:22.000',
@@ -234,7 +234,7 @@ VALUES
     'C3311598-BF0B-4D6C-B925-A15B2F0D8318',
     'title023',
     'author023',
-    'isbn023',
+    '9784000000239',
     'description023',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:23.000',
@@ -244,7 +244,7 @@ VALUES
     'B2B11DA4-8B37-420D-81B1-7319FA899BAD',
     'title024',
     'author024',
-    'isbn024',
+    '9784000000246',
     'description024',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:24.000',
@@ -254,7 +254,7 @@ VALUES
     '3200E5A4-AC55-43B5-9567-62E6FF51A883',
     'title025',
     'author025',
-    'isbn025',
+    '9784000000253',
     'description025',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:25.000',
@@ -264,7 +264,7 @@ VALUES
     '6100FB74-F641-4B47-9E08-D5804B678792',
     'title026',
     'author026',
-    'isbn026',
+    '9784000000260',
     'description026',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:26.000',
@@ -274,7 +274,7 @@ VALUES
     'CCEDDA28-BDFF-4C58-9689-94CA0AC020D3',
     'title027',
     'author027',
-    'isbn027',
+    '9784000000277',
     'description027',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:27.000',
@@ -284,7 +284,7 @@ VALUES
     '0C3B4D5E-E7A3-446A-8430-1DB1DC475751',
     'title028',
     'author028',
-    'isbn028',
+    '9784000000284',
     'description028',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:28.000',
@@ -294,7 +294,7 @@ VALUES
     'F1D55D47-E36B-4F2C-991C-830344DA7357',
     'title029',
     'author029',
-    'isbn029',
+    '9784000000291',
     'description029',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:29.000',
@@ -304,7 +304,7 @@ VALUES
     '309F2BC5-2705-4C57-8385-BDA1D4DEFEC0',
     'title030',
     'author030',
-    'isbn030',
+    '9784000000307',
     'description030',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:30.000',
@@ -314,7 +314,7 @@ VALUES
     'DACEB024-C021-4E50-8845-F8825B0B8ED9',
     'title031',
     'author031',
-    'isbn031',
+    '9784000000314',
     'description031',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:31.000',
@@ -324,7 +324,7 @@ VALUES
     'A162FB6A-9A75-4EE9-A46F-5CD2F2455832',
     'title032',
     'author032',
-    'isbn032',
+    '9784000000321',
     'description032',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:32.000',
@@ -334,7 +334,7 @@ VALUES
     'BEF86414-26A2-46A9-8FE1-30E8E31F4833',
     'title033',
     'author033',
-    'isbn033',
+    '9784000000338',
     'description033',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:33.000',
@@ -344,7 +344,7 @@ VALUES
     '5B54E3C5-6ED0-4D9F-9059-6CE121106040',
     'title034',
     'author034',
-    'isbn034',
+    '9784000000345',
     'description034',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:34.000',
@@ -354,7 +354,7 @@ VALUES
     '3313E507-3E35-4F35-8DF1-248ED29A35A5',
     'title035',
     'author035',
-    'isbn035',
+    '9784000000352',
     'description035',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:35.000',
@@ -364,7 +364,7 @@ VALUES
     '1FE4D0E2-30B2-410F-9844-0777FB1E3967',
     'title036',
     'author036',
-    'isbn036',
+    '9784000000369',
     'description036',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:36.000',
@@ -374,7 +374,7 @@ VALUES
     '79412B83-5362-470F-A1A2-DC085C5D2877',
     'title037',
     'author037',
-    'isbn037',
+    '9784000000376',
     'description037',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:37.000',
@@ -384,7 +384,7 @@ VALUES
     '7FCC1134-DF5D-48CA-AB65-3205906FDA6F',
     'title038',
     'author038',
-    'isbn038',
+    '9784000000383',
     'description038',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:38.000',
@@ -394,7 +394,7 @@ VALUES
     '8646BEF7-827B-4EC1-9DAC-AAE2B97E6578',
     'title039',
     'author039',
-    'isbn039',
+    '9784000000390',
     'description039',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:39.000',
@@ -404,7 +404,7 @@ VALUES
     '0097CE09-8624-41F4-BB3A-7D5A38F61D4D',
     'title040',
     'author040',
-    'isbn040',
+    '9784000000406',
     'description040',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:40.000',
@@ -414,7 +414,7 @@ VALUES
     '22985A55-6127-447E-A289-8091C15DD90C',
     'title041',
     'author041',
-    'isbn041',
+    '9784000000413',
     'description041',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:41.000',
@@ -424,7 +424,7 @@ VALUES
     'DFEFA4EC-D6C3-41DC-AB9F-614B7E32CC1C',
     'title042',
     'author042',
-    'isbn042',
+    '9784000000420',
     'description042',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:42.000',
@@ -434,7 +434,7 @@ VALUES
     '0921E4A1-196A-47FD-ADA0-4CF16B8C1BE1',
     'title043',
     'author043',
-    'isbn043',
+    '9784000000437',
     'description043',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:43.000',
@@ -444,7 +444,7 @@ VALUES
     'F06F84A8-ACDB-4197-886D-A36927FAE260',
     'title044',
     'author044',
-    'isbn044',
+    '9784000000444',
     'description044',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:44.000',
@@ -454,7 +454,7 @@ VALUES
     '756CA1B1-29AF-4C68-A481-796EEB36636A',
     'title045',
     'author045',
-    'isbn045',
+    '9784000000451',
     'description045',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:45.000',
@@ -464,7 +464,7 @@ VALUES
     '289F2B5E-CC38-4957-81F6-F943AA5E9577',
     'title046',
     'author046',
-    'isbn046',
+    '9784000000468',
     'description046',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:46.000',
@@ -474,7 +474,7 @@ VALUES
     '0D5125E4-EE64-4660-B28F-10B8C3D6134E',
     'title047',
     'author047',
-    'isbn047',
+    '9784000000475',
     'description047',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:47.000',
@@ -484,7 +484,7 @@ VALUES
     '23432C7F-C95F-4FD6-BBDC-ACC1DF015F65',
     'title048',
     'author048',
-    'isbn048',
+    '9784000000482',
     'description048',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:48.000',
@@ -494,7 +494,7 @@ VALUES
     '226B5CA7-EC2E-4E17-A1BA-33EC69D3BD47',
     'title049',
     'author049',
-    'isbn049',
+    '9784000000499',
     'description049',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:49.000',
@@ -504,9 +504,14 @@ VALUES
     '35F4CAF4-52A8-4522-8770-D6CDBD30CC75',
     'title050',
     'author050',
-    'isbn050',
+    '9784000000505',
     'description050',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     '2023-12-01 01:00:50.000',
     '2023-12-01 01:00:50.000'
   ) ON CONFLICT DO NOTHING;
+
+-- 蔵書ごとに所蔵資料を 1 冊ずつ登録する
+INSERT INTO
+  book_copies (book_id, acquired_on)
+SELECT book_id, DATE(created_at) FROM books;
@@ -32,9 +32,25 @@ VALUES
     '9890736e-a4e4-461a-a77d-eac3517ef11b',
     '実践Rustプログラミング入門',
     '初田直也他',
-    '978-4798061702',
+    '9784798061702',
     'C/C++の代わりとなるべき最新言語その独特な仕様をわかりやすく解説。',
     '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c',
     now(),
     now()
   ) ON CONFLICT DO NOTHING;
+
+INSERT INTO
+  book_copies (copy_id, book_id, barcode, acquired_on)
+VALUES
+  (
+    '1e4b6a0c-2f5d-4c1e-9a57-0c1d3b9f2a01',
+    '9890736e-a4e4-461a-a77d-eac3517ef11b',
+    'BC0001',
+    '2024-04-01'
+  ),
+  (
+    '3d7f9b21-4c6e-4a8d-b2f3-6e1a0c9d5b04',
+    '9890736e-a4e4-461a-a77d-eac3517ef11b',
+    'BC0004',
+    '2024-05-01'
+  ) ON CONFLICT DO NOTHING;
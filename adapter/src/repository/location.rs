@@ -0,0 +1,329 @@
+use crate::database::{
+    model::location::{BranchRow, ShelfRow},
+    ConnectionPool,
+};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::{
+    id::{BranchId, ShelfId},
+    location::{
+        event::{CreateBranch, CreateShelf, DeleteBranch, DeleteShelf, UpdateBranch, UpdateShelf},
+        Branch, Shelf,
+    },
+};
+use kernel::repository::location::LocationRepository;
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct LocationRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl LocationRepository for LocationRepositoryImpl {
+    async fn create_branch(&self, event: CreateBranch) -> AppResult<Branch> {
+        let branch_id = BranchId::new();
+        sqlx::query!(
+            r#"
+                INSERT INTO branches (branch_id, name)
+                VALUES (?, ?)
+            "#,
+            branch_id as _,
+            event.name
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(map_branch_name_conflict)?;
+
+        Ok(Branch {
+            id: branch_id,
+            name: event.name,
+        })
+    }
+
+    async fn find_branches(&self) -> AppResult<Vec<Branch>> {
+        let rows = sqlx::query_as!(
+            BranchRow,
+            r#"
+                SELECT branch_id, name
+                FROM branches
+                ORDER BY name ASC
+            "#
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(rows.into_iter().map(Branch::from).collect())
+    }
+
+    async fn update_branch(&self, event: UpdateBranch) -> AppResult<()> {
+        // MySQL では名前が変わらない場合に変更行数が 0 になるため、存在の確認は別に行う
+        if !self.branch_exists(event.branch_id).await? {
+            return Err(AppError::EntityNotFound(
+                "specified branch not found".into(),
+            ));
+        }
+
+        sqlx::query!(
+            r#"
+                UPDATE branches
+                SET name = ?
+                WHERE branch_id = ?
+            "#,
+            event.name,
+            event.branch_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(map_branch_name_conflict)?;
+
+        Ok(())
+    }
+
+    async fn delete_branch(&self, event: DeleteBranch) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM branches WHERE branch_id = ?
+            "#,
+            event.branch_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                "specified branch not found".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn create_shelf(&self, event: CreateShelf) -> AppResult<Shelf> {
+        if !self.branch_exists(event.branch_id).await? {
+            return Err(AppError::EntityNotFound(
+                "specified branch not found".into(),
+            ));
+        }
+
+        let shelf_id = ShelfId::new();
+        sqlx::query!(
+            r#"
+                INSERT INTO shelves (shelf_id, branch_id, name)
+                VALUES (?, ?, ?)
+            "#,
+            shelf_id as _,
+            event.branch_id as _,
+            event.name
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(map_shelf_name_conflict)?;
+
+        Ok(Shelf {
+            id: shelf_id,
+            branch_id: event.branch_id,
+            name: event.name,
+        })
+    }
+
+    async fn find_shelves_by_branch_id(&self, branch_id: BranchId) -> AppResult<Vec<Shelf>> {
+        if !self.branch_exists(branch_id).await? {
+            return Err(AppError::EntityNotFound(
+                "specified branch not found".into(),
+            ));
+        }
+
+        let rows = sqlx::query_as!(
+            ShelfRow,
+            r#"
+                SELECT shelf_id, branch_id, name
+                FROM shelves
+                WHERE branch_id = ?
+                ORDER BY name ASC
+            "#,
+            branch_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(rows.into_iter().map(Shelf::from).collect())
+    }
+
+    async fn update_shelf(&self, event: UpdateShelf) -> AppResult<()> {
+        let exists = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS(
+                    SELECT 1 FROM shelves WHERE shelf_id = ? AND branch_id = ?
+                ) AS "exists: bool"
+            "#,
+            event.shelf_id as _,
+            event.branch_id as _
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if !exists {
+            return Err(AppError::EntityNotFound("specified shelf not found".into()));
+        }
+
+        sqlx::query!(
+            r#"
+                UPDATE shelves
+                SET name = ?
+                WHERE shelf_id = ?
+            "#,
+            event.name,
+            event.shelf_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(map_shelf_name_conflict)?;
+
+        Ok(())
+    }
+
+    async fn delete_shelf(&self, event: DeleteShelf) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM shelves WHERE shelf_id = ? AND branch_id = ?
+            "#,
+            event.shelf_id as _,
+            event.branch_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound("specified shelf not found".into()));
+        }
+
+        Ok(())
+    }
+}
+
+impl LocationRepositoryImpl {
+    async fn branch_exists(&self, branch_id: BranchId) -> AppResult<bool> {
+        sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS(
+                    SELECT 1 FROM branches WHERE branch_id = ?
+                ) AS "exists: bool"
+            "#,
+            branch_id as _
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)
+    }
+}
+
+// 館の名前の一意制約に違反した場合は、重複エラーとして返す
+fn map_branch_name_conflict(e: sqlx::Error) -> AppError {
+    match e.as_database_error() {
+        Some(db_error) if db_error.is_unique_violation() => {
+            AppError::Conflict("同じ名前の館がすでに登録されています".into())
+        }
+        _ => AppError::SpecificOperationError(e),
+    }
+}
+
+// 同じ館の書架の名前の一意制約に違反した場合は、重複エラーとして返す
+fn map_shelf_name_conflict(e: sqlx::Error) -> AppError {
+    match e.as_database_error() {
+        Some(db_error) if db_error.is_unique_violation() => {
+            AppError::Conflict("同じ名前の書架がこの館にすでに登録されています".into())
+        }
+        _ => AppError::SpecificOperationError(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    async fn test_branch_and_shelf_crud(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let repo = LocationRepositoryImpl::new(ConnectionPool::new(pool));
+
+        let main = repo
+            .create_branch(CreateBranch {
+                name: "本館".into(),
+            })
+            .await?;
+        let annex = repo
+            .create_branch(CreateBranch {
+                name: "別館".into(),
+            })
+            .await?;
+        let res = repo
+            .create_branch(CreateBranch {
+                name: "本館".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+        assert_eq!(repo.find_branches().await?.len(), 2);
+
+        let shelf = repo
+            .create_shelf(CreateShelf {
+                branch_id: main.id,
+                name: "A-1".into(),
+            })
+            .await?;
+        // 別の館であれば同じ名前の書架を登録できる
+        repo.create_shelf(CreateShelf {
+            branch_id: annex.id,
+            name: "A-1".into(),
+        })
+        .await?;
+        let res = repo
+            .create_shelf(CreateShelf {
+                branch_id: main.id,
+                name: "A-1".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+        let res = repo
+            .create_shelf(CreateShelf {
+                branch_id: BranchId::new(),
+                name: "A-1".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        repo.update_shelf(UpdateShelf {
+            shelf_id: shelf.id,
+            branch_id: main.id,
+            name: "A-2".into(),
+        })
+        .await?;
+        assert_eq!(
+            repo.find_shelves_by_branch_id(main.id).await?,
+            vec![Shelf {
+                name: "A-2".into(),
+                ..shelf.clone()
+            }]
+        );
+        // 別の館の書架としては更新・削除できない
+        let res = repo
+            .delete_shelf(DeleteShelf {
+                shelf_id: shelf.id,
+                branch_id: annex.id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        // 館を削除すると、その館の書架もあわせて削除される
+        repo.delete_branch(DeleteBranch { branch_id: main.id })
+            .await?;
+        assert_eq!(repo.find_branches().await?, vec![annex]);
+        let res = repo.find_shelves_by_branch_id(main.id).await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        Ok(())
+    }
+}
@@ -1,5 +1,11 @@
 pub mod auth;
 pub mod book;
 pub mod checkout;
+pub mod copy;
+pub mod fine;
 pub mod health;
+pub mod location;
+pub mod report;
+pub mod reservation;
+pub mod tag;
 pub mod user;
@@ -0,0 +1,421 @@
+use crate::database::{
+    model::report::{BookLoanCountRow, LoanStatsRow, OverdueLoanRow, ReturnStatsRow},
+    ConnectionPool,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use derive_new::new;
+use kernel::model::report::{BookLoanCount, CirculationStats, ReportGrouping, ReportOptions};
+use kernel::repository::report::ReportRepository;
+use shared::error::{AppError, AppResult};
+use sqlx::QueryBuilder;
+use std::collections::BTreeMap;
+
+#[derive(new)]
+pub struct ReportRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl ReportRepository for ReportRepositoryImpl {
+    async fn find_circulation_stats(
+        &self,
+        options: ReportOptions,
+    ) -> AppResult<Vec<CirculationStats>> {
+        let ReportOptions { from, to, grouping } = options;
+        let (from_at, to_at) = (start_of_day(from), start_of_day(to));
+
+        // 貸出は、貸出中・返却済みの両方を合わせて貸出日で集計する
+        let mut query = QueryBuilder::new("SELECT ");
+        query
+            .push(period_start_expr(grouping, "h.checked_out_at"))
+            .push(
+                r#"
+                    AS period_start,
+                    COUNT(*) AS loans,
+                    COUNT(DISTINCT h.user_id) AS active_borrowers
+                    FROM (
+                        SELECT user_id, checked_out_at FROM checkouts
+                        UNION ALL
+                        SELECT user_id, checked_out_at FROM returned_checkouts
+                    ) AS h
+                    WHERE h.checked_out_at >= 
+                "#,
+            )
+            .push_bind(from_at)
+            .push(" AND h.checked_out_at < ")
+            .push_bind(to_at)
+            .push(" GROUP BY period_start");
+        let loan_rows: Vec<LoanStatsRow> = query
+            .build_query_as()
+            .fetch_all(self.db.inner_ref())
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+
+        // 返却は返却日で集計し、延滞の有無と貸出日数もあわせて求める
+        let mut query = QueryBuilder::new("SELECT ");
+        query
+            .push(period_start_expr(grouping, "r.returned_at"))
+            .push(
+                r#"
+                    AS period_start,
+                    COUNT(*) AS returns,
+                    CAST(SUM(r.returned_at > r.due_at) AS SIGNED) AS overdue_returns,
+                    CAST(
+                        AVG(TIMESTAMPDIFF(SECOND, r.checked_out_at, r.returned_at)) / 86400
+                        AS DOUBLE
+                    ) AS average_loan_days
+                    FROM returned_checkouts AS r
+                    WHERE r.returned_at >= 
+                "#,
+            )
+            .push_bind(from_at)
+            .push(" AND r.returned_at < ")
+            .push_bind(to_at)
+            .push(" GROUP BY period_start");
+        let return_rows: Vec<ReturnStatsRow> = query
+            .build_query_as()
+            .fetch_all(self.db.inner_ref())
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+
+        // 期間の終わりの時点で延滞しているかは期間ごとに異なるため、
+        // 範囲のいずれかの時点で延滞していた貸出を取得し、期間ごとの数は後で数える
+        // 期間が終わっていない場合は、現在の時点で延滞しているかどうかとする
+        let until = to_at.min(Utc::now());
+        let mut query = QueryBuilder::new(
+            r#"
+                SELECT due_at, returned_at FROM returned_checkouts
+                WHERE returned_at > due_at
+                AND due_at < 
+            "#,
+        );
+        query
+            .push_bind(until)
+            .push(" AND returned_at > ")
+            .push_bind(from_at)
+            .push(
+                r#"
+                    UNION ALL
+                    SELECT due_at, NULL AS returned_at FROM checkouts
+                    WHERE due_at < 
+                "#,
+            )
+            .push_bind(until);
+        let overdue_rows: Vec<OverdueLoanRow> = query
+            .build_query_as()
+            .fetch_all(self.db.inner_ref())
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+
+        // 貸出・返却のない期間も 0 件として返す
+        let mut stats = grouping
+            .periods(from, to)
+            .into_iter()
+            .map(|period_start| {
+                let mut entry = CirculationStats::empty(period_start);
+                let period_end = start_of_day(grouping.next_period_start(period_start)).min(until);
+                // まだ始まっていない期間では数えない
+                if period_end > start_of_day(period_start) {
+                    entry.overdue_outstanding = overdue_rows
+                        .iter()
+                        .filter(|row| {
+                            row.due_at < period_end
+                                && row.returned_at.map_or(true, |at| at >= period_end)
+                        })
+                        .count() as i64;
+                }
+                (period_start, entry)
+            })
+            .collect::<BTreeMap<_, _>>();
+        for row in loan_rows {
+            let entry = stats
+                .entry(row.period_start)
+                .or_insert_with(|| CirculationStats::empty(row.period_start));
+            entry.loans = row.loans;
+            entry.active_borrowers = row.active_borrowers;
+        }
+        for row in return_rows {
+            let entry = stats
+                .entry(row.period_start)
+                .or_insert_with(|| CirculationStats::empty(row.period_start));
+            entry.returns = row.returns;
+            entry.overdue_returns = row.overdue_returns;
+            entry.average_loan_days = row.average_loan_days;
+        }
+
+        Ok(stats.into_values().collect())
+    }
+
+    async fn find_most_borrowed(
+        &self,
+        options: ReportOptions,
+        limit: i64,
+    ) -> AppResult<Vec<BookLoanCount>> {
+        let ReportOptions { from, to, grouping } = options;
+        let period_start = period_start_expr(grouping, "h.checked_out_at");
+
+        // 期間ごとに貸出回数の多い順で順位をつけ、上位 limit 件を取り出す
+        // 貸出回数が同じ場合は蔵書 ID 順とし、結果が毎回同じになるようにする
+        // 完全に削除された蔵書も順位から外さず、返却済みの貸出に記録した蔵書の情報を使う
+        let mut query = QueryBuilder::new(
+            r#"
+                SELECT
+                t.period_start,
+                t.book_id,
+                COALESCE(b.title, t.book_title) AS title,
+                COALESCE(b.author, t.book_author) AS author,
+                t.loans
+                FROM (
+                    SELECT
+            "#,
+        );
+        query
+            .push(&period_start)
+            .push(
+                r#"
+                    AS period_start,
+                    h.book_id,
+                    MAX(h.book_title) AS book_title,
+                    MAX(h.book_author) AS book_author,
+                    COUNT(*) AS loans,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY 
+                "#,
+            )
+            .push(&period_start)
+            .push(
+                r#"
+                        ORDER BY COUNT(*) DESC, h.book_id
+                    ) AS rank_in_period
+                    FROM (
+                        SELECT
+                            book_id,
+                            checked_out_at,
+                            NULL AS book_title,
+                            NULL AS book_author
+                        FROM checkouts
+                        UNION ALL
+                        SELECT book_id, checked_out_at, book_title, book_author
+                        FROM returned_checkouts
+                    ) AS h
+                    WHERE h.checked_out_at >= 
+                "#,
+            )
+            .push_bind(start_of_day(from))
+            .push(" AND h.checked_out_at < ")
+            .push_bind(start_of_day(to))
+            .push(
+                r#"
+                    GROUP BY period_start, h.book_id
+                ) AS t
+                LEFT OUTER JOIN books AS b USING(book_id)
+                WHERE t.rank_in_period <= 
+                "#,
+            )
+            .push_bind(limit)
+            .push(" ORDER BY t.period_start ASC, t.rank_in_period ASC");
+        let rows: Vec<BookLoanCountRow> = query
+            .build_query_as()
+            .fetch_all(self.db.inner_ref())
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+
+        Ok(rows.into_iter().map(BookLoanCount::from).collect())
+    }
+}
+
+// 日付の 0 時（UTC）を返す
+fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    date.and_time(NaiveTime::MIN).and_utc()
+}
+
+// 日時のカラムから、それを含む期間の初日を求める SQL の式を返す
+// ReportGrouping::period_start と同じく、週は月曜日始まりとする
+fn period_start_expr(grouping: ReportGrouping, column: &str) -> String {
+    match grouping {
+        ReportGrouping::Day => format!("DATE({column})"),
+        ReportGrouping::Week => format!("DATE({column}) - INTERVAL WEEKDAY({column}) DAY"),
+        ReportGrouping::Month => {
+            format!("DATE({column}) - INTERVAL (DAYOFMONTH({column}) - 1) DAY")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::{book::BookRepositoryImpl, checkout::CheckoutRepositoryImpl};
+    use chrono::Duration;
+    use kernel::model::{
+        book::event::{DeleteBook, PurgeBook},
+        checkout::event::{CreateCheckout, UpdateReturned},
+        id::{BookId, UserId},
+    };
+    use kernel::repository::{book::BookRepository, checkout::CheckoutRepository};
+    use shared::config::{CheckoutConfig, FineConfig, IsbnUniqueness, ReservationConfig};
+    use std::str::FromStr;
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_report(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let repo = ReportRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let checkout_repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            CheckoutConfig::default(),
+            ReservationConfig {
+                pickup_window_hours: 72,
+            },
+            FineConfig::default(),
+        );
+        let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let user_id2 = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5")?;
+        let book_id1 = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let at = |d: NaiveDate| {
+            d.and_time(NaiveTime::from_hms_opt(10, 0, 0).unwrap())
+                .and_utc()
+        };
+
+        // 1 月に 2 人がそれぞれ借りて、1 人は返却期限内に、もう 1 人は延滞して 2 月に返却する
+        // 3 月に 1 人が借りて、まだ返却していない
+        for (user_id, checked_out_on, returned_after) in [
+            (user_id1, date(2024, 1, 10), Some(Duration::days(4))),
+            (user_id2, date(2024, 1, 25), Some(Duration::days(30))),
+            (user_id1, date(2024, 3, 5), None),
+        ] {
+            checkout_repo
+                .create(CreateCheckout::new(
+                    book_id1,
+                    None,
+                    user_id,
+                    at(checked_out_on),
+                ))
+                .await?;
+            if let Some(returned_after) = returned_after {
+                let co = checkout_repo.find_unreturned_by_user_id(user_id).await?;
+                checkout_repo
+                    .update_returned(UpdateReturned::new(
+                        co[0].id,
+                        book_id1,
+                        user_id,
+                        at(checked_out_on) + returned_after,
+                    ))
+                    .await?;
+            }
+        }
+
+        let options = ReportOptions {
+            from: date(2024, 1, 1),
+            to: date(2024, 4, 1),
+            grouping: ReportGrouping::Month,
+        };
+        let stats = repo.find_circulation_stats(options.clone()).await?;
+        assert_eq!(
+            stats.iter().map(|s| s.period_start).collect::<Vec<_>>(),
+            vec![date(2024, 1, 1), date(2024, 2, 1), date(2024, 3, 1)]
+        );
+        let (jan, feb, mar) = (&stats[0], &stats[1], &stats[2]);
+        assert_eq!((jan.loans, jan.active_borrowers, jan.returns), (2, 2, 1));
+        assert_eq!(jan.overdue_returns, 0);
+        assert_eq!(jan.average_loan_days, Some(4.0));
+        assert_eq!((feb.loans, feb.returns, feb.overdue_returns), (0, 1, 1));
+        assert_eq!(feb.overdue_rate(), Some(1.0));
+        assert_eq!((mar.loans, mar.returns), (1, 0));
+        assert_eq!(mar.average_loan_days, None);
+        // 3 月の貸出は返却期限を過ぎても返却されていないため、返却がなくても延滞として数える
+        // 2 月に延滞していた貸出は月内に返却されたため、2 月の終わりの時点では数えない
+        assert_eq!((jan.overdue_outstanding, feb.overdue_outstanding), (0, 0));
+        assert_eq!(mar.overdue_outstanding, 1);
+        assert_eq!(mar.overdue_rate(), Some(1.0));
+
+        // 週単位では、貸出・返却のない週も含めて返す
+        let stats = repo
+            .find_circulation_stats(ReportOptions {
+                from: date(2024, 1, 8),
+                to: date(2024, 1, 22),
+                grouping: ReportGrouping::Week,
+            })
+            .await?;
+        assert_eq!(stats.len(), 2);
+        assert_eq!((stats[0].loans, stats[0].returns), (1, 1));
+        assert_eq!((stats[1].loans, stats[1].returns), (0, 0));
+
+        let ranking = repo.find_most_borrowed(options, 10).await?;
+        assert_eq!(ranking.len(), 2);
+        assert_eq!(ranking[0].period_start, date(2024, 1, 1));
+        assert_eq!(ranking[0].book_id, book_id1);
+        assert_eq!(ranking[0].loans, 2);
+        assert_eq!(ranking[1].period_start, date(2024, 3, 1));
+        assert_eq!(ranking[1].loans, 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_most_borrowed_purged_book(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let repo = ReportRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let book_repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), IsbnUniqueness::Disabled);
+        let checkout_repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            CheckoutConfig::default(),
+            ReservationConfig {
+                pickup_window_hours: 72,
+            },
+            FineConfig::default(),
+        );
+        let owner_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let user_id = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let checked_out_at = date(2024, 1, 10)
+            .and_time(NaiveTime::from_hms_opt(10, 0, 0).unwrap())
+            .and_utc();
+
+        // 1 月に借りて返却した後、蔵書を完全に削除する
+        checkout_repo
+            .create(CreateCheckout::new(book_id, None, user_id, checked_out_at))
+            .await?;
+        let co = checkout_repo.find_unreturned_by_user_id(user_id).await?;
+        checkout_repo
+            .update_returned(UpdateReturned::new(
+                co[0].id,
+                book_id,
+                user_id,
+                checked_out_at + Duration::days(3),
+            ))
+            .await?;
+        book_repo
+            .delete(DeleteBook {
+                book_id,
+                requested_user: owner_id,
+                expected_version: None,
+            })
+            .await?;
+        book_repo
+            .purge(PurgeBook {
+                book_id,
+                requested_user: owner_id,
+            })
+            .await?;
+
+        // 完全に削除された蔵書も、返却済みの貸出に記録した蔵書の情報とともに順位に含める
+        let ranking = repo
+            .find_most_borrowed(
+                ReportOptions {
+                    from: date(2024, 1, 1),
+                    to: date(2024, 2, 1),
+                    grouping: ReportGrouping::Month,
+                },
+                10,
+            )
+            .await?;
+        assert_eq!(ranking.len(), 1);
+        assert_eq!(ranking[0].book_id, book_id);
+        assert_eq!(ranking[0].title, "実践Rustプログラミング入門");
+        assert_eq!(ranking[0].author, "初田直也他");
+        assert_eq!(ranking[0].loans, 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,422 @@
+use crate::database::{
+    model::reservation::{HoldStateRow, ReservationRow},
+    ConnectionPool,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use derive_new::new;
+use kernel::model::{
+    id::{BookId, ReservationId, UserId},
+    reservation::{
+        event::{CreateReservation, DeleteReservation},
+        Reservation,
+    },
+};
+use kernel::repository::reservation::ReservationRepository;
+use shared::config::ReservationConfig;
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct ReservationRepositoryImpl {
+    db: ConnectionPool,
+    config: ReservationConfig,
+}
+
+#[async_trait]
+impl ReservationRepository for ReservationRepositoryImpl {
+    // 蔵書を予約する
+    async fn create(&self, event: CreateReservation) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // 削除済みの蔵書は予約できない
+        let deleted = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS(
+                    SELECT 1 FROM books WHERE book_id = ? AND deleted_at IS NOT NULL
+                ) AS "deleted: bool"
+            "#,
+            event.book_id as _
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if deleted {
+            return Err(AppError::EntityNotFound(format!(
+                " 書籍（{}）が見つかりませんでした。",
+                event.book_id
+            )));
+        }
+
+        // 予約する前に、取り置きの状態を最新にしておく
+        let state = assign_holds(&mut tx, event.book_id, event.reserved_at, &self.config).await?;
+
+        // 取り置き中のもの以外に貸出可能な所蔵資料がある場合は、予約せずに借りればよい
+        if state.available_copies > state.ready_reservations {
+            return Err(AppError::UnprocessableEntity(format!(
+                " 書籍（{}）には貸出可能な所蔵資料があるため予約できません。",
+                event.book_id
+            )));
+        }
+
+        // 自分が借りている蔵書は予約できない
+        let checked_out = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS(
+                    SELECT 1 FROM checkouts WHERE book_id = ? AND user_id = ?
+                ) AS "checked_out: bool"
+            "#,
+            event.book_id as _,
+            event.reserved_by as _
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if checked_out {
+            return Err(AppError::UnprocessableEntity(format!(
+                " 書籍（{}）は貸出中のため予約できません。",
+                event.book_id
+            )));
+        }
+
+        sqlx::query!(
+            r#"
+                INSERT INTO reservations (reservation_id, book_id, user_id, reserved_at)
+                VALUES (?, ?, ?, ?)
+            "#,
+            ReservationId::new() as _,
+            event.book_id as _,
+            event.reserved_by as _,
+            event.reserved_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            // 同じユーザーは同じ蔵書を重複して予約できない
+            Some(db_error) if db_error.is_unique_violation() => {
+                AppError::Conflict(format!(" 書籍（{}）はすでに予約済みです。", event.book_id))
+            }
+            _ => AppError::SpecificOperationError(e),
+        })?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    // 蔵書に対する予約を、予約の順番に取得する
+    async fn find_by_book_id(&self, book_id: BookId) -> AppResult<Vec<Reservation>> {
+        // 受け取りの期限を過ぎた取り置きを次の予約に移してから取得する
+        let mut tx = self.db.begin().await?;
+        assign_holds(&mut tx, book_id, Utc::now(), &self.config).await?;
+
+        let rows = sqlx::query_as!(
+            ReservationRow,
+            r#"
+                SELECT
+                reservation_id AS "reservation_id: ReservationId",
+                book_id AS "book_id: BookId",
+                user_id AS "user_id: UserId",
+                reserved_at,
+                ready_until
+                FROM reservations
+                WHERE book_id = ?
+                ORDER BY reserved_at ASC, reservation_id ASC
+            "#,
+            book_id as _
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(rows
+            .into_iter()
+            .zip(1..)
+            .map(|(row, position)| row.into_reservation(position))
+            .collect())
+    }
+
+    // 予約を取り消す
+    async fn delete(&self, event: DeleteReservation) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // 予約したユーザーのみ取り消せる
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM reservations
+                WHERE reservation_id = ? AND book_id = ? AND user_id = ?
+            "#,
+            event.reservation_id as _,
+            event.book_id as _,
+            event.requested_user as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(format!(
+                " 予約（{}）が見つかりませんでした。",
+                event.reservation_id
+            )));
+        }
+
+        // 取り置き中の予約を取り消した場合は、次の予約に取り置きを移す
+        assign_holds(&mut tx, event.book_id, event.deleted_at, &self.config).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+}
+
+// 貸出中でない所蔵資料を、予約の先頭から順に取り置く
+// 受け取りの期限を過ぎた予約は取り消し、その所蔵資料は次の予約のために取り置く
+// 予約・貸出・返却の各操作のトランザクション内で呼び出し、取り置きの状態を最新にする
+pub(crate) async fn assign_holds(
+    tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    book_id: BookId,
+    now: DateTime<Utc>,
+    config: &ReservationConfig,
+) -> AppResult<HoldStateRow> {
+    sqlx::query!(
+        r#"
+            DELETE FROM reservations
+            WHERE book_id = ? AND ready_until < ?
+        "#,
+        book_id as _,
+        now
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    let state = sqlx::query_as!(
+        HoldStateRow,
+        r#"
+            SELECT
+            (
+                SELECT COUNT(*)
+                FROM book_copies AS bc
+                LEFT OUTER JOIN checkouts AS c ON c.copy_id = bc.copy_id
+                WHERE bc.book_id = b.book_id
+                AND bc.copy_condition <> 'Lost'
+                AND c.checkout_id IS NULL
+            ) AS "available_copies!: i64",
+            (
+                SELECT COUNT(*)
+                FROM reservations AS r
+                WHERE r.book_id = b.book_id AND r.ready_until IS NOT NULL
+            ) AS "ready_reservations!: i64"
+            FROM books AS b
+            WHERE b.book_id = ?
+        "#,
+        book_id as _
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?
+    .ok_or_else(|| {
+        AppError::EntityNotFound(format!(" 書籍（{}）が見つかりませんでした。", book_id))
+    })?;
+
+    let unassigned = state.available_copies - state.ready_reservations;
+    if unassigned <= 0 {
+        return Ok(state);
+    }
+
+    let res = sqlx::query!(
+        r#"
+            UPDATE reservations
+            SET ready_until = ?
+            WHERE book_id = ? AND ready_until IS NULL
+            ORDER BY reserved_at ASC, reservation_id ASC
+            LIMIT ?
+        "#,
+        now + Duration::hours(config.pickup_window_hours),
+        book_id as _,
+        unassigned
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    Ok(HoldStateRow {
+        ready_reservations: state.ready_reservations + res.rows_affected() as i64,
+        ..state
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::checkout::CheckoutRepositoryImpl;
+    use kernel::model::checkout::event::{CreateCheckout, UpdateReturned};
+    use kernel::repository::checkout::CheckoutRepository;
+    use shared::config::{CheckoutConfig, FineConfig};
+    use std::str::FromStr;
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_reservation_queue(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let config = ReservationConfig {
+            pickup_window_hours: 72,
+        };
+        let repo = ReservationRepositoryImpl::new(ConnectionPool::new(pool.clone()), config);
+        let checkout_repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            CheckoutConfig::default(),
+            config,
+            FineConfig::default(),
+        );
+
+        // 事前登録したユーザー＆蔵書のID（fixtures/common.sql, fixtures/checkout.sql参照）
+        let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let user_id2 = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5")?;
+        let user_id3 = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book_id1 = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+
+        // 貸出可能な所蔵資料がある間は予約できない
+        let res = repo
+            .create(CreateReservation::new(book_id1, user_id2, Utc::now()))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // user_id1 が 2 冊とも借りる
+        for _ in 0..2 {
+            checkout_repo
+                .create(CreateCheckout::new(book_id1, None, user_id1, Utc::now()))
+                .await?;
+        }
+
+        // 自分が借りている蔵書は予約できない
+        let res = repo
+            .create(CreateReservation::new(book_id1, user_id1, Utc::now()))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 予約した順に並ぶ
+        repo.create(CreateReservation::new(book_id1, user_id2, Utc::now()))
+            .await?;
+        repo.create(CreateReservation::new(book_id1, user_id3, Utc::now()))
+            .await?;
+        let res = repo
+            .create(CreateReservation::new(book_id1, user_id2, Utc::now()))
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        let reservations = repo.find_by_book_id(book_id1).await?;
+        assert_eq!(reservations.len(), 2);
+        assert_eq!(reservations[0].reserved_by, user_id2);
+        assert_eq!(reservations[0].position, 1);
+        assert!(reservations[0].ready_until.is_none());
+        assert_eq!(reservations[1].reserved_by, user_id3);
+        assert_eq!(reservations[1].position, 2);
+
+        // 1 冊返却されると、予約の先頭の user_id2 のために取り置かれる
+        let checkouts = checkout_repo.find_unreturned_by_user_id(user_id1).await?;
+        checkout_repo
+            .update_returned(UpdateReturned::new(
+                checkouts[0].id,
+                book_id1,
+                user_id1,
+                Utc::now(),
+            ))
+            .await?;
+        let reservations = repo.find_by_book_id(book_id1).await?;
+        assert!(reservations[0].ready_until.is_some());
+        assert!(reservations[1].ready_until.is_none());
+
+        // 取り置きの期間中は、予約の先頭のユーザー以外は借りられない
+        let res = checkout_repo
+            .create(CreateCheckout::new(book_id1, None, user_id3, Utc::now()))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 予約の先頭のユーザーが借りると、その予約はなくなる
+        checkout_repo
+            .create(CreateCheckout::new(book_id1, None, user_id2, Utc::now()))
+            .await?;
+        let reservations = repo.find_by_book_id(book_id1).await?;
+        assert_eq!(reservations.len(), 1);
+        assert_eq!(reservations[0].reserved_by, user_id3);
+        assert_eq!(reservations[0].position, 1);
+
+        // 予約したユーザー以外は取り消せない
+        let res = repo
+            .delete(DeleteReservation::new(
+                reservations[0].id,
+                book_id1,
+                user_id2,
+                Utc::now(),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+        repo.delete(DeleteReservation::new(
+            reservations[0].id,
+            book_id1,
+            user_id3,
+            Utc::now(),
+        ))
+        .await?;
+        assert!(repo.find_by_book_id(book_id1).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_reservation_pickup_window(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let config = ReservationConfig {
+            pickup_window_hours: 72,
+        };
+        let repo = ReservationRepositoryImpl::new(ConnectionPool::new(pool.clone()), config);
+        let checkout_repo = CheckoutRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            CheckoutConfig::default(),
+            config,
+            FineConfig::default(),
+        );
+
+        let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let user_id2 = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5")?;
+        let user_id3 = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book_id1 = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+
+        for _ in 0..2 {
+            checkout_repo
+                .create(CreateCheckout::new(book_id1, None, user_id1, Utc::now()))
+                .await?;
+        }
+        repo.create(CreateReservation::new(book_id1, user_id2, Utc::now()))
+            .await?;
+        repo.create(CreateReservation::new(book_id1, user_id3, Utc::now()))
+            .await?;
+
+        // 4 日前に返却されたことにすると、user_id2 の取り置きは期限を過ぎている
+        let checkouts = checkout_repo.find_unreturned_by_user_id(user_id1).await?;
+        checkout_repo
+            .update_returned(UpdateReturned::new(
+                checkouts[0].id,
+                book_id1,
+                user_id1,
+                Utc::now() - Duration::days(4),
+            ))
+            .await?;
+
+        // 期限を過ぎた予約は取り消され、次の user_id3 に取り置きが移る
+        let reservations = repo.find_by_book_id(book_id1).await?;
+        assert_eq!(reservations.len(), 1);
+        assert_eq!(reservations[0].reserved_by, user_id3);
+        assert!(reservations[0].ready_until.is_some());
+
+        let res = checkout_repo
+            .create(CreateCheckout::new(book_id1, None, user_id2, Utc::now()))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        checkout_repo
+            .create(CreateCheckout::new(book_id1, None, user_id3, Utc::now()))
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,180 @@
+use crate::database::{model::tag::TagRow, ConnectionPool};
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::{
+    id::TagId,
+    tag::{
+        event::{CreateTag, DeleteTag, UpdateTag},
+        Tag,
+    },
+};
+use kernel::repository::tag::TagRepository;
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct TagRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl TagRepository for TagRepositoryImpl {
+    async fn create(&self, event: CreateTag) -> AppResult<Tag> {
+        let tag_id = TagId::new();
+        sqlx::query!(
+            r#"
+                INSERT INTO tags (tag_id, name)
+                VALUES (?, ?)
+            "#,
+            tag_id as _,
+            event.name
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(map_name_conflict)?;
+
+        Ok(Tag {
+            id: tag_id,
+            name: event.name,
+        })
+    }
+
+    async fn find_all(&self) -> AppResult<Vec<Tag>> {
+        let rows = sqlx::query_as!(
+            TagRow,
+            r#"
+                SELECT tag_id, name
+                FROM tags
+                ORDER BY name ASC
+            "#
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(rows.into_iter().map(Tag::from).collect())
+    }
+
+    async fn find_by_id(&self, tag_id: TagId) -> AppResult<Option<Tag>> {
+        let row = sqlx::query_as!(
+            TagRow,
+            r#"
+                SELECT tag_id, name
+                FROM tags
+                WHERE tag_id = ?
+            "#,
+            tag_id as _
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(row.map(Tag::from))
+    }
+
+    async fn update(&self, event: UpdateTag) -> AppResult<()> {
+        // MySQL では名前が変わらない場合に変更行数が 0 になるため、存在の確認は別に行う
+        if self.find_by_id(event.tag_id).await?.is_none() {
+            return Err(AppError::EntityNotFound("specified tag not found".into()));
+        }
+
+        sqlx::query!(
+            r#"
+                UPDATE tags
+                SET name = ?
+                WHERE tag_id = ?
+            "#,
+            event.name,
+            event.tag_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(map_name_conflict)?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, event: DeleteTag) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM tags WHERE tag_id = ?
+            "#,
+            event.tag_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound("specified tag not found".into()));
+        }
+
+        Ok(())
+    }
+}
+
+// タグの名前の一意制約に違反した場合は、重複エラーとして返す
+fn map_name_conflict(e: sqlx::Error) -> AppError {
+    match e.as_database_error() {
+        Some(db_error) if db_error.is_unique_violation() => {
+            AppError::Conflict("同じ名前のタグがすでに登録されています".into())
+        }
+        _ => AppError::SpecificOperationError(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    async fn test_tag_crud(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let repo = TagRepositoryImpl::new(ConnectionPool::new(pool));
+
+        let rust = repo
+            .create(CreateTag {
+                name: "Rust".into(),
+            })
+            .await?;
+        let db = repo
+            .create(CreateTag {
+                name: "データベース".into(),
+            })
+            .await?;
+
+        // 同じ名前のタグは登録できない
+        let res = repo
+            .create(CreateTag {
+                name: "Rust".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        // 名前の順に取得できる
+        let tags = repo.find_all().await?;
+        assert_eq!(tags, vec![rust.clone(), db.clone()]);
+
+        repo.update(UpdateTag {
+            tag_id: rust.id,
+            name: "Rust 言語".into(),
+        })
+        .await?;
+        let tag = repo.find_by_id(rust.id).await?.unwrap();
+        assert_eq!(tag.name, "Rust 言語");
+
+        // 他のタグと同じ名前には変更できない
+        let res = repo
+            .update(UpdateTag {
+                tag_id: rust.id,
+                name: "データベース".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        repo.delete(DeleteTag { tag_id: db.id }).await?;
+        assert!(repo.find_by_id(db.id).await?.is_none());
+        let res = repo.delete(DeleteTag { tag_id: db.id }).await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        Ok(())
+    }
+}
@@ -1,14 +1,15 @@
-use crate::database::{model::user::UserRow, ConnectionPool};
+use crate::database::{model::user::UserRow, version::ensure_version, ConnectionPool};
 use async_trait::async_trait;
 use derive_new::new;
 use kernel::model::id::UserId;
 use kernel::model::role::Role;
 use kernel::model::user::{
-    event::{CreateUser, DeleteUser, UpdateUserPassword, UpdateUserRole},
+    event::{CreateUser, DeleteUser, PatchUser, UpdateUserPassword, UpdateUserRole},
     User,
 };
 use kernel::repository::user::UserRepository;
 use shared::error::{AppError, AppResult};
+use sqlx::{MySql, QueryBuilder};
 
 #[derive(new)]
 pub struct UserRepositoryImpl {
@@ -26,6 +27,7 @@ impl UserRepository for UserRepositoryImpl {
                 u.name,
                 u.email,
                 r.name as role_name,
+                u.version,
                 u.created_at,
                 u.updated_at
                 FROM users AS u
@@ -52,6 +54,7 @@ impl UserRepository for UserRepositoryImpl {
                     u.name,
                     u.email,
                     r.name as role_name,
+                    u.version,
                     u.created_at,
                     u.updated_at
                 FROM users AS u
@@ -97,6 +100,7 @@ impl UserRepository for UserRepositoryImpl {
             name: event.name,
             email: event.email,
             role,
+            version: 1,
         })
     }
 
@@ -131,44 +135,117 @@ impl UserRepository for UserRepositoryImpl {
     }
 
     async fn update_role(&self, event: UpdateUserRole) -> AppResult<()> {
-        let res = sqlx::query!(
+        let mut tx = self.db.begin().await?;
+        let version = self
+            .lock_user_version(&mut tx, event.user_id)
+            .await?
+            .ok_or_else(|| AppError::EntityNotFound("Specified user not found".into()))?;
+        ensure_version(version, event.expected_version)?;
+        sqlx::query!(
             r#"
                 UPDATE users
-                SET role_id = (
-                   SELECT role_id FROM roles WHERE name = ? /* ★修正: $2 を ? に置換 */
-                )
-                WHERE user_id = ? /* ★修正: $1 を ? に置換 */
+                SET
+                    role_id = (
+                        SELECT role_id FROM roles WHERE name = ?
+                    ),
+                    version = version + 1
+                WHERE user_id = ?
             "#,
-            event.user_id as _,
-            event.role.as_ref()
+            event.role.as_ref(),
+            event.user_id as _
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
-        if res.rows_affected() < 1 {
-            return Err(AppError::EntityNotFound("Specified user not found".into()));
+        tx.commit().await.map_err(AppError::TransactionError)?;
+        Ok(())
+    }
+
+    async fn patch(&self, event: PatchUser) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        let version = self
+            .lock_user_version(&mut tx, event.user_id)
+            .await?
+            .ok_or_else(|| AppError::EntityNotFound("Specified user not found".into()))?;
+        ensure_version(version, event.expected_version)?;
+
+        // 更新する項目がなければ、ユーザーの存在とバージョンの確認だけを行う
+        if event.name.is_none() && event.email.is_none() {
+            return Ok(());
         }
+
+        // 指定された項目の列だけを更新する
+        let mut query = QueryBuilder::<MySql>::new("UPDATE users SET ");
+        let mut columns = query.separated(", ");
+        if let Some(name) = &event.name {
+            columns.push("name = ").push_bind_unseparated(name);
+        }
+        if let Some(email) = &event.email {
+            columns.push("email = ").push_bind_unseparated(email);
+        }
+        columns.push("version = version + 1");
+        query.push(" WHERE user_id = ").push_bind(event.user_id);
+        query
+            .build()
+            .execute(&mut *tx)
+            .await
+            .map_err(map_email_conflict)?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
         Ok(())
     }
 
     async fn delete(&self, event: DeleteUser) -> AppResult<()> {
-        let res = sqlx::query!(
+        let mut tx = self.db.begin().await?;
+        let version = self
+            .lock_user_version(&mut tx, event.user_id)
+            .await?
+            .ok_or_else(|| AppError::EntityNotFound("Specified user not found".into()))?;
+        ensure_version(version, event.expected_version)?;
+        sqlx::query!(
             r#"
                 DELETE FROM users
                 WHERE user_id = ? /* ★修正: $1 を ? に置換 */
             "#,
             event.user_id as _
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
-        if res.rows_affected() < 1 {
-            return Err(AppError::EntityNotFound("Specified user not found".into()));
-        }
+        tx.commit().await.map_err(AppError::TransactionError)?;
         Ok(())
     }
 }
 
+impl UserRepositoryImpl {
+    // バージョンを確認してから更新・削除するまでの間に他の操作で更新されないよう、ユーザーの行をロックして
+    // 現在のバージョンを取得する
+    async fn lock_user_version(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        user_id: UserId,
+    ) -> AppResult<Option<i64>> {
+        sqlx::query_scalar!(
+            r#"
+                SELECT version FROM users WHERE user_id = ? FOR UPDATE
+            "#,
+            user_id as _
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)
+    }
+}
+
+// メールアドレスの一意制約に違反した場合は、重複エラーとして返す
+fn map_email_conflict(e: sqlx::Error) -> AppError {
+    match e.as_database_error() {
+        Some(db_error) if db_error.is_unique_violation() => {
+            AppError::Conflict("同じメールアドレスのユーザーがすでに登録されています".into())
+        }
+        _ => AppError::SpecificOperationError(e),
+    }
+}
+
 fn hash_password(password: &str) -> AppResult<String> {
     bcrypt::hash(password, bcrypt::DEFAULT_COST).map_err(AppError::from)
 }
@@ -190,12 +267,13 @@ mod tests {
             id::UserId,
             role::Role,
             user::{
-                event::{CreateUser, DeleteUser, UpdateUserPassword, UpdateUserRole},
+                event::{CreateUser, DeleteUser, PatchUser, UpdateUserPassword, UpdateUserRole},
                 User,
             },
         },
         repository::user::UserRepository,
     };
+    use shared::error::AppError;
     use std::str::FromStr;
 
     // ★修正: sqlx::PgPool を sqlx::MySqlPool に置換 ★
@@ -212,6 +290,7 @@ mod tests {
                 email: "eleazar.fig@example.com".into(),
                 name: "Eleazar Fig".into(),
                 role: Role::Admin,
+                version: 1,
             })
         );
 
@@ -244,20 +323,34 @@ mod tests {
             let event = UpdateUserRole {
                 user_id: user.id,
                 role: Role::Admin,
+                expected_version: Some(user.version),
             };
             repo.update_role(event).await?;
         }
 
         // find
-        let user_found = repo.find_current_user(user.id).await?;
-        assert_eq!(user_found.unwrap().id, user.id);
+        let user_found = repo.find_current_user(user.id).await?.unwrap();
+        assert_eq!(user_found.id, user.id);
+        assert_eq!(user_found.role, Role::Admin);
+        assert_eq!(user_found.version, user.version + 1);
 
         let users = repo.find_all().await?;
         assert!(!users.is_empty());
 
         {
+            // 更新前のバージョンを指定した場合は削除できない
+            let event = DeleteUser {
+                user_id: user.id,
+                expected_version: Some(user.version),
+            };
+            let res = repo.delete(event).await;
+            assert!(matches!(res, Err(AppError::PreconditionFailed(_))));
+
             // delete
-            let event = DeleteUser { user_id: user.id };
+            let event = DeleteUser {
+                user_id: user.id,
+                expected_version: Some(user_found.version),
+            };
             repo.delete(event).await?;
         }
 
@@ -266,4 +359,55 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_patch_user(pool: sqlx::MySqlPool) -> anyhow::Result<()> {
+        let repo = UserRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let user = repo
+            .create(CreateUser {
+                name: "Test".into(),
+                email: "test@example.com".into(),
+                password: "dummy".into(),
+            })
+            .await?;
+
+        // 名前だけを更新し、メールアドレスはそのままにする
+        let event = PatchUser {
+            user_id: user.id,
+            name: Some("Renamed".into()),
+            email: None,
+            expected_version: Some(user.version),
+        };
+        repo.patch(event).await?;
+        let patched = repo.find_current_user(user.id).await?.unwrap();
+        assert_eq!(patched.name, "Renamed");
+        assert_eq!(patched.email, "test@example.com");
+        assert_eq!(patched.version, user.version + 1);
+
+        // 更新前のバージョンを指定した場合は更新できない
+        let event = PatchUser {
+            user_id: user.id,
+            name: None,
+            email: Some("renamed@example.com".into()),
+            expected_version: Some(user.version),
+        };
+        let res = repo.patch(event).await;
+        assert!(matches!(res, Err(AppError::PreconditionFailed(_))));
+
+        // 他のユーザーのメールアドレスには変更できない
+        let event = PatchUser {
+            user_id: user.id,
+            name: None,
+            email: Some("eleazar.fig@example.com".into()),
+            expected_version: Some(patched.version),
+        };
+        let res = repo.patch(event).await;
+        assert!(matches!(res, Err(AppError::Conflict(_))));
+
+        let unchanged = repo.find_current_user(user.id).await?.unwrap();
+        assert_eq!(unchanged.email, "test@example.com");
+        assert_eq!(unchanged.version, patched.version);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,97 @@
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::repository::storage::ObjectStorage;
+use shared::error::{AppError, AppResult};
+use std::{io::ErrorKind, path::PathBuf};
+
+// ローカルのファイルシステムをオブジェクトストレージとして使う
+// キーを root からの相対パスとしてファイルに保存する
+// ファイルには内容のみを保存し、content_type は保存しない
+#[derive(new)]
+pub struct LocalObjectStorage {
+    root: PathBuf,
+}
+
+impl LocalObjectStorage {
+    // root の外のファイルを指さないよう、空・. ・.. の要素を含むキーは受け付けない
+    fn path(&self, key: &str) -> AppResult<PathBuf> {
+        if key
+            .split('/')
+            .any(|part| part.is_empty() || part == "." || part == "..")
+        {
+            return Err(AppError::ObjectStorageError(format!("invalid key: {key}")));
+        }
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl ObjectStorage for LocalObjectStorage {
+    async fn put(&self, key: &str, body: Vec<u8>, _content_type: &str) -> AppResult<()> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| storage_error(key, e))?;
+        }
+        // 書き込み途中のファイルを読み取られないよう、一時ファイルに書き込んでから置き換える
+        let tmp = path.with_extension("tmp");
+        tokio::fs::write(&tmp, body)
+            .await
+            .map_err(|e| storage_error(key, e))?;
+        tokio::fs::rename(&tmp, &path)
+            .await
+            .map_err(|e| storage_error(key, e))
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Option<Vec<u8>>> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(body) => Ok(Some(body)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(storage_error(key, e)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(storage_error(key, e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn storage_error(key: &str, e: std::io::Error) -> AppError {
+    AppError::ObjectStorageError(format!("{key}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_put_get_delete() -> anyhow::Result<()> {
+        let root = std::env::temp_dir().join(format!("object_storage_{}", std::process::id()));
+        let storage = LocalObjectStorage::new(root.clone());
+        let key = "covers/book/original";
+
+        // 保存前は None
+        assert!(storage.get(key).await?.is_none());
+
+        // 保存したものを取得でき、上書きもできる
+        storage.put(key, b"first".to_vec(), "image/png").await?;
+        storage.put(key, b"second".to_vec(), "image/png").await?;
+        assert_eq!(storage.get(key).await?, Some(b"second".to_vec()));
+
+        // 削除すると取得できなくなり、存在しないものを削除してもエラーにならない
+        storage.delete(key).await?;
+        assert!(storage.get(key).await?.is_none());
+        storage.delete(key).await?;
+
+        // root の外を指すキーは受け付けない
+        let res = storage.get("../outside").await;
+        assert!(matches!(res, Err(AppError::ObjectStorageError(_))));
+
+        tokio::fs::remove_dir_all(&root).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use image::{ImageFormat, ImageReader};
+use shared::error::{AppError, AppResult};
+use std::io::Cursor;
+
+// 表紙画像として受け付けるファイルの大きさの上限（5 MiB）
+pub const MAX_COVER_BYTES: usize = 5 * 1024 * 1024;
+
+// サムネイルの幅・高さの上限
+// 縦横比は保ったまま、この範囲に収まるよう縮小する
+const THUMBNAIL_WIDTH: u32 = 200;
+const THUMBNAIL_HEIGHT: u32 = 300;
+
+// 表紙画像として受け付ける形式
+const COVER_FORMATS: &[(&str, ImageFormat)] = &[
+    ("image/jpeg", ImageFormat::Jpeg),
+    ("image/png", ImageFormat::Png),
+];
+
+// 検証済みの表紙画像と、そこから作ったサムネイル
+// サムネイルは元の画像と同じ形式で作る
+pub struct CoverImage {
+    pub content_type: &'static str,
+    pub original: Vec<u8>,
+    pub thumbnail: Vec<u8>,
+}
+
+impl CoverImage {
+    // アップロードされたファイルの形式と大きさを検証し、サムネイルを作る
+    // content_type で指定された形式と、ファイルの内容から判別した形式が一致しない場合はエラーにする
+    pub fn from_upload(content_type: Option<&str>, body: Vec<u8>) -> AppResult<Self> {
+        if body.len() > MAX_COVER_BYTES {
+            return Err(AppError::UploadTooLarge(MAX_COVER_BYTES));
+        }
+        let content_type = content_type.unwrap_or_default();
+        let Some(&(content_type, format)) = COVER_FORMATS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(content_type))
+        else {
+            return Err(AppError::InvalidUpload(format!(
+                "表紙画像は JPEG または PNG にしてください: {content_type}"
+            )));
+        };
+        if image::guess_format(&body).ok() != Some(format) {
+            return Err(AppError::InvalidUpload(format!(
+                "ファイルの内容が {content_type} ではありません"
+            )));
+        }
+
+        let image = ImageReader::with_format(Cursor::new(&body), format)
+            .decode()
+            .map_err(|e| AppError::InvalidUpload(e.to_string()))?;
+        // 十分に小さい画像はそのままサムネイルにする
+        let thumbnail = if image.width() <= THUMBNAIL_WIDTH && image.height() <= THUMBNAIL_HEIGHT {
+            image
+        } else {
+            image.thumbnail(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT)
+        };
+        let mut thumbnail_body = Cursor::new(Vec::new());
+        thumbnail
+            .write_to(&mut thumbnail_body, format)
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+
+        Ok(Self {
+            content_type,
+            original: body,
+            thumbnail: thumbnail_body.into_inner(),
+        })
+    }
+}
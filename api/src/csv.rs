@@ -0,0 +1,115 @@
+// CSV（RFC 4180）形式のレスポンスの組み立てと、アップロードされた CSV の読み取りに使うヘルパー
+
+use shared::error::{AppError, AppResult};
+
+pub const CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+
+// 1 行分のフィールドを CSV の形式で書き出し、改行（CRLF）を付け加える
+pub fn push_record<I, S>(out: &mut String, fields: I)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        push_field(out, field.as_ref());
+    }
+    out.push_str("\r\n");
+}
+
+// カンマ・ダブルクオート・改行を含むフィールドはダブルクオートで囲み、
+// フィールド内のダブルクオートは 2 つ重ねてエスケープする
+fn push_field(out: &mut String, field: &str) {
+    if field.contains([',', '"', '\r', '\n']) {
+        out.push('"');
+        out.push_str(&field.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(field);
+    }
+}
+
+// CSV の 1 レコード
+// line はレコードが始まる行の番号（1 始まり）で、エラーの報告に使う
+#[derive(Debug, PartialEq, Eq)]
+pub struct CsvRecord {
+    pub line: usize,
+    pub fields: Vec<String>,
+}
+
+// CSV の文字列をレコードの一覧に分割する
+// 改行は LF・CRLF のどちらでもよく、先頭の BOM と空行は読み飛ばす
+// 引用符で囲まれたフィールドには、カンマや改行を含めることができる
+pub fn parse(input: &str) -> AppResult<Vec<CsvRecord>> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    // 現在のフィールドが引用符で囲まれているか、引用符の中を読んでいるか
+    let (mut quoted, mut in_quotes) = (false, false);
+    let (mut line, mut record_line) = (1, 1);
+
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() && !quoted => {
+                quoted = true;
+                in_quotes = true;
+            }
+            ',' => {
+                fields.push(std::mem::take(&mut field));
+                quoted = false;
+            }
+            '\r' | '\n' => {
+                if c == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                // 空行はレコードとして扱わない
+                if !fields.is_empty() || !field.is_empty() || quoted {
+                    fields.push(std::mem::take(&mut field));
+                    records.push(CsvRecord {
+                        line: record_line,
+                        fields: std::mem::take(&mut fields),
+                    });
+                }
+                quoted = false;
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(AppError::InvalidCsv(format!(
+            "{} 行目の引用符が閉じられていません",
+            record_line
+        )));
+    }
+    if !fields.is_empty() || !field.is_empty() || quoted {
+        fields.push(field);
+        records.push(CsvRecord {
+            line: record_line,
+            fields,
+        });
+    }
+
+    Ok(records)
+}
@@ -0,0 +1,71 @@
+use axum::{
+    http::{header::IF_MATCH, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use axum_extra::{
+    headers::{ETag, HeaderMapExt, IfMatch, IfNoneMatch},
+    TypedHeader,
+};
+use shared::error::{AppError, AppResult};
+
+// 蔵書・ユーザーのバージョンから ETag を作る
+pub fn version_etag(version: i64) -> ETag {
+    // 数字を二重引用符で囲んだ文字列は常に ETag として正しい
+    format!("\"{version}\"")
+        .parse()
+        .expect("version should be a valid entity tag")
+}
+
+// レスポンスに ETag ヘッダーを付ける
+// If-None-Match の ETag が現在の ETag と一致する場合は、本文を返さず 304 を返す
+pub fn with_etag(headers: &HeaderMap, version: i64, body: impl IntoResponse) -> Response {
+    let etag = version_etag(version);
+    let not_modified = headers
+        .typed_get::<IfNoneMatch>()
+        .is_some_and(|if_none_match| !if_none_match.precondition_passes(&etag));
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, TypedHeader(etag)).into_response();
+    }
+    (TypedHeader(etag), body).into_response()
+}
+
+// If-Match ヘッダーを取り出す。指定されていない場合は None を返す
+// TypedHeader で取り出すとヘッダーがない場合も空の If-Match として扱われるため、ヘッダーから直接取り出す
+pub fn find_if_match(headers: &HeaderMap) -> Option<IfMatch> {
+    headers.typed_get::<IfMatch>()
+}
+
+// 更新・削除の前に If-Match ヘッダーが指定されていることを確認する
+pub fn require_if_match(headers: &HeaderMap) -> AppResult<IfMatch> {
+    find_if_match(headers).ok_or(AppError::PreconditionRequired)
+}
+
+// If-Match の ETag からバージョンを取り出す
+// 照合は更新と同じトランザクションの中でリポジトリが行うため、ここでは現在のバージョンを取得しない
+// ヘッダーが指定されていない場合は 428 を、"*" の場合はバージョンを照合しないものとして None を返す
+// 取得時の ETag ではない値（弱い ETag や複数の ETag など）は、一致しないものとして 412 を返す
+pub fn require_if_match_version(headers: &HeaderMap) -> AppResult<Option<i64>> {
+    if require_if_match(headers)? == IfMatch::any() {
+        return Ok(None);
+    }
+    headers
+        .get(IF_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().strip_prefix('"')?.strip_suffix('"'))
+        .and_then(|version| version.parse().ok())
+        .map(Some)
+        .ok_or_else(|| {
+            AppError::PreconditionFailed("If-Match の ETag が現在の ETag と一致しません".into())
+        })
+}
+
+// If-Match の ETag が現在のバージョンと一致するかを確認し、
+// リポジトリで更新する直前にも照合できるよう、確認したバージョンを返す
+pub fn check_if_match(if_match: &IfMatch, version: i64) -> AppResult<i64> {
+    if !if_match.precondition_passes(&version_etag(version)) {
+        return Err(AppError::PreconditionFailed(
+            "If-Match の ETag が現在の ETag と一致しません".into(),
+        ));
+    }
+    Ok(version)
+}
@@ -1,92 +1,586 @@
 use crate::{
+    cover::CoverImage,
+    etag::{check_if_match, require_if_match, require_if_match_version, with_etag},
     extractor::AuthorizedUser,
-    model::book::{
-        BookListQuery, BookResponse, CreateBookRequest, PaginatedBookResponse, UpdateBookRequest,
-        UpdateBookRequestWithIds,
+    model::{
+        book::{
+            BookHistoryResponse, BookListQuery, BookListResponse, BookLookupQuery, BookResponse,
+            CreateBookRequest, CursorPaginatedBookResponse, PaginatedBookResponse,
+            PatchBookRequest, PatchBookRequestWithIds, RegisterBookQuery, UpdateBookRequest,
+            UpdateBookRequestWithIds,
+        },
+        export::{BookExportFormat, BookExportQuery},
+        import::{BookImportError, BookImportQuery, BookImportResponse, BookImportRows},
     },
 };
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    body::Body,
+    extract::{Multipart, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use garde::Validate;
-use kernel::model::{book::event::DeleteBook, id::BookId};
+use kernel::model::{
+    book::{
+        cover::BookCoverSize,
+        event::{DeleteBook, PatchBook, PurgeBook, RestoreBook, UpdateBook, UpdateBookCover},
+        isbn::Isbn,
+        Book, BookCursorListOptions,
+    },
+    id::{BookId, UserId},
+    list::CursorPaginatedList,
+};
 use registry::AppRegistry;
 use shared::error::{AppError, AppResult};
 
 pub async fn register_book(
     user: AuthorizedUser,
+    Query(query): Query<RegisterBookQuery>,
     State(registry): State<AppRegistry>,
-    Json(req): Json<CreateBookRequest>,
+    Json(mut req): Json<CreateBookRequest>,
 ) -> Result<StatusCode, AppError> {
+    // fillMissing が指定され、省略された項目がある場合は書誌情報で補ってから検証する
+    // ISBN が正しくない場合は検索せず、検証のエラーとして返す
+    if query.fill_missing && req.has_missing_fields() {
+        if let Ok(isbn) = req.isbn.parse::<Isbn>() {
+            if let Some(metadata) = registry
+                .book_metadata_provider()
+                .find_by_isbn(&isbn)
+                .await?
+            {
+                req.fill_missing(metadata);
+            }
+        }
+    }
     req.validate(&())?;
 
     registry
         .book_repository()
-        .create(req.into(), user.id())
+        .create(req.try_into()?, user.id())
         .await
         .map(|_| StatusCode::CREATED)
 }
 
-pub async fn show_book_list(
+/// ISBN から書誌情報を検索し、蔵書登録のリクエストと同じ形式で返す
+/// 書誌情報に含まれない項目は空文字になる
+pub async fn lookup_book(
     _user: AuthorizedUser,
+    Query(query): Query<BookLookupQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<CreateBookRequest>> {
+    let BookLookupQuery { isbn } = query;
+    match registry
+        .book_metadata_provider()
+        .find_by_isbn(&isbn)
+        .await?
+    {
+        Some(metadata) => Ok(Json(CreateBookRequest::from_metadata(isbn, metadata))),
+        None => Err(AppError::EntityNotFound(format!(
+            "ISBN {} の書誌情報が見つかりませんでした",
+            isbn.hyphenated()
+        ))),
+    }
+}
+
+/// CSV ファイルから蔵書を一括で登録する
+/// multipart/form-data の file フィールドで CSV ファイルを受け取る
+/// 検証を通らなかった行は登録せず、行番号とあわせてレスポンスで返す
+pub async fn import_books(
+    user: AuthorizedUser,
+    Query(query): Query<BookImportQuery>,
+    State(registry): State<AppRegistry>,
+    multipart: Multipart,
+) -> AppResult<Json<BookImportResponse>> {
+    let file = read_upload_file(multipart, "file").await?;
+    let input = String::from_utf8(file.body)
+        .map_err(|_| AppError::InvalidCsv("文字コードは UTF-8 にしてください".into()))?;
+    let rows = BookImportRows::from_csv(&input)?;
+
+    import_book_rows(&registry, user.id(), query.dry_run, rows)
+        .await
+        .map(Json)
+}
+
+/// MARC21（バイナリ形式または MARCXML）のファイルから蔵書を一括で登録する
+/// multipart/form-data の file フィールドでファイルを受け取る
+/// 蔵書の情報に変換できなかったレコードは登録せず、何件目のレコードかとあわせてレスポンスで返す
+pub async fn import_marc_books(
+    user: AuthorizedUser,
+    Query(query): Query<BookImportQuery>,
+    State(registry): State<AppRegistry>,
+    multipart: Multipart,
+) -> AppResult<Json<BookImportResponse>> {
+    let file = read_upload_file(multipart, "file").await?;
+    let rows = BookImportRows::from_marc(&file.body)?;
+
+    import_book_rows(&registry, user.id(), query.dry_run, rows)
+        .await
+        .map(Json)
+}
+
+// ファイルから読み取った蔵書のうち、検証を通ったものをまとめて登録する
+// ISBN が登録済みの蔵書やファイル内の他の蔵書と重複するものは、エラーとして登録しない
+// dry_run の場合は登録せずに結果のみを返す
+// CLI からの一括登録でも使う
+pub async fn import_book_rows(
+    registry: &AppRegistry,
+    user_id: UserId,
+    dry_run: bool,
+    rows: BookImportRows,
+) -> AppResult<BookImportResponse> {
+    let BookImportRows {
+        total,
+        books,
+        mut errors,
+    } = rows;
+
+    let (positions, events): (Vec<_>, Vec<_>) = books
+        .into_iter()
+        .map(|row| ((row.record, row.line), row.book))
+        .unzip();
+    let book_repository = registry.book_repository();
+    let conflicts = if events.is_empty() {
+        vec![]
+    } else if dry_run {
+        let isbns = events.iter().map(|e| e.isbn.clone()).collect::<Vec<_>>();
+        book_repository.find_isbn_conflicts(&isbns, user_id).await?
+    } else {
+        book_repository.create_many(events, user_id).await?
+    };
+
+    let valid_rows = positions.len() - conflicts.len();
+    let imported_rows = if dry_run { 0 } else { valid_rows };
+    errors.extend(conflicts.iter().map(|conflict| {
+        let (record, line) = positions[conflict.index];
+        BookImportError::isbn_conflict(record, line, conflict)
+    }));
+    errors.sort_by_key(|e| e.record);
+
+    Ok(BookImportResponse {
+        dry_run,
+        total_rows: total,
+        valid_rows,
+        imported_rows,
+        errors,
+    })
+}
+
+// multipart/form-data で受け取ったファイル
+struct UploadedFile {
+    content_type: Option<String>,
+    body: Vec<u8>,
+}
+
+// multipart/form-data のリクエストから、指定した名前のフィールドの内容を取り出す
+async fn read_upload_file(mut multipart: Multipart, name: &str) -> AppResult<UploadedFile> {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::InvalidUpload(e.body_text()))?
+    {
+        if field.name() == Some(name) {
+            let content_type = field.content_type().map(String::from);
+            return field
+                .bytes()
+                .await
+                .map(|bytes| UploadedFile {
+                    content_type,
+                    body: bytes.to_vec(),
+                })
+                .map_err(|e| AppError::InvalidUpload(e.body_text()));
+        }
+    }
+    Err(AppError::InvalidUpload(format!(
+        "{} フィールドがありません",
+        name
+    )))
+}
+
+/// 蔵書を CSV・JSON Lines・MARCXML のいずれかの形式で出力する
+/// 絞り込み条件と並び順は蔵書一覧と同じクエリで指定でき、limit などのページネーションの指定は無視する
+/// 蔵書はカーソル方式で少しずつ取得し、取得したものから順にレスポンスに書き出す
+pub async fn export_books(
+    user: AuthorizedUser,
+    Query(export): Query<BookExportQuery>,
     Query(query): Query<BookListQuery>,
     State(registry): State<AppRegistry>,
-) -> AppResult<Json<PaginatedBookResponse>> {
+) -> AppResult<Response> {
     query.validate(&())?;
+    if query.deleted && !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+
+    let BookCursorListOptions { filter, sort, .. } = query.into();
+    let options = move |cursor| BookCursorListOptions {
+        limit: EXPORT_PAGE_SIZE,
+        cursor,
+        filter: filter.clone(),
+        sort,
+    };
+    // 最初のページはレスポンスを返す前に取得し、取得に失敗した場合はエラーのステータスを返す
+    let first_page = registry
+        .book_repository()
+        .find_all_by_cursor(options(None))
+        .await?;
+
+    let format = export.format;
+    let (tx, rx) = tokio::sync::mpsc::channel::<AppResult<String>>(EXPORT_BUFFER_PAGES);
+    tokio::spawn(async move {
+        let mut chunk = format.header();
+        let mut page = first_page;
+        loop {
+            let CursorPaginatedList {
+                items, next_cursor, ..
+            } = page;
+            if let Err(e) = push_books(&mut chunk, format, items) {
+                let _ = tx.send(Err(e)).await;
+                return;
+            }
+            let Some(cursor) = next_cursor else {
+                break;
+            };
+            // クライアントが切断した場合は、それ以降の蔵書を取得しない
+            if tx.send(Ok(std::mem::take(&mut chunk))).await.is_err() {
+                return;
+            }
+            page = match registry
+                .book_repository()
+                .find_all_by_cursor(options(Some(cursor)))
+                .await
+            {
+                Ok(page) => page,
+                // ステータスコードは返却済みのため、レスポンスを途中で打ち切る
+                Err(e) => {
+                    tracing::error!(error.message = %e, "failed to export books");
+                    let _ = tx.send(Err(e)).await;
+                    return;
+                }
+            };
+        }
+        chunk.push_str(&format.footer());
+        let _ = tx.send(Ok(chunk)).await;
+    });
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", format.file_name()),
+            ),
+        ],
+        Body::from_stream(tokio_stream::wrappers::ReceiverStream::new(rx)),
+    )
+        .into_response())
+}
+
+// 蔵書の出力で、1 回に取得する蔵書の数
+const EXPORT_PAGE_SIZE: i64 = 500;
+// 蔵書の出力で、クライアントへの書き出しを待たずに先に取得しておくページ数
+const EXPORT_BUFFER_PAGES: usize = 2;
+
+fn push_books(out: &mut String, format: BookExportFormat, books: Vec<Book>) -> AppResult<()> {
+    for book in books {
+        format.push_book(out, &BookResponse::from(book))?;
+    }
+    Ok(())
+}
+
+pub async fn show_book_list(
+    user: AuthorizedUser,
+    Query(query): Query<BookListQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<BookListResponse>> {
+    query.validate(&())?;
+    // 削除済みの蔵書は Admin のみ取得できる
+    if query.deleted && !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+
+    // cursor が指定された場合はカーソル方式、それ以外は offset 方式で取得する
+    if query.cursor.is_some() {
+        return registry
+            .book_repository()
+            .find_all_by_cursor(query.into())
+            .await
+            .map(CursorPaginatedBookResponse::from)
+            .map(BookListResponse::CursorPaginated)
+            .map(Json);
+    }
 
     registry
         .book_repository()
         .find_all(query.into())
         .await
         .map(PaginatedBookResponse::from)
+        .map(BookListResponse::Paginated)
         .map(Json)
 }
 
+/// 蔵書を取得する
+/// 蔵書のバージョンを ETag ヘッダーで返し、If-None-Match が一致する場合は 304 を返す
 pub async fn show_book(
     _user: AuthorizedUser,
     Path(book_id): Path<BookId>,
+    headers: HeaderMap,
     State(registry): State<AppRegistry>,
-) -> AppResult<Json<BookResponse>> {
-    registry
+) -> AppResult<Response> {
+    let book = registry
         .book_repository()
         .find_by_id(book_id)
-        .await
-        .and_then(|bc| match bc {
-            Some(bc) => Ok(Json(bc.into())),
-            None => Err(AppError::EntityNotFound("not found".into())),
-        })
+        .await?
+        .ok_or_else(|| AppError::EntityNotFound("not found".into()))?;
+    let version = book.version;
+    Ok(with_etag(&headers, version, Json(BookResponse::from(book))))
+}
+
+/// 蔵書の変更履歴を、変更日時の新しい順に取得する（蔵書の所有者または Admin のみ）
+/// 削除済みの蔵書の履歴も取得できる。完全に削除された蔵書の履歴は Admin のみ取得できる
+pub async fn show_book_history(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<BookHistoryResponse>> {
+    let history = registry
+        .book_repository()
+        .find_history(book_id)
+        .await?
+        .ok_or_else(|| AppError::EntityNotFound("specified book not found".into()))?;
+    if history.owned_by != Some(user.id()) && !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+
+    Ok(Json(history.into()))
 }
 
+/// 蔵書を更新する（蔵書の所有者のみ）
+/// 取得時の ETag を If-Match ヘッダーで指定し、その後に他の操作で更新されていた場合は 412 を返す
 pub async fn update_book(
     user: AuthorizedUser,
     Path(book_id): Path<BookId>,
+    headers: HeaderMap,
     State(registry): State<AppRegistry>,
     Json(req): Json<UpdateBookRequest>,
 ) -> AppResult<StatusCode> {
+    let if_match = require_if_match(&headers)?;
+    req.validate(&())?;
+
+    let book = registry
+        .book_repository()
+        .find_by_id(book_id)
+        .await?
+        .ok_or_else(|| AppError::EntityNotFound("specified book not found".into()))?;
+    let version = check_if_match(&if_match, book.version)?;
+
+    let mut update_book: UpdateBook =
+        UpdateBookRequestWithIds::new(book_id, user.id(), req).try_into()?;
+    update_book.expected_version = Some(version);
+    registry
+        .book_repository()
+        .update(update_book)
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+/// 蔵書の一部の項目だけを更新する（蔵書の所有者のみ）
+/// JSON Merge Patch として、送られた項目だけを更新し、省略された項目は現在の値のままにする
+/// 取得時の ETag を If-Match ヘッダーで指定し、その後に他の操作で更新されていた場合は 412 を返す
+pub async fn patch_book(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    headers: HeaderMap,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<PatchBookRequest>,
+) -> AppResult<StatusCode> {
+    let expected_version = require_if_match_version(&headers)?;
     req.validate(&())?;
 
-    let update_book = UpdateBookRequestWithIds::new(book_id, user.id(), req);
+    let mut patch_book: PatchBook =
+        PatchBookRequestWithIds::new(book_id, user.id(), req).try_into()?;
+    patch_book.expected_version = expected_version;
+    registry
+        .book_repository()
+        .patch(patch_book)
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+/// 蔵書の表紙画像を登録する（蔵書の所有者または Admin のみ）
+/// multipart/form-data の file フィールドで JPEG または PNG の画像を受け取り、
+/// 作成したサムネイルとあわせてオブジェクトストレージに保存する
+/// すでに表紙画像がある場合は差し替える
+pub async fn upload_book_cover(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+    multipart: Multipart,
+) -> AppResult<StatusCode> {
+    let book = registry
+        .book_repository()
+        .find_by_id(book_id)
+        .await?
+        .ok_or_else(|| AppError::EntityNotFound("specified book not found".into()))?;
+    // 他の蔵書の画像を上書きしないよう、保存する前に権限を確認する
+    if book.owner.id != user.id() && !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+
+    let file = read_upload_file(multipart, "file").await?;
+    // 画像の読み込みと縮小には時間がかかるため、非同期のタスクを止めないよう別スレッドで行う
+    let cover = tokio::task::spawn_blocking(move || {
+        CoverImage::from_upload(file.content_type.as_deref(), file.body)
+    })
+    .await
+    .map_err(|e| AppError::ConversionEntityError(e.to_string()))??;
+
+    let storage = registry.object_storage();
+    storage
+        .put(
+            &BookCoverSize::Original.storage_key(book_id),
+            cover.original,
+            cover.content_type,
+        )
+        .await?;
+    storage
+        .put(
+            &BookCoverSize::Thumbnail.storage_key(book_id),
+            cover.thumbnail,
+            cover.content_type,
+        )
+        .await?;
+
+    let mut update_cover = UpdateBookCover::new(book_id, cover.content_type.into(), user.id());
+    update_cover.by_admin = user.is_admin();
     registry
         .book_repository()
-        .update(update_book.into())
+        .update_cover(update_cover)
         .await
         .map(|_| StatusCode::OK)
 }
 
+/// 蔵書の表紙画像を取得する
+/// img 要素から直接参照できるよう、認証なしで取得できる
+pub async fn show_book_cover(
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Response> {
+    book_cover_response(&registry, book_id, BookCoverSize::Original).await
+}
+
+/// 蔵書の表紙画像のサムネイルを取得する
+/// img 要素から直接参照できるよう、認証なしで取得できる
+pub async fn show_book_cover_thumbnail(
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Response> {
+    book_cover_response(&registry, book_id, BookCoverSize::Thumbnail).await
+}
+
+// 表紙画像の URL には更新日時が含まれ、差し替えると URL が変わるため、長めにキャッシュさせる
+const COVER_CACHE_CONTROL: &str = "public, max-age=86400";
+
+async fn book_cover_response(
+    registry: &AppRegistry,
+    book_id: BookId,
+    size: BookCoverSize,
+) -> AppResult<Response> {
+    let not_found = || AppError::EntityNotFound("cover not found".into());
+    let cover = registry
+        .book_repository()
+        .find_by_id(book_id)
+        .await?
+        .and_then(|book| book.cover)
+        .ok_or_else(not_found)?;
+    let body = registry
+        .object_storage()
+        .get(&size.storage_key(book_id))
+        .await?
+        .ok_or_else(not_found)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, cover.content_type),
+            (header::CACHE_CONTROL, COVER_CACHE_CONTROL.to_string()),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// 蔵書を削除済みにする（蔵書の所有者のみ）
+/// 取得時の ETag を If-Match ヘッダーで指定し、その後に他の操作で更新されていた場合は 412 を返す
 pub async fn delete_book(
     user: AuthorizedUser,
     Path(book_id): Path<BookId>,
+    headers: HeaderMap,
     State(registry): State<AppRegistry>,
 ) -> AppResult<StatusCode> {
+    let if_match = require_if_match(&headers)?;
+    let book = registry
+        .book_repository()
+        .find_by_id(book_id)
+        .await?
+        .ok_or_else(|| AppError::EntityNotFound("specified book not found".into()))?;
     let delete_book = DeleteBook {
         book_id,
         requested_user: user.id(),
+        expected_version: Some(check_if_match(&if_match, book.version)?),
     };
+    // 元に戻せるよう、表紙画像は完全に削除するまで残しておく
     registry
         .book_repository()
         .delete(delete_book)
         .await
         .map(|_| StatusCode::OK)
 }
+
+/// 削除済みの蔵書を元に戻す（Admin のみ）
+pub async fn restore_book(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+
+    registry
+        .book_repository()
+        .restore(RestoreBook {
+            book_id,
+            requested_user: user.id(),
+        })
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+/// 削除済みの蔵書を完全に削除する（Admin のみ）。削除済みでない蔵書や、貸出中の所蔵資料がある蔵書は削除できない
+/// 返却済みの貸出履歴と変更履歴は残す
+pub async fn purge_book(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+
+    registry
+        .book_repository()
+        .purge(PurgeBook {
+            book_id,
+            requested_user: user.id(),
+        })
+        .await?;
+
+    // 表紙画像も削除する。削除に失敗しても蔵書の削除は取り消さない
+    let storage = registry.object_storage();
+    for size in [BookCoverSize::Original, BookCoverSize::Thumbnail] {
+        if let Err(e) = storage.delete(&size.storage_key(book_id)).await {
+            tracing::warn!(error.message = %e, %book_id, "failed to delete book cover");
+        }
+    }
+
+    Ok(StatusCode::OK)
+}
@@ -1,22 +1,51 @@
-use crate::{extractor::AuthorizedUser, model::checkout::CheckoutsResponse};
+use crate::{
+    extractor::AuthorizedUser,
+    model::checkout::{
+        CheckoutBookRequest, CheckoutListQuery, CheckoutOnBehalfRequest,
+        CheckoutOnBehalfRequestWithIds, CheckoutsResponse, ReturnBookRequest,
+        ReturnBookRequestWithIds,
+    },
+};
 use axum::{
-    extract::{Path, State},
+    extract::{rejection::JsonRejection, Path, Query, State},
     http::StatusCode,
     Json,
 };
+use garde::Validate;
 use kernel::model::{
-    checkout::event::{CreateCheckout, UpdateReturned},
-    id::{BookId, CheckoutId},
+    checkout::event::{CreateCheckout, RenewCheckout, UpdateReturned},
+    id::{BookId, CheckoutId, CopyId, UserId},
 };
 use registry::AppRegistry;
-use shared::error::AppResult;
+use shared::error::{AppError, AppResult};
 
 pub async fn checkout_book(
     user: AuthorizedUser,
     Path(book_id): Path<BookId>,
     State(registry): State<AppRegistry>,
+    req: Result<Json<CheckoutBookRequest>, JsonRejection>,
+) -> AppResult<StatusCode> {
+    // 所蔵資料を指定しない場合は、貸出可能な所蔵資料のいずれかを貸し出す
+    let mut create_checkout_history =
+        CreateCheckout::new(book_id, None, user.id(), chrono::Utc::now());
+    create_checkout_history.branch_id = checkout_book_request(req)?.branch_id;
+
+    registry
+        .checkout_repository()
+        .create(create_checkout_history)
+        .await
+        .map(|_| StatusCode::CREATED)
+}
+
+pub async fn checkout_copy(
+    user: AuthorizedUser,
+    Path((book_id, copy_id)): Path<(BookId, CopyId)>,
+    State(registry): State<AppRegistry>,
+    req: Result<Json<CheckoutBookRequest>, JsonRejection>,
 ) -> AppResult<StatusCode> {
-    let create_checkout_history = CreateCheckout::new(book_id, user.id(), chrono::Utc::now());
+    let mut create_checkout_history =
+        CreateCheckout::new(book_id, Some(copy_id), user.id(), chrono::Utc::now());
+    create_checkout_history.branch_id = checkout_book_request(req)?.branch_id;
 
     registry
         .checkout_repository()
@@ -25,12 +54,57 @@ pub async fn checkout_book(
         .map(|_| StatusCode::CREATED)
 }
 
+// ボディがない場合は、手続きをした館を記録せずに貸し出す
+fn checkout_book_request(
+    req: Result<Json<CheckoutBookRequest>, JsonRejection>,
+) -> AppResult<CheckoutBookRequest> {
+    match req {
+        Ok(Json(req)) => Ok(req),
+        Err(JsonRejection::MissingJsonContentType(_)) => Ok(CheckoutBookRequest::default()),
+        Err(e) => Err(AppError::UnprocessableEntity(e.body_text())),
+    }
+}
+
+/// 借りるユーザーに代わって蔵書を貸し出す（Admin only）
+pub async fn checkout_book_on_behalf(
+    user: AuthorizedUser,
+    Path(user_id): Path<UserId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CheckoutOnBehalfRequest>,
+) -> AppResult<StatusCode> {
+    //AuthorizedUser の権限が Admin のときのみ実行可能とする
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    req.validate(&())?;
+
+    let create_checkout = CheckoutOnBehalfRequestWithIds::new(user_id, user.id(), req);
+
+    registry
+        .checkout_repository()
+        .create(create_checkout.into())
+        .await
+        .map(|_| StatusCode::CREATED)
+}
+
+/// 蔵書を返却する。管理者は借りたユーザーに代わって返却できる
 pub async fn return_book(
     user: AuthorizedUser,
     Path((book_id, checkout_id)): Path<(BookId, CheckoutId)>,
     State(registry): State<AppRegistry>,
+    req: Result<Json<ReturnBookRequest>, JsonRejection>,
 ) -> AppResult<StatusCode> {
-    let update_returned = UpdateReturned::new(checkout_id, book_id, user.id(), chrono::Utc::now());
+    let req = match req {
+        Ok(Json(req)) => req,
+        // ボディがない場合は通常の返却として扱う
+        Err(JsonRejection::MissingJsonContentType(_)) => ReturnBookRequest::default(),
+        Err(e) => return Err(AppError::UnprocessableEntity(e.body_text())),
+    };
+    req.validate(&())?;
+
+    let mut update_returned: UpdateReturned =
+        ReturnBookRequestWithIds::new(checkout_id, book_id, user.id(), req).into();
+    update_returned.by_admin = user.is_admin();
 
     registry
         .checkout_repository()
@@ -39,26 +113,49 @@ pub async fn return_book(
         .map(|_| StatusCode::OK)
 }
 
-pub async fn show_checked_out_list(
-    _user: AuthorizedUser,
+pub async fn renew_checkout(
+    user: AuthorizedUser,
+    Path((book_id, checkout_id)): Path<(BookId, CheckoutId)>,
     State(registry): State<AppRegistry>,
-) -> AppResult<Json<CheckoutsResponse>> {
+) -> AppResult<StatusCode> {
+    let renew_checkout = RenewCheckout::new(checkout_id, book_id, user.id(), chrono::Utc::now());
+
     registry
         .checkout_repository()
-        .find_unreturned_all()
+        .renew(renew_checkout)
         .await
-        .map(CheckoutsResponse::from)
-        .map(Json)
+        .map(|_| StatusCode::OK)
+}
+
+pub async fn show_checked_out_list(
+    _user: AuthorizedUser,
+    Query(query): Query<CheckoutListQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<CheckoutsResponse>> {
+    query.validate(&())?;
+
+    let checkout_repository = registry.checkout_repository();
+    let checkouts = if query.overdue {
+        checkout_repository
+            .find_overdue_all(chrono::Utc::now(), query.into())
+            .await
+    } else {
+        checkout_repository.find_unreturned_all(query.into()).await
+    };
+    checkouts.map(CheckoutsResponse::from).map(Json)
 }
 
 pub async fn checkout_history(
     _user: AuthorizedUser,
     Path(book_id): Path<BookId>,
+    Query(query): Query<CheckoutListQuery>,
     State(registry): State<AppRegistry>,
 ) -> AppResult<Json<CheckoutsResponse>> {
+    query.validate(&())?;
+
     registry
         .checkout_repository()
-        .find_history_by_book_id(book_id)
+        .find_history_by_book_id(book_id, query.into())
         .await
         .map(CheckoutsResponse::from)
         .map(Json)
@@ -0,0 +1,85 @@
+use crate::{
+    extractor::AuthorizedUser,
+    model::copy::{
+        BookCopiesResponse, CreateCopyRequest, CreateCopyRequestWithIds, UpdateCopyRequest,
+        UpdateCopyRequestWithIds,
+    },
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::model::{
+    copy::event::DeleteCopy,
+    id::{BookId, CopyId},
+};
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+/// 蔵書に属する所蔵資料の一覧を取得する
+pub async fn show_copy_list(
+    _user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<BookCopiesResponse>> {
+    registry
+        .book_copy_repository()
+        .find_by_book_id(book_id)
+        .await
+        .map(BookCopiesResponse::from)
+        .map(Json)
+}
+
+/// 蔵書に所蔵資料を追加する。蔵書の所有者のみ実行できる
+pub async fn register_copy(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateCopyRequest>,
+) -> AppResult<StatusCode> {
+    req.validate(&())?;
+
+    let create_copy = CreateCopyRequestWithIds::new(book_id, user.id(), req);
+    registry
+        .book_copy_repository()
+        .create(create_copy.into())
+        .await
+        .map(|_| StatusCode::CREATED)
+}
+
+/// 所蔵資料の情報を更新する。蔵書の所有者のみ実行できる
+pub async fn update_copy(
+    user: AuthorizedUser,
+    Path((book_id, copy_id)): Path<(BookId, CopyId)>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateCopyRequest>,
+) -> AppResult<StatusCode> {
+    req.validate(&())?;
+
+    let update_copy = UpdateCopyRequestWithIds::new(book_id, copy_id, user.id(), req);
+    registry
+        .book_copy_repository()
+        .update(update_copy.into())
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+/// 所蔵資料を削除する。蔵書の所有者のみ実行でき、貸出中の所蔵資料は削除できない
+pub async fn delete_copy(
+    user: AuthorizedUser,
+    Path((book_id, copy_id)): Path<(BookId, CopyId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    let delete_copy = DeleteCopy {
+        copy_id,
+        book_id,
+        requested_user: user.id(),
+    };
+    registry
+        .book_copy_repository()
+        .delete(delete_copy)
+        .await
+        .map(|_| StatusCode::OK)
+}
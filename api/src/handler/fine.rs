@@ -0,0 +1,86 @@
+use crate::{
+    extractor::AuthorizedUser,
+    model::fine::{CreateFineCreditRequest, CreateFineCreditRequestWithIds, FinesResponse},
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::model::{fine::event::FineCreditKind, id::UserId};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+/// ログイン中のユーザーの延滞料金の残高と記録を取得する
+pub async fn get_my_fines(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<FinesResponse>> {
+    registry
+        .fine_repository()
+        .find_by_user_id(user.id())
+        .await
+        .map(FinesResponse::from)
+        .map(Json)
+}
+
+/// 指定したユーザーの延滞料金の残高と記録を取得する（Admin または本人のみ）
+pub async fn get_user_fines(
+    user: AuthorizedUser,
+    Path(user_id): Path<UserId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<FinesResponse>> {
+    if !user.is_admin() && user.id() != user_id {
+        return Err(AppError::ForbiddenOperation);
+    }
+
+    registry
+        .fine_repository()
+        .find_by_user_id(user_id)
+        .await
+        .map(FinesResponse::from)
+        .map(Json)
+}
+
+/// 延滞料金の支払いを記録する（Admin only）
+pub async fn record_fine_payment(
+    user: AuthorizedUser,
+    Path(user_id): Path<UserId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateFineCreditRequest>,
+) -> AppResult<StatusCode> {
+    create_fine_credit(user, user_id, FineCreditKind::Payment, registry, req).await
+}
+
+/// 延滞料金の免除を記録する（Admin only）
+pub async fn record_fine_waiver(
+    user: AuthorizedUser,
+    Path(user_id): Path<UserId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateFineCreditRequest>,
+) -> AppResult<StatusCode> {
+    create_fine_credit(user, user_id, FineCreditKind::Waiver, registry, req).await
+}
+
+async fn create_fine_credit(
+    user: AuthorizedUser,
+    user_id: UserId,
+    kind: FineCreditKind,
+    registry: AppRegistry,
+    req: CreateFineCreditRequest,
+) -> AppResult<StatusCode> {
+    //AuthorizedUser の権限が Admin のときのみ実行可能とする
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    req.validate(&())?;
+
+    let create_fine_credit = CreateFineCreditRequestWithIds::new(user_id, user.id(), kind, req);
+
+    registry
+        .fine_repository()
+        .create_credit(create_fine_credit.into())
+        .await
+        .map(|_| StatusCode::CREATED)
+}
@@ -0,0 +1,183 @@
+use crate::{
+    extractor::AuthorizedUser,
+    model::location::{
+        BranchResponse, BranchesResponse, CreateBranchRequest, CreateShelfRequest,
+        CreateShelfRequestWithIds, ShelfResponse, ShelvesResponse, UpdateBookLocationRequest,
+        UpdateBookLocationRequestWithIds, UpdateBranchRequest, UpdateBranchRequestWithIds,
+        UpdateShelfRequest, UpdateShelfRequestWithIds,
+    },
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::model::{
+    book::event::UpdateBookLocation,
+    id::{BookId, BranchId, ShelfId},
+    location::event::{DeleteBranch, DeleteShelf},
+};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+/// 館の一覧を名前の順に取得する
+pub async fn show_branch_list(
+    _user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<BranchesResponse>> {
+    registry
+        .location_repository()
+        .find_branches()
+        .await
+        .map(BranchesResponse::from)
+        .map(Json)
+}
+
+/// 館を登録する（Admin のみ）
+pub async fn register_branch(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateBranchRequest>,
+) -> AppResult<(StatusCode, Json<BranchResponse>)> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    req.validate(&())?;
+
+    let branch = registry
+        .location_repository()
+        .create_branch(req.into())
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(branch.into())))
+}
+
+/// 館の名前を変更する（Admin のみ）
+pub async fn update_branch(
+    user: AuthorizedUser,
+    Path(branch_id): Path<BranchId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateBranchRequest>,
+) -> AppResult<StatusCode> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    req.validate(&())?;
+
+    let update_branch = UpdateBranchRequestWithIds::new(branch_id, req);
+    registry
+        .location_repository()
+        .update_branch(update_branch.into())
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+/// 館を削除する（Admin のみ）。館の書架もあわせて削除し、配架されていた蔵書は配架場所が未設定になる
+pub async fn delete_branch(
+    user: AuthorizedUser,
+    Path(branch_id): Path<BranchId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+
+    registry
+        .location_repository()
+        .delete_branch(DeleteBranch { branch_id })
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+/// 館の書架の一覧を名前の順に取得する
+pub async fn show_shelf_list(
+    _user: AuthorizedUser,
+    Path(branch_id): Path<BranchId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<ShelvesResponse>> {
+    registry
+        .location_repository()
+        .find_shelves_by_branch_id(branch_id)
+        .await
+        .map(ShelvesResponse::from)
+        .map(Json)
+}
+
+/// 館に書架を登録する（Admin のみ）
+pub async fn register_shelf(
+    user: AuthorizedUser,
+    Path(branch_id): Path<BranchId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateShelfRequest>,
+) -> AppResult<(StatusCode, Json<ShelfResponse>)> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    req.validate(&())?;
+
+    let create_shelf = CreateShelfRequestWithIds::new(branch_id, req);
+    let shelf = registry
+        .location_repository()
+        .create_shelf(create_shelf.into())
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(shelf.into())))
+}
+
+/// 書架の名前を変更する（Admin のみ）
+pub async fn update_shelf(
+    user: AuthorizedUser,
+    Path((branch_id, shelf_id)): Path<(BranchId, ShelfId)>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateShelfRequest>,
+) -> AppResult<StatusCode> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    req.validate(&())?;
+
+    let update_shelf = UpdateShelfRequestWithIds::new(branch_id, shelf_id, req);
+    registry
+        .location_repository()
+        .update_shelf(update_shelf.into())
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+/// 書架を削除する（Admin のみ）。配架されていた蔵書は配架場所が未設定になる
+pub async fn delete_shelf(
+    user: AuthorizedUser,
+    Path((branch_id, shelf_id)): Path<(BranchId, ShelfId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+
+    registry
+        .location_repository()
+        .delete_shelf(DeleteShelf {
+            shelf_id,
+            branch_id,
+        })
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+/// 蔵書の配架場所を変更する（蔵書の所有者または Admin のみ）
+pub async fn update_book_location(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateBookLocationRequest>,
+) -> AppResult<StatusCode> {
+    let mut update_location: UpdateBookLocation =
+        UpdateBookLocationRequestWithIds::new(book_id, user.id(), req).into();
+    update_location.by_admin = user.is_admin();
+    registry
+        .book_repository()
+        .update_location(update_location)
+        .await
+        .map(|_| StatusCode::OK)
+}
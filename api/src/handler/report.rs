@@ -0,0 +1,69 @@
+use crate::{
+    csv,
+    extractor::AuthorizedUser,
+    model::report::{
+        CirculationReportResponse, MostBorrowedReportResponse, ReportFormat, ReportQuery,
+    },
+};
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use garde::Validate;
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+/// 期間ごとの貸出数・返却数・利用者数・延滞率・平均貸出日数を取得する（Admin only）
+/// 延滞率には、期間の終わりの時点で延滞したまま返却されていない貸出も含める
+pub async fn show_circulation_report(
+    user: AuthorizedUser,
+    Query(query): Query<ReportQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Response> {
+    //AuthorizedUser の権限が Admin のときのみ実行可能とする
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    query.validate(&())?;
+
+    let stats = registry
+        .report_repository()
+        .find_circulation_stats((&query).into())
+        .await?;
+    let res = CirculationReportResponse::new(&query, stats);
+
+    Ok(match query.format {
+        ReportFormat::Json => Json(res).into_response(),
+        ReportFormat::Csv => {
+            ([(header::CONTENT_TYPE, csv::CONTENT_TYPE)], res.to_csv()).into_response()
+        }
+    })
+}
+
+/// 期間ごとに貸出回数の多い蔵書を取得する（Admin only）
+pub async fn show_most_borrowed_report(
+    user: AuthorizedUser,
+    Query(query): Query<ReportQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Response> {
+    //AuthorizedUser の権限が Admin のときのみ実行可能とする
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    query.validate(&())?;
+
+    let ranking = registry
+        .report_repository()
+        .find_most_borrowed((&query).into(), query.limit)
+        .await?;
+    let res = MostBorrowedReportResponse::new(&query, ranking);
+
+    Ok(match query.format {
+        ReportFormat::Json => Json(res).into_response(),
+        ReportFormat::Csv => {
+            ([(header::CONTENT_TYPE, csv::CONTENT_TYPE)], res.to_csv()).into_response()
+        }
+    })
+}
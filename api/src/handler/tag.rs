@@ -0,0 +1,103 @@
+use crate::{
+    extractor::AuthorizedUser,
+    model::tag::{
+        CreateTagRequest, TagResponse, TagsResponse, UpdateBookTagsRequest,
+        UpdateBookTagsRequestWithIds, UpdateTagRequest, UpdateTagRequestWithIds,
+    },
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::model::{
+    book::event::UpdateBookTags,
+    id::{BookId, TagId},
+    tag::event::DeleteTag,
+};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+/// タグの一覧を名前の順に取得する
+pub async fn show_tag_list(
+    _user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<TagsResponse>> {
+    registry
+        .tag_repository()
+        .find_all()
+        .await
+        .map(TagsResponse::from)
+        .map(Json)
+}
+
+/// タグを登録する（Admin のみ）
+pub async fn register_tag(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateTagRequest>,
+) -> AppResult<(StatusCode, Json<TagResponse>)> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    req.validate(&())?;
+
+    let tag = registry.tag_repository().create(req.into()).await?;
+
+    Ok((StatusCode::CREATED, Json(tag.into())))
+}
+
+/// タグの名前を変更する（Admin のみ）
+pub async fn update_tag(
+    user: AuthorizedUser,
+    Path(tag_id): Path<TagId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateTagRequest>,
+) -> AppResult<StatusCode> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    req.validate(&())?;
+
+    let update_tag = UpdateTagRequestWithIds::new(tag_id, req);
+    registry
+        .tag_repository()
+        .update(update_tag.into())
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+/// タグを削除する（Admin のみ）。蔵書に付けられていたタグもあわせて外れる
+pub async fn delete_tag(
+    user: AuthorizedUser,
+    Path(tag_id): Path<TagId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+
+    registry
+        .tag_repository()
+        .delete(DeleteTag { tag_id })
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+/// 蔵書に付けるタグを置き換える（蔵書の所有者または Admin のみ）
+pub async fn update_book_tags(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateBookTagsRequest>,
+) -> AppResult<StatusCode> {
+    let mut update_tags: UpdateBookTags =
+        UpdateBookTagsRequestWithIds::new(book_id, user.id(), req).into();
+    update_tags.by_admin = user.is_admin();
+    registry
+        .book_repository()
+        .update_tags(update_tags)
+        .await
+        .map(|_| StatusCode::OK)
+}
@@ -1,17 +1,26 @@
 use crate::{
+    etag::{check_if_match, require_if_match, require_if_match_version, with_etag},
     extractor::AuthorizedUser,
     model::user::{
-        CreateUserRequest, UpdateUserPasswordRequest, UpdateUserPasswordRequestWithUserId,
-        UpdateUserRoleRequest, UpdateUserRoleRequestWithUserId, UserResponse, UsersResponse,
+        CreateUserRequest, PatchUserRequest, PatchUserRequestWithUserId, UpdateUserPasswordRequest,
+        UpdateUserPasswordRequestWithUserId, UpdateUserRoleRequest,
+        UpdateUserRoleRequestWithUserId, UserResponse, UsersResponse,
     },
 };
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Response,
     Json,
 };
 use garde::Validate;
-use kernel::model::{id::UserId, user::event::DeleteUser};
+use kernel::model::{
+    id::UserId,
+    user::{
+        event::{DeleteUser, PatchUser, UpdateUserRole},
+        User,
+    },
+};
 use registry::AppRegistry;
 use shared::error::{AppError, AppResult};
 
@@ -48,29 +57,58 @@ pub async fn list_users(
     Ok(Json(UsersResponse { items }))
 }
 
+/// 指定したユーザーの情報を取得する（Admin only）
+/// ユーザーのバージョンを ETag ヘッダーで返し、If-None-Match が一致する場合は 304 を返す
+pub async fn get_user(
+    user: AuthorizedUser,
+    Path(user_id): Path<UserId>,
+    headers: HeaderMap,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Response> {
+    //AuthorizedUser の権限が Admin のときのみ実行可能とする
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+
+    let found = find_user(&registry, user_id).await?;
+    let version = found.version;
+    Ok(with_etag(
+        &headers,
+        version,
+        Json(UserResponse::from(found)),
+    ))
+}
+
 /// ユーザーを削除する（Admin only）
+/// 取得時の ETag を If-Match ヘッダーで指定し、その後に他の操作で更新されていた場合は 412 を返す
 pub async fn delete_user(
     user: AuthorizedUser,
     Path(user_id): Path<UserId>,
+    headers: HeaderMap,
     State(registry): State<AppRegistry>,
 ) -> AppResult<StatusCode> {
     //AuthorizedUser の権限が Admin のときのみ実行可能とする
     if !user.is_admin() {
         return Err(AppError::ForbiddenOperation);
     }
+    let if_match = require_if_match(&headers)?;
 
-    registry
-        .user_repository()
-        .delete(DeleteUser { user_id })
-        .await?;
+    let found = find_user(&registry, user_id).await?;
+    let delete_user = DeleteUser {
+        user_id,
+        expected_version: Some(check_if_match(&if_match, found.version)?),
+    };
+    registry.user_repository().delete(delete_user).await?;
 
     Ok(StatusCode::OK)
 }
 
 /// ユーザーのロールを変更する（Admin only）
+/// 取得時の ETag を If-Match ヘッダーで指定し、その後に他の操作で更新されていた場合は 412 を返す
 pub async fn change_role(
     user: AuthorizedUser,
     Path(user_id): Path<UserId>,
+    headers: HeaderMap,
     State(registry): State<AppRegistry>,
     Json(req): Json<UpdateUserRoleRequest>,
 ) -> AppResult<StatusCode> {
@@ -78,18 +116,50 @@ pub async fn change_role(
     if !user.is_admin() {
         return Err(AppError::ForbiddenOperation);
     }
+    let if_match = require_if_match(&headers)?;
 
-    registry
-        .user_repository()
-        .update_role(UpdateUserRoleRequestWithUserId::new(user_id, req).into())
-        .await?;
+    let found = find_user(&registry, user_id).await?;
+    let mut update_role: UpdateUserRole = UpdateUserRoleRequestWithUserId::new(user_id, req).into();
+    update_role.expected_version = Some(check_if_match(&if_match, found.version)?);
+    registry.user_repository().update_role(update_role).await?;
 
     Ok(StatusCode::OK)
 }
 
 /// ユーザーが自分自身のユーザー情報を取得する
-pub async fn get_current_user(user: AuthorizedUser) -> Json<UserResponse> {
-    Json(UserResponse::from(user.user))
+/// ユーザーのバージョンを ETag ヘッダーで返し、If-None-Match が一致する場合は 304 を返す
+pub async fn get_current_user(user: AuthorizedUser, headers: HeaderMap) -> Response {
+    let version = user.user.version;
+    with_etag(&headers, version, Json(UserResponse::from(user.user)))
+}
+
+/// ユーザーが自分自身のユーザー情報の一部の項目だけを更新する
+/// JSON Merge Patch として、送られた項目だけを更新し、省略された項目は現在の値のままにする
+/// 取得時の ETag を If-Match ヘッダーで指定し、その後に他の操作で更新されていた場合は 412 を返す
+pub async fn patch_current_user(
+    user: AuthorizedUser,
+    headers: HeaderMap,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<PatchUserRequest>,
+) -> AppResult<StatusCode> {
+    let expected_version = require_if_match_version(&headers)?;
+    req.validate(&())?;
+
+    let mut patch_user: PatchUser = PatchUserRequestWithUserId::new(user.id(), req).into();
+    patch_user.expected_version = expected_version;
+    registry
+        .user_repository()
+        .patch(patch_user)
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+async fn find_user(registry: &AppRegistry, user_id: UserId) -> AppResult<User> {
+    registry
+        .user_repository()
+        .find_current_user(user_id)
+        .await?
+        .ok_or_else(|| AppError::EntityNotFound("Specified user not found".into()))
 }
 
 /// ユーザーが自分自身のパスワードを変更する
@@ -108,7 +178,7 @@ pub async fn change_password(
     Ok(StatusCode::OK)
 }
 
-use crate::model::checkout::CheckoutsResponse;
+use crate::model::checkout::{CheckoutHistoryQuery, CheckoutsResponse};
 /// 追加する関数
 /// ユーザーが自身の借りている書籍の一覧を取得する
 pub async fn get_checkouts(
@@ -122,3 +192,40 @@ pub async fn get_checkouts(
         .map(CheckoutsResponse::from)
         .map(Json)
 }
+
+/// ユーザーが自身の貸出履歴（返却済みも含む）を、貸出日の新しい順に取得する
+pub async fn get_checkout_history(
+    user: AuthorizedUser,
+    Query(query): Query<CheckoutHistoryQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<CheckoutsResponse>> {
+    query.validate(&())?;
+
+    registry
+        .checkout_repository()
+        .find_history_by_user_id(user.id(), query.into())
+        .await
+        .map(CheckoutsResponse::from)
+        .map(Json)
+}
+
+/// 指定したユーザーの貸出履歴（返却済みも含む）を、貸出日の新しい順に取得する（Admin only）
+pub async fn get_user_checkout_history(
+    user: AuthorizedUser,
+    Path(user_id): Path<UserId>,
+    Query(query): Query<CheckoutHistoryQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<CheckoutsResponse>> {
+    //AuthorizedUser の権限が Admin のときのみ実行可能とする
+    if !user.is_admin() {
+        return Err(AppError::ForbiddenOperation);
+    }
+    query.validate(&())?;
+
+    registry
+        .checkout_repository()
+        .find_history_by_user_id(user_id, query.into())
+        .await
+        .map(CheckoutsResponse::from)
+        .map(Json)
+}
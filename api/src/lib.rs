@@ -1,4 +1,8 @@
+pub mod cover;
+pub mod csv;
+pub mod etag;
 pub mod extractor;
 pub mod handler;
+pub mod marc;
 pub mod model;
 pub mod route;
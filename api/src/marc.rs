@@ -0,0 +1,188 @@
+// MARC21 のレコード（ISO 2709 形式のバイナリ、および MARCXML）の読み取りに使うヘルパー
+
+use quick_xml::events::{BytesStart, Event};
+use shared::error::{AppError, AppResult};
+
+const RECORD_TERMINATOR: u8 = 0x1d;
+const FIELD_TERMINATOR: u8 = 0x1e;
+const SUBFIELD_DELIMITER: u8 = 0x1f;
+const LEADER_LEN: usize = 24;
+const DIRECTORY_ENTRY_LEN: usize = 12;
+
+// MARC21 の 1 レコード
+// 蔵書の登録に使うのはデータフィールドのみのため、制御フィールドは読み飛ばす
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MarcRecord {
+    pub fields: Vec<MarcDataField>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct MarcDataField {
+    pub tag: String,
+    pub subfields: Vec<(char, String)>,
+}
+
+impl MarcRecord {
+    // 指定したタグのフィールドにある、指定したコードのサブフィールドの値をレコード中の順に返す
+    pub fn subfields<'a>(&'a self, tag: &'a str, code: char) -> impl Iterator<Item = &'a str> {
+        self.fields
+            .iter()
+            .filter(move |f| f.tag == tag)
+            .flat_map(move |f| f.subfields.iter().filter(move |(c, _)| *c == code))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+// MARC21 のファイルをレコードの一覧に分割する
+// 先頭が < で始まる場合は MARCXML、それ以外はバイナリ形式として読み取る
+// レコード単位で読み取れなかったものは、その理由を Err として返す
+pub fn parse(input: &[u8]) -> AppResult<Vec<Result<MarcRecord, String>>> {
+    match input.iter().find(|b| !b.is_ascii_whitespace()) {
+        Some(b'<') => parse_xml(input),
+        _ => Ok(parse_binary(input)),
+    }
+}
+
+fn parse_binary(input: &[u8]) -> Vec<Result<MarcRecord, String>> {
+    input
+        .split(|b| *b == RECORD_TERMINATOR)
+        .map(|record| record.trim_ascii())
+        .filter(|record| !record.is_empty())
+        .map(parse_binary_record)
+        .collect()
+}
+
+fn parse_binary_record(record: &[u8]) -> Result<MarcRecord, String> {
+    if record.len() < LEADER_LEN {
+        return Err("リーダーが 24 バイトに足りません".into());
+    }
+    let base_address = std::str::from_utf8(&record[12..17])
+        .ok()
+        .and_then(|s| s.parse::<usize>().ok())
+        .filter(|base| (LEADER_LEN..=record.len()).contains(base))
+        .ok_or("リーダーのデータの開始位置が正しくありません")?;
+    let directory = record[LEADER_LEN..base_address]
+        .strip_suffix(&[FIELD_TERMINATOR])
+        .unwrap_or(&record[LEADER_LEN..base_address]);
+    if directory.len() % DIRECTORY_ENTRY_LEN != 0 {
+        return Err("ディレクトリの長さが正しくありません".into());
+    }
+
+    let data = &record[base_address..];
+    let mut fields = Vec::new();
+    for entry in directory.chunks(DIRECTORY_ENTRY_LEN) {
+        // 項目はバイト数で区切るため、ASCII 以外の文字があると文字の途中で区切られてしまう
+        let entry = std::str::from_utf8(entry)
+            .ok()
+            .filter(|entry| entry.is_ascii())
+            .ok_or("ディレクトリに不正な文字があります")?;
+        let (tag, rest) = entry.split_at(3);
+        let (len, start) = rest.split_at(4);
+        let (Ok(len), Ok(start)) = (len.parse::<usize>(), start.parse::<usize>()) else {
+            return Err(format!("フィールド {} の位置が正しくありません", tag));
+        };
+        let value = data
+            .get(start..start + len)
+            .ok_or_else(|| format!("フィールド {} の位置が正しくありません", tag))?;
+        // 001 から 009 までは制御フィールド
+        if tag.starts_with("00") {
+            continue;
+        }
+        let value = value.strip_suffix(&[FIELD_TERMINATOR]).unwrap_or(value);
+        // リーダーの文字コードが MARC-8 でも、UTF-8 として読めるもの（ASCII のみなど）は受け付ける
+        let value = std::str::from_utf8(value).map_err(|_| {
+            format!(
+                "フィールド {} を UTF-8 として読み取れません（MARC-8 には対応していません）",
+                tag
+            )
+        })?;
+        fields.push(MarcDataField {
+            tag: tag.to_string(),
+            // 先頭の 2 文字はインディケーター
+            subfields: value
+                .split(SUBFIELD_DELIMITER as char)
+                .skip(1)
+                .filter_map(|subfield| {
+                    let mut chars = subfield.chars();
+                    chars.next().map(|code| (code, chars.as_str().to_string()))
+                })
+                .collect(),
+        });
+    }
+    Ok(MarcRecord { fields })
+}
+
+fn parse_xml(input: &[u8]) -> AppResult<Vec<Result<MarcRecord, String>>> {
+    let input = std::str::from_utf8(input)
+        .map_err(|_| AppError::InvalidMarc("文字コードは UTF-8 にしてください".into()))?;
+    let invalid = |e: quick_xml::Error| AppError::InvalidMarc(e.to_string());
+
+    let mut reader = quick_xml::Reader::from_str(input);
+    let mut records = Vec::new();
+    // 読み取り中のレコード・データフィールド・サブフィールド
+    let mut record: Option<Result<MarcRecord, String>> = None;
+    let mut field: Option<MarcDataField> = None;
+    let mut subfield: Option<(char, String)> = None;
+    loop {
+        match reader.read_event().map_err(invalid)? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"record" => record = Some(Ok(MarcRecord::default())),
+                b"datafield" => {
+                    field = match attribute(&e, "tag") {
+                        Ok(tag) => Some(MarcDataField {
+                            tag,
+                            subfields: Vec::new(),
+                        }),
+                        Err(message) => {
+                            record = record.map(|_| Err(message));
+                            None
+                        }
+                    }
+                }
+                b"subfield" if field.is_some() => {
+                    subfield = attribute(&e, "code")
+                        .ok()
+                        .and_then(|code| code.chars().next())
+                        .map(|code| (code, String::new()));
+                }
+                _ => {}
+            },
+            Event::Text(e) => {
+                if let Some((_, value)) = subfield.as_mut() {
+                    value.push_str(&e.unescape().map_err(invalid)?);
+                }
+            }
+            Event::CData(e) => {
+                if let Some((_, value)) = subfield.as_mut() {
+                    value.push_str(&String::from_utf8_lossy(&e));
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"subfield" => {
+                    if let (Some(field), Some(subfield)) = (field.as_mut(), subfield.take()) {
+                        field.subfields.push(subfield);
+                    }
+                }
+                b"datafield" => {
+                    if let (Some(Ok(record)), Some(field)) = (record.as_mut(), field.take()) {
+                        record.fields.push(field);
+                    }
+                }
+                b"record" => records.extend(record.take()),
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(records)
+}
+
+fn attribute(e: &BytesStart, name: &str) -> Result<String, String> {
+    e.try_get_attribute(name)
+        .ok()
+        .flatten()
+        .and_then(|attr| attr.unescape_value().ok())
+        .map(|value| value.into_owned())
+        .ok_or_else(|| format!("{} 属性がない要素があります", name))
+}
@@ -1,50 +1,122 @@
-use super::user::BookOwner;
+use super::{location::BookLocationResponse, tag::TagResponse, user::BookOwner};
 use derive_new::new;
 use garde::Validate;
 use kernel::model::{
     book::{
-        event::{CreateBook, UpdateBook},
-        Book, BookListOptions,
+        cover::{BookCover, BookCoverSize},
+        event::{CreateBook, PatchBook, UpdateBook},
+        history::{BookField, BookFieldChange, BookHistory, BookHistoryAction, BookHistoryEntry},
+        isbn::Isbn,
+        metadata::BookMetadata,
+        Book, BookCursorListOptions, BookListFilter, BookListOptions, BookListSort, BookSortKey,
+        BookTagFilter, TagMatchMode,
     },
-    id::{BookId, UserId},
-    list::PaginatedList,
+    id::{BookHistoryId, BookId, BranchId, ShelfId, UserId},
+    list::{CursorPaginatedList, PaginatedList, SortOrder},
 };
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use shared::error::AppError;
 
 use super::user::CheckoutUser;
 use chrono::{DateTime, Utc};
 use kernel::model::book::Checkout;
-use kernel::model::id::CheckoutId;
+use kernel::model::id::{CheckoutId, CopyId};
 
 // garde で蔵書登録時の文字数制約を追加
 // description は空文字でもよいので skip を指定している
-#[derive(Debug, Deserialize, Validate)]
+// ISBN 以外の項目は、書誌情報で補う場合に備えて省略できるようにしている（省略時は空文字）
+#[derive(Debug, Default, Serialize, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateBookRequest {
     #[garde(length(min = 1))]
+    #[serde(default)]
     pub title: String,
     #[garde(length(min = 1))]
+    #[serde(default)]
     pub author: String,
-    #[garde(length(min = 1))]
+    #[garde(custom(validate_isbn))]
     pub isbn: String,
     #[garde(skip)]
+    #[serde(default)]
     pub description: String,
 }
 
-impl From<CreateBookRequest> for CreateBook {
-    fn from(value: CreateBookRequest) -> Self {
+impl CreateBookRequest {
+    // 書誌情報から、蔵書登録のリクエストの値を組み立てる
+    // 書誌情報に含まれない項目は空文字になる
+    pub fn from_metadata(isbn: Isbn, metadata: BookMetadata) -> Self {
+        let mut req = Self {
+            isbn: isbn.into(),
+            ..Default::default()
+        };
+        req.fill_missing(metadata);
+        req
+    }
+
+    // 空の項目があるか
+    pub fn has_missing_fields(&self) -> bool {
+        self.title.is_empty() || self.author.is_empty() || self.description.is_empty()
+    }
+
+    // 空の項目を、書誌情報の値で補う
+    pub fn fill_missing(&mut self, metadata: BookMetadata) {
+        let BookMetadata {
+            title,
+            author,
+            description,
+        } = metadata;
+        for (field, value) in [
+            (&mut self.title, title),
+            (&mut self.author, author),
+            (&mut self.description, description),
+        ] {
+            if let (true, Some(value)) = (field.is_empty(), value) {
+                *field = value;
+            }
+        }
+    }
+}
+
+// 蔵書登録時のクエリ
+// fillMissing が true の場合は、省略した項目を ISBN から検索した書誌情報で補ってから登録する
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterBookQuery {
+    #[serde(default)]
+    pub fill_missing: bool,
+}
+
+// 書誌情報を検索する際のクエリ
+#[derive(Debug, Deserialize)]
+pub struct BookLookupQuery {
+    pub isbn: Isbn,
+}
+
+// ISBN-10 または ISBN-13 として正しい値かを検証する
+// ハイフンや空白を含んでいてもよい
+fn validate_isbn(value: &str, _context: &()) -> garde::Result {
+    value
+        .parse::<Isbn>()
+        .map(|_| ())
+        .map_err(|e| garde::Error::new(e.to_string()))
+}
+
+impl TryFrom<CreateBookRequest> for CreateBook {
+    type Error = AppError;
+
+    fn try_from(value: CreateBookRequest) -> Result<Self, Self::Error> {
         let CreateBookRequest {
             title,
             author,
             isbn,
             description,
         } = value;
-        CreateBook {
+        Ok(CreateBook {
             title,
             author,
-            isbn,
+            isbn: isbn.parse()?,
             description,
-        }
+        })
     }
 }
 
@@ -56,7 +128,7 @@ pub struct UpdateBookRequest {
     pub title: String,
     #[garde(length(min = 1))]
     pub author: String,
-    #[garde(length(min = 1))]
+    #[garde(custom(validate_isbn))]
     pub isbn: String,
     #[garde(skip)]
     pub description: String,
@@ -67,8 +139,10 @@ pub struct UpdateBookRequest {
 // UpdateBookRequest の 3 つの値のセットを UpdateBook 型に変換するための一時的な型
 #[derive(new)]
 pub struct UpdateBookRequestWithIds(BookId, UserId, UpdateBookRequest);
-impl From<UpdateBookRequestWithIds> for UpdateBook {
-    fn from(value: UpdateBookRequestWithIds) -> Self {
+impl TryFrom<UpdateBookRequestWithIds> for UpdateBook {
+    type Error = AppError;
+
+    fn try_from(value: UpdateBookRequestWithIds) -> Result<Self, Self::Error> {
         let UpdateBookRequestWithIds(
             book_id,
             user_id,
@@ -79,27 +153,218 @@ impl From<UpdateBookRequestWithIds> for UpdateBook {
                 description,
             },
         ) = value;
-        UpdateBook {
+        Ok(UpdateBook {
             book_id,
             title,
             author,
-            isbn,
+            isbn: isbn.parse()?,
             description,
             requested_user: user_id,
-        }
+            expected_version: None,
+        })
     }
 }
 
-// クエリで limit と offset を受け取るための型
+// 蔵書データの部分更新（JSON Merge Patch）用の型
+// 送られた項目だけを更新し、省略された項目は現在の値のままにする
+// title・author・isbn は必須の項目のため、null を指定した場合はエラーにする
+// description に null を指定した場合は、項目を削除したものとして空文字にする
+#[derive(Debug, Default, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchBookRequest {
+    #[garde(inner(length(min = 1)))]
+    #[serde(default, deserialize_with = "deserialize_non_null")]
+    pub title: Option<String>,
+    #[garde(inner(length(min = 1)))]
+    #[serde(default, deserialize_with = "deserialize_non_null")]
+    pub author: Option<String>,
+    #[garde(inner(custom(validate_isbn)))]
+    #[serde(default, deserialize_with = "deserialize_non_null")]
+    pub isbn: Option<String>,
+    #[garde(skip)]
+    #[serde(default, deserialize_with = "deserialize_null_as_default")]
+    pub description: Option<String>,
+}
+
+// 項目が存在する場合のみ呼ばれるため、値を Some で包んで返す。null はエラーになる
+pub(super) fn deserialize_non_null<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+// 項目が存在する場合のみ呼ばれるため、null は項目を削除したものとして既定値に置き換える
+fn deserialize_null_as_default<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    Option::<T>::deserialize(deserializer).map(|value| Some(value.unwrap_or_default()))
+}
+
+#[derive(new)]
+pub struct PatchBookRequestWithIds(BookId, UserId, PatchBookRequest);
+impl TryFrom<PatchBookRequestWithIds> for PatchBook {
+    type Error = AppError;
+
+    fn try_from(value: PatchBookRequestWithIds) -> Result<Self, Self::Error> {
+        let PatchBookRequestWithIds(
+            book_id,
+            user_id,
+            PatchBookRequest {
+                title,
+                author,
+                isbn,
+                description,
+            },
+        ) = value;
+        Ok(PatchBook {
+            book_id,
+            title,
+            author,
+            isbn: isbn.map(|isbn| isbn.parse()).transpose()?,
+            description,
+            requested_user: user_id,
+            expected_version: None,
+        })
+    }
+}
+
+// クエリで limit と offset、および絞り込み条件を受け取るための型
 // handler 側のメソッドで、クエリのデータを取得できる。
 #[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
 pub struct BookListQuery {
-    #[garde(range(min = 0))]
+    #[garde(range(min = 0, max = 100))]
     #[serde(default = "default_limit")]
     pub limit: i64,
     #[garde(range(min = 0))]
     #[serde(default)] // default は 0
     pub offset: i64,
+    // タイトル・著者名・ISBN・説明文を対象としたキーワード検索
+    #[garde(inner(length(min = 1)))]
+    pub q: Option<String>,
+    #[garde(inner(length(min = 1)))]
+    pub author: Option<String>,
+    // ハイフン区切りや ISBN-10 でも指定できる。不正な値は deserialize の時点で弾く
+    #[garde(skip)]
+    pub isbn: Option<Isbn>,
+    #[garde(skip)]
+    pub owner: Option<UserId>,
+    #[garde(skip)]
+    pub checked_out: Option<bool>,
+    // カンマ区切りで複数のタグの名前を指定する
+    #[garde(inner(length(min = 1)))]
+    pub tags: Option<String>,
+    // tags のうちいずれかが付いている蔵書（any）か、すべてが付いている蔵書（all）かを指定する
+    // 省略した場合は any とする
+    #[garde(skip)]
+    pub tag_match: Option<TagMatchName>,
+    // 館を指定した場合は、その館のいずれかの書架に配架されている蔵書を返す
+    #[garde(skip)]
+    pub branch: Option<BranchId>,
+    #[garde(skip)]
+    pub shelf: Option<ShelfId>,
+    // true の場合は削除済みの蔵書のみを返す（Admin のみ）
+    #[garde(skip)]
+    #[serde(default)]
+    pub deleted: bool,
+    #[garde(skip)]
+    pub sort: Option<BookSortKeyName>,
+    #[garde(skip)]
+    pub order: Option<SortOrderName>,
+    // 指定された場合は offset の代わりにカーソル方式でページネーションを行う
+    // 空文字の場合は先頭のページを返す
+    #[garde(skip)]
+    pub cursor: Option<String>,
+}
+
+// 並び替えのキーとして受け付ける値
+// 列挙型で受け取ることで、想定外の値は deserialize の時点で弾く
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BookSortKeyName {
+    Title,
+    Author,
+    CreatedAt,
+    UpdatedAt,
+    Isbn,
+}
+
+impl From<BookSortKeyName> for BookSortKey {
+    fn from(value: BookSortKeyName) -> Self {
+        match value {
+            BookSortKeyName::Title => Self::Title,
+            BookSortKeyName::Author => Self::Author,
+            BookSortKeyName::CreatedAt => Self::CreatedAt,
+            BookSortKeyName::UpdatedAt => Self::UpdatedAt,
+            BookSortKeyName::Isbn => Self::Isbn,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrderName {
+    Asc,
+    Desc,
+}
+
+impl From<SortOrderName> for SortOrder {
+    fn from(value: SortOrderName) -> Self {
+        match value {
+            SortOrderName::Asc => Self::Asc,
+            SortOrderName::Desc => Self::Desc,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatchName {
+    Any,
+    All,
+}
+
+impl From<TagMatchName> for TagMatchMode {
+    fn from(value: TagMatchName) -> Self {
+        match value {
+            TagMatchName::Any => Self::Any,
+            TagMatchName::All => Self::All,
+        }
+    }
+}
+
+// 空の名前は無視する
+fn book_tag_filter(tags: Option<String>, tag_match: Option<TagMatchName>) -> Option<BookTagFilter> {
+    let names = tags?
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect();
+    Some(BookTagFilter {
+        names,
+        mode: tag_match.map(TagMatchMode::from).unwrap_or_default(),
+    })
+}
+
+// sort も order も指定がない場合は登録日時の新しい順とする
+// sort のみ指定された場合は昇順とする
+fn book_list_sort(sort: Option<BookSortKeyName>, order: Option<SortOrderName>) -> BookListSort {
+    match (sort, order) {
+        (None, None) => BookListSort::default(),
+        (Some(key), order) => BookListSort {
+            key: key.into(),
+            order: order.map(SortOrder::from).unwrap_or(SortOrder::Asc),
+        },
+        (None, Some(order)) => BookListSort {
+            order: order.into(),
+            ..Default::default()
+        },
+    }
 }
 
 const DEFAULT_LIMIT: i64 = 20;
@@ -109,8 +374,57 @@ const fn default_limit() -> i64 {
 
 impl From<BookListQuery> for BookListOptions {
     fn from(value: BookListQuery) -> Self {
-        let BookListQuery { limit, offset } = value;
-        Self { limit, offset }
+        let BookListQuery {
+            limit,
+            offset,
+            q,
+            author,
+            isbn,
+            owner,
+            checked_out,
+            tags,
+            tag_match,
+            branch,
+            shelf,
+            deleted,
+            sort,
+            order,
+            cursor: _,
+        } = value;
+        Self {
+            limit,
+            offset,
+            filter: BookListFilter {
+                keyword: q,
+                author,
+                isbn,
+                owner,
+                checked_out,
+                tags: book_tag_filter(tags, tag_match),
+                branch,
+                shelf,
+                deleted,
+            },
+            sort: book_list_sort(sort, order),
+        }
+    }
+}
+
+impl From<BookListQuery> for BookCursorListOptions {
+    fn from(mut value: BookListQuery) -> Self {
+        let cursor = value.cursor.take().filter(|c| !c.is_empty());
+        let BookListOptions {
+            limit,
+            filter,
+            sort,
+            ..
+        } = value.into();
+        Self {
+            limit,
+            cursor,
+            filter,
+            sort,
+        }
     }
 }
 
@@ -124,7 +438,15 @@ pub struct BookResponse {
     pub isbn: String,
     pub description: String,
     pub owner: BookOwner,
-    pub checkout: Option<BookCheckoutResponse>,
+    pub total_copies: i64,
+    pub available_copies: i64,
+    pub checkouts: Vec<BookCheckoutResponse>,
+    // 表紙画像が登録されていなければ null
+    pub cover_url: Option<String>,
+    pub cover_thumbnail_url: Option<String>,
+    pub tags: Vec<TagResponse>,
+    // 配架場所が設定されていなければ null
+    pub location: Option<BookLocationResponse>,
 }
 
 impl From<Book> for BookResponse {
@@ -136,20 +458,50 @@ impl From<Book> for BookResponse {
             isbn,
             description,
             owner,
-            checkout,
+            copies,
+            checkouts,
+            cover,
+            tags,
+            location,
+            ..
         } = value;
+        let cover_url = |size| cover.as_ref().map(|cover| book_cover_url(id, size, cover));
         Self {
             id,
             title,
             author,
-            isbn,
+            isbn: isbn.into(),
             description,
             owner: owner.into(),
-            checkout: checkout.map(BookCheckoutResponse::from),
+            total_copies: copies.total,
+            available_copies: copies.available,
+            checkouts: checkouts
+                .into_iter()
+                .map(BookCheckoutResponse::from)
+                .collect(),
+            cover_url: cover_url(BookCoverSize::Original),
+            cover_thumbnail_url: cover_url(BookCoverSize::Thumbnail),
+            tags: tags.into_iter().map(TagResponse::from).collect(),
+            location: location.map(BookLocationResponse::from),
         }
     }
 }
 
+// 表紙画像を取得する API の URL
+// 差し替え前の画像がキャッシュから返らないよう、更新日時をクエリに含める
+fn book_cover_url(book_id: BookId, size: BookCoverSize, cover: &BookCover) -> String {
+    let path = match size {
+        BookCoverSize::Original => "cover",
+        BookCoverSize::Thumbnail => "cover/thumbnail",
+    };
+    format!(
+        "/api/v1/books/{}/{}?v={}",
+        book_id,
+        path,
+        cover.updated_at.timestamp_millis()
+    )
+}
+
 // api レイヤーでのページネーション表現用の型
 // 型の内部で持つフィールドは `PaginatedList<Book>` と同じであるが、
 // serde::Serialize を実装しているので JSON に変換してクライアントに返せる
@@ -179,26 +531,195 @@ impl From<PaginatedList<Book>> for PaginatedBookResponse {
     }
 }
 
+// カーソル方式のページネーション表現用の型
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorPaginatedBookResponse {
+    pub limit: i64,
+    pub items: Vec<BookResponse>,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+}
+
+impl From<CursorPaginatedList<Book>> for CursorPaginatedBookResponse {
+    fn from(value: CursorPaginatedList<Book>) -> Self {
+        let CursorPaginatedList {
+            limit,
+            items,
+            next_cursor,
+            prev_cursor,
+        } = value;
+        Self {
+            limit,
+            items: items.into_iter().map(BookResponse::from).collect(),
+            next_cursor,
+            prev_cursor,
+        }
+    }
+}
+
+// 蔵書一覧のレスポンス
+// ページネーションの方式によって返す JSON の形が異なる
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum BookListResponse {
+    Paginated(PaginatedBookResponse),
+    CursorPaginated(CursorPaginatedBookResponse),
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BookCheckoutResponse {
     pub id: CheckoutId,
+    pub copy_id: CopyId,
     pub checked_out_by: CheckoutUser,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
 }
 
 impl From<Checkout> for BookCheckoutResponse {
     fn from(value: Checkout) -> Self {
         let Checkout {
             checkout_id,
+            copy_id,
             checked_out_by,
             checked_out_at,
+            due_at,
         } = value;
         Self {
             id: checkout_id,
+            copy_id,
             checked_out_by: checked_out_by.into(),
-
             checked_out_at,
+            due_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookHistoryResponse {
+    pub book_id: BookId,
+    pub items: Vec<BookHistoryEntryResponse>,
+}
+
+impl From<BookHistory> for BookHistoryResponse {
+    fn from(value: BookHistory) -> Self {
+        let BookHistory {
+            book_id,
+            owned_by: _,
+            entries,
+        } = value;
+        Self {
+            book_id,
+            items: entries
+                .into_iter()
+                .map(BookHistoryEntryResponse::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookHistoryEntryResponse {
+    pub id: BookHistoryId,
+    pub action: BookHistoryActionName,
+    pub changed_by: Option<UserId>,
+    pub changed_at: DateTime<Utc>,
+    pub changes: Vec<BookFieldChangeResponse>,
+}
+
+impl From<BookHistoryEntry> for BookHistoryEntryResponse {
+    fn from(value: BookHistoryEntry) -> Self {
+        let BookHistoryEntry {
+            id,
+            action,
+            changed_by,
+            changed_at,
+            changes,
+        } = value;
+        Self {
+            id,
+            action: action.into(),
+            changed_by,
+            changed_at,
+            changes: changes
+                .into_iter()
+                .map(BookFieldChangeResponse::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BookHistoryActionName {
+    Create,
+    Update,
+    Delete,
+    Restore,
+    Purge,
+}
+
+impl From<BookHistoryAction> for BookHistoryActionName {
+    fn from(value: BookHistoryAction) -> Self {
+        match value {
+            BookHistoryAction::Create => Self::Create,
+            BookHistoryAction::Update => Self::Update,
+            BookHistoryAction::Delete => Self::Delete,
+            BookHistoryAction::Restore => Self::Restore,
+            BookHistoryAction::Purge => Self::Purge,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BookFieldName {
+    Title,
+    Author,
+    Isbn,
+    Description,
+    Cover,
+    Tags,
+    Shelf,
+}
+
+impl From<BookField> for BookFieldName {
+    fn from(value: BookField) -> Self {
+        match value {
+            BookField::Title => Self::Title,
+            BookField::Author => Self::Author,
+            BookField::Isbn => Self::Isbn,
+            BookField::Description => Self::Description,
+            BookField::Cover => Self::Cover,
+            BookField::Tags => Self::Tags,
+            BookField::Shelf => Self::Shelf,
+        }
+    }
+}
+
+// 項目の変更前後の値。登録時は before が、完全に削除した時は after が null になる
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookFieldChangeResponse {
+    pub field: BookFieldName,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+impl From<BookFieldChange> for BookFieldChangeResponse {
+    fn from(value: BookFieldChange) -> Self {
+        let BookFieldChange {
+            field,
+            before,
+            after,
+        } = value;
+        Self {
+            field: field.into(),
+            before,
+            after,
         }
     }
 }
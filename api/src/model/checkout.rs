@@ -1,20 +1,254 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use derive_new::new;
+use garde::Validate;
 use kernel::model::{
-    checkout::{Checkout, CheckoutBook},
-    id::{BookId, CheckoutId, UserId},
+    checkout::{
+        event::{CreateCheckout, UpdateReturned},
+        Checkout, CheckoutBook, CheckoutHistoryOptions, ReturnOutcome,
+    },
+    id::{BookId, BranchId, CheckoutId, CopyId, UserId},
+    list::{CursorListOptions, CursorPaginatedList},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+// 貸出一覧をカーソル方式で取得する際のクエリ
+// limit と cursor のどちらも指定しない場合は、ページ分割せずに全件を返す
+// cursor のみを指定した場合は、既定の件数ずつ返す
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutListQuery {
+    #[garde(range(min = 0, max = 100))]
+    pub limit: Option<i64>,
+    #[garde(skip)]
+    pub cursor: Option<String>,
+    // true の場合は返却期限を過ぎた貸出のみを返す（貸出中の一覧でのみ使用する）
+    #[garde(skip)]
+    #[serde(default)]
+    pub overdue: bool,
+}
+
+// ユーザーの貸出履歴をカーソル方式で取得する際のクエリ
+// from / to を指定した場合は、貸出日がその期間（両端の日付を含む）のものに絞り込む
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutHistoryQuery {
+    #[garde(range(min = 0, max = 100))]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[garde(skip)]
+    pub cursor: Option<String>,
+    #[garde(skip)]
+    pub from: Option<NaiveDate>,
+    #[garde(custom(validate_date_range(&self.from)))]
+    pub to: Option<NaiveDate>,
+}
+
+// 期間の終わりが始まりより前になっていないかを検証する
+fn validate_date_range(
+    from: &Option<NaiveDate>,
+) -> impl FnOnce(&Option<NaiveDate>, &()) -> garde::Result + '_ {
+    move |to, _| match (from, to) {
+        (Some(from), Some(to)) if from > to => {
+            Err(garde::Error::new("to must not be earlier than from"))
+        }
+        _ => Ok(()),
+    }
+}
+
+impl From<CheckoutHistoryQuery> for CheckoutHistoryOptions {
+    fn from(value: CheckoutHistoryQuery) -> Self {
+        let CheckoutHistoryQuery {
+            limit,
+            cursor,
+            from,
+            to,
+        } = value;
+        // 日付は UTC の 0 時として扱い、to の日付は翌日の 0 時未満までを含める
+        Self {
+            limit: Some(limit),
+            cursor,
+            checked_out_from: from.map(|d| d.and_time(NaiveTime::MIN).and_utc()),
+            checked_out_to: to
+                .and_then(|d| d.succ_opt())
+                .map(|d| d.and_time(NaiveTime::MIN).and_utc()),
+        }
+    }
+}
+
+const DEFAULT_LIMIT: i64 = 20;
+const fn default_limit() -> i64 {
+    DEFAULT_LIMIT
+}
+
+impl From<CheckoutListQuery> for CursorListOptions {
+    fn from(value: CheckoutListQuery) -> Self {
+        let CheckoutListQuery {
+            limit,
+            cursor,
+            overdue: _,
+        } = value;
+        let limit = match (limit, &cursor) {
+            (None, None) => None,
+            (limit, _) => Some(limit.unwrap_or(DEFAULT_LIMIT)),
+        };
+        Self { limit, cursor }
+    }
+}
+
+// 返却時の所蔵資料の状態
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReturnOutcomeName {
+    #[default]
+    Ok,
+    Damaged,
+    Lost,
+}
+
+impl From<ReturnOutcome> for ReturnOutcomeName {
+    fn from(value: ReturnOutcome) -> Self {
+        match value {
+            ReturnOutcome::Ok => Self::Ok,
+            ReturnOutcome::Damaged => Self::Damaged,
+            ReturnOutcome::Lost => Self::Lost,
+        }
+    }
+}
+
+impl From<ReturnOutcomeName> for ReturnOutcome {
+    fn from(value: ReturnOutcomeName) -> Self {
+        match value {
+            ReturnOutcomeName::Ok => Self::Ok,
+            ReturnOutcomeName::Damaged => Self::Damaged,
+            ReturnOutcomeName::Lost => Self::Lost,
+        }
+    }
+}
+
+// 利用者自身が貸し出す際のリクエストボディ
+// ボディを省略した場合は、手続きをした館を記録しない
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutBookRequest {
+    pub branch_id: Option<BranchId>,
+}
+
+// 管理者が借りるユーザーに代わって貸し出す際のリクエストボディ
+// 所蔵資料を指定しない場合は、貸出可能な所蔵資料のいずれかを貸し出す
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutOnBehalfRequest {
+    #[garde(skip)]
+    pub book_id: BookId,
+    #[garde(skip)]
+    pub copy_id: Option<CopyId>,
+    // 貸出の手続きをした館
+    #[garde(skip)]
+    pub branch_id: Option<BranchId>,
+}
+
+// パスパラメータからの借りるユーザーの UserId、
+// リクエスト時に AuthorizedUser から取り出す管理者の UserId、
+// CheckoutOnBehalfRequest の値のセットを CreateCheckout 型に変換するための型
+#[derive(new)]
+pub struct CheckoutOnBehalfRequestWithIds(UserId, UserId, CheckoutOnBehalfRequest);
+impl From<CheckoutOnBehalfRequestWithIds> for CreateCheckout {
+    fn from(value: CheckoutOnBehalfRequestWithIds) -> Self {
+        let CheckoutOnBehalfRequestWithIds(
+            checked_out_by,
+            processed_by,
+            CheckoutOnBehalfRequest {
+                book_id,
+                copy_id,
+                branch_id,
+            },
+        ) = value;
+        Self {
+            book_id,
+            copy_id,
+            checked_out_by,
+            checked_out_at: Utc::now(),
+            processed_by: Some(processed_by),
+            branch_id,
+        }
+    }
+}
+
+// 返却時のリクエストボディ
+// ボディを省略した場合や outcome を省略した場合は通常の返却として扱う
+#[derive(Debug, Default, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnBookRequest {
+    #[garde(skip)]
+    #[serde(default)]
+    pub outcome: ReturnOutcomeName,
+    #[garde(inner(length(min = 1)))]
+    pub note: Option<String>,
+    // 返却の手続きをした館
+    #[garde(skip)]
+    pub branch_id: Option<BranchId>,
+}
+
+// パスパラメータからの CheckoutId と BookId、
+// リクエスト時に AuthorizedUser から取り出す UserId、
+// ReturnBookRequest の値のセットを UpdateReturned 型に変換するための型
+#[derive(new)]
+pub struct ReturnBookRequestWithIds(CheckoutId, BookId, UserId, ReturnBookRequest);
+impl From<ReturnBookRequestWithIds> for UpdateReturned {
+    fn from(value: ReturnBookRequestWithIds) -> Self {
+        let ReturnBookRequestWithIds(
+            checkout_id,
+            book_id,
+            returned_by,
+            ReturnBookRequest {
+                outcome,
+                note,
+                branch_id,
+            },
+        ) = value;
+        Self {
+            checkout_id,
+            book_id,
+            returned_by,
+            returned_at: Utc::now(),
+            outcome: outcome.into(),
+            note,
+            by_admin: false,
+            branch_id,
+        }
+    }
+}
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CheckoutsResponse {
     pub items: Vec<CheckoutResponse>,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
 }
 
 impl From<Vec<Checkout>> for CheckoutsResponse {
     fn from(value: Vec<Checkout>) -> Self {
         Self {
             items: value.into_iter().map(CheckoutResponse::from).collect(),
+            next_cursor: None,
+            prev_cursor: None,
+        }
+    }
+}
+
+impl From<CursorPaginatedList<Checkout>> for CheckoutsResponse {
+    fn from(value: CursorPaginatedList<Checkout>) -> Self {
+        let CursorPaginatedList {
+            items,
+            next_cursor,
+            prev_cursor,
+            ..
+        } = value;
+        Self {
+            items: items.into_iter().map(CheckoutResponse::from).collect(),
+            next_cursor,
+            prev_cursor,
         }
     }
 }
@@ -23,9 +257,19 @@ impl From<Vec<Checkout>> for CheckoutsResponse {
 #[serde(rename_all = "camelCase")]
 pub struct CheckoutResponse {
     pub id: CheckoutId,
+    pub copy_id: CopyId,
     pub checked_out_by: UserId,
     pub checked_out_at: DateTime<Utc>,
+    pub checkout_processed_by: UserId,
+    // 手続きをした館が記録されていなければ null
+    pub checkout_branch_id: Option<BranchId>,
+    pub due_at: DateTime<Utc>,
+    pub renewal_count: i32,
     pub returned_at: Option<DateTime<Utc>>,
+    pub return_outcome: Option<ReturnOutcomeName>,
+    pub return_note: Option<String>,
+    pub return_processed_by: Option<UserId>,
+    pub return_branch_id: Option<BranchId>,
     pub book: CheckoutBookResponse,
 }
 
@@ -33,16 +277,34 @@ impl From<Checkout> for CheckoutResponse {
     fn from(value: Checkout) -> Self {
         let Checkout {
             id,
+            copy_id,
             checked_out_by,
             checked_out_at,
+            checkout_processed_by,
+            checkout_branch_id,
+            due_at,
+            renewal_count,
             returned_at,
+            return_outcome,
+            return_note,
+            return_processed_by,
+            return_branch_id,
             book,
         } = value;
         Self {
             id,
+            copy_id,
             checked_out_by,
             checked_out_at,
+            checkout_processed_by,
+            checkout_branch_id,
+            due_at,
+            renewal_count,
             returned_at,
+            return_outcome: return_outcome.map(ReturnOutcomeName::from),
+            return_note,
+            return_processed_by,
+            return_branch_id,
             book: book.into(),
         }
     }
@@ -0,0 +1,179 @@
+use super::book::BookCheckoutResponse;
+use chrono::{NaiveDate, Utc};
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    copy::{
+        event::{CreateCopy, UpdateCopy},
+        BookCopy, CopyCondition,
+    },
+    id::{BookId, CopyId, UserId},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CopyConditionName {
+    New,
+    Good,
+    Fair,
+    Poor,
+    Damaged,
+    Lost,
+}
+
+impl From<CopyCondition> for CopyConditionName {
+    fn from(value: CopyCondition) -> Self {
+        match value {
+            CopyCondition::New => Self::New,
+            CopyCondition::Good => Self::Good,
+            CopyCondition::Fair => Self::Fair,
+            CopyCondition::Poor => Self::Poor,
+            CopyCondition::Damaged => Self::Damaged,
+            CopyCondition::Lost => Self::Lost,
+        }
+    }
+}
+
+impl From<CopyConditionName> for CopyCondition {
+    fn from(value: CopyConditionName) -> Self {
+        match value {
+            CopyConditionName::New => Self::New,
+            CopyConditionName::Good => Self::Good,
+            CopyConditionName::Fair => Self::Fair,
+            CopyConditionName::Poor => Self::Poor,
+            CopyConditionName::Damaged => Self::Damaged,
+            CopyConditionName::Lost => Self::Lost,
+        }
+    }
+}
+
+// 所蔵資料の登録用の型
+// 受け入れ日を省略した場合は当日、状態を省略した場合は Good とする
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCopyRequest {
+    #[garde(inner(length(min = 1)))]
+    pub barcode: Option<String>,
+    #[garde(skip)]
+    pub acquired_on: Option<NaiveDate>,
+    #[garde(skip)]
+    pub condition: Option<CopyConditionName>,
+    #[garde(inner(length(min = 1)))]
+    pub shelf_location: Option<String>,
+}
+
+#[derive(new)]
+pub struct CreateCopyRequestWithIds(BookId, UserId, CreateCopyRequest);
+impl From<CreateCopyRequestWithIds> for CreateCopy {
+    fn from(value: CreateCopyRequestWithIds) -> Self {
+        let CreateCopyRequestWithIds(
+            book_id,
+            user_id,
+            CreateCopyRequest {
+                barcode,
+                acquired_on,
+                condition,
+                shelf_location,
+            },
+        ) = value;
+        CreateCopy {
+            book_id,
+            barcode,
+            acquired_on: acquired_on.unwrap_or_else(|| Utc::now().date_naive()),
+            condition: condition.map(CopyCondition::from).unwrap_or_default(),
+            shelf_location,
+            requested_user: user_id,
+        }
+    }
+}
+
+// 所蔵資料の更新用の型
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCopyRequest {
+    #[garde(inner(length(min = 1)))]
+    pub barcode: Option<String>,
+    #[garde(skip)]
+    pub acquired_on: NaiveDate,
+    #[garde(skip)]
+    pub condition: CopyConditionName,
+    #[garde(inner(length(min = 1)))]
+    pub shelf_location: Option<String>,
+}
+
+#[derive(new)]
+pub struct UpdateCopyRequestWithIds(BookId, CopyId, UserId, UpdateCopyRequest);
+impl From<UpdateCopyRequestWithIds> for UpdateCopy {
+    fn from(value: UpdateCopyRequestWithIds) -> Self {
+        let UpdateCopyRequestWithIds(
+            book_id,
+            copy_id,
+            user_id,
+            UpdateCopyRequest {
+                barcode,
+                acquired_on,
+                condition,
+                shelf_location,
+            },
+        ) = value;
+        UpdateCopy {
+            copy_id,
+            book_id,
+            barcode,
+            acquired_on,
+            condition: condition.into(),
+            shelf_location,
+            requested_user: user_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookCopiesResponse {
+    pub items: Vec<BookCopyResponse>,
+}
+
+impl From<Vec<BookCopy>> for BookCopiesResponse {
+    fn from(value: Vec<BookCopy>) -> Self {
+        Self {
+            items: value.into_iter().map(BookCopyResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookCopyResponse {
+    pub id: CopyId,
+    pub book_id: BookId,
+    pub barcode: Option<String>,
+    pub acquired_on: NaiveDate,
+    pub condition: CopyConditionName,
+    pub shelf_location: Option<String>,
+    pub checkout: Option<BookCheckoutResponse>,
+}
+
+impl From<BookCopy> for BookCopyResponse {
+    fn from(value: BookCopy) -> Self {
+        let BookCopy {
+            id,
+            book_id,
+            barcode,
+            acquired_on,
+            condition,
+            shelf_location,
+            checkout,
+        } = value;
+        Self {
+            id,
+            book_id,
+            barcode,
+            acquired_on,
+            condition: condition.into(),
+            shelf_location,
+            checkout: checkout.map(BookCheckoutResponse::from),
+        }
+    }
+}
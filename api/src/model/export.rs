@@ -0,0 +1,193 @@
+use super::book::BookResponse;
+use crate::csv;
+use serde::Deserialize;
+use shared::error::{AppError, AppResult};
+
+// 蔵書を出力する際のクエリ
+// 絞り込み条件と並び順は、蔵書一覧と同じクエリ（BookListQuery）で受け取る
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookExportQuery {
+    pub format: BookExportFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BookExportFormat {
+    Csv,
+    Jsonl,
+    Marcxml,
+}
+
+const CSV_HEADER: [&str; 11] = [
+    "id",
+    "title",
+    "author",
+    "isbn",
+    "description",
+    "ownerId",
+    "ownerName",
+    "totalCopies",
+    "availableCopies",
+    "checkedOutCopies",
+    "nextDueAt",
+];
+
+impl BookExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => csv::CONTENT_TYPE,
+            Self::Jsonl => "application/jsonl; charset=utf-8",
+            Self::Marcxml => "application/marcxml+xml; charset=utf-8",
+        }
+    }
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Self::Csv => "books.csv",
+            Self::Jsonl => "books.jsonl",
+            Self::Marcxml => "books.xml",
+        }
+    }
+
+    // 蔵書の前に出力する内容
+    pub fn header(&self) -> String {
+        match self {
+            Self::Csv => {
+                let mut out = String::new();
+                csv::push_record(&mut out, CSV_HEADER);
+                out
+            }
+            Self::Jsonl => String::new(),
+            Self::Marcxml => concat!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+                "\n",
+                r#"<collection xmlns="http://www.loc.gov/MARC21/slim">"#,
+                "\n"
+            )
+            .into(),
+        }
+    }
+
+    // 蔵書の後に出力する内容
+    pub fn footer(&self) -> String {
+        match self {
+            Self::Csv | Self::Jsonl => String::new(),
+            Self::Marcxml => "</collection>\n".into(),
+        }
+    }
+
+    // 蔵書 1 冊分を出力する
+    pub fn push_book(&self, out: &mut String, book: &BookResponse) -> AppResult<()> {
+        match self {
+            Self::Csv => push_csv_record(out, book),
+            Self::Jsonl => {
+                let line = serde_json::to_string(book)
+                    .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+                out.push_str(&line);
+                out.push('\n');
+            }
+            Self::Marcxml => push_marc_record(out, book),
+        }
+        Ok(())
+    }
+}
+
+fn push_csv_record(out: &mut String, book: &BookResponse) {
+    // 貸出中の所蔵資料のうち、最も早い返却期限
+    let next_due_at = book
+        .checkouts
+        .iter()
+        .map(|c| c.due_at)
+        .min()
+        .map(|d| d.to_rfc3339())
+        .unwrap_or_default();
+    csv::push_record(
+        out,
+        [
+            book.id.to_string(),
+            book.title.clone(),
+            book.author.clone(),
+            book.isbn.clone(),
+            book.description.clone(),
+            book.owner.id.to_string(),
+            book.owner.name.clone(),
+            book.total_copies.to_string(),
+            book.available_copies.to_string(),
+            book.checkouts.len().to_string(),
+            next_due_at,
+        ],
+    );
+}
+
+// MARC21 の書誌レコードとして出力する
+// 001 に蔵書 ID、020 に ISBN、100 に著者名、245 にタイトル、520 に説明文を入れる
+// 所有者と所蔵資料の貸出状況はローカルフィールド 999 に入れる
+// （$a 所有者 ID、$b 所有者名、$c 所蔵資料数、$d 貸出可能な数、$e 貸出中の数）
+fn push_marc_record(out: &mut String, book: &BookResponse) {
+    out.push_str("  <record>\n");
+    out.push_str("    <leader>00000nam a2200000   4500</leader>\n");
+    out.push_str(&format!(
+        "    <controlfield tag=\"001\">{}</controlfield>\n",
+        book.id
+    ));
+    push_marc_datafield(out, "020", "  ", &[('a', book.isbn.clone())]);
+    push_marc_datafield(out, "100", "1 ", &[('a', book.author.clone())]);
+    push_marc_datafield(out, "245", "10", &[('a', book.title.clone())]);
+    if !book.description.is_empty() {
+        push_marc_datafield(out, "520", "  ", &[('a', book.description.clone())]);
+    }
+    push_marc_datafield(
+        out,
+        "999",
+        "  ",
+        &[
+            ('a', book.owner.id.to_string()),
+            ('b', book.owner.name.clone()),
+            ('c', book.total_copies.to_string()),
+            ('d', book.available_copies.to_string()),
+            ('e', book.checkouts.len().to_string()),
+        ],
+    );
+    out.push_str("  </record>\n");
+}
+
+fn push_marc_datafield(
+    out: &mut String,
+    tag: &str,
+    indicators: &str,
+    subfields: &[(char, String)],
+) {
+    let mut ind = indicators.chars();
+    out.push_str(&format!(
+        "    <datafield tag=\"{}\" ind1=\"{}\" ind2=\"{}\">\n",
+        tag,
+        ind.next().unwrap_or(' '),
+        ind.next().unwrap_or(' ')
+    ));
+    for (code, value) in subfields {
+        out.push_str(&format!(
+            "      <subfield code=\"{}\">{}</subfield>\n",
+            code,
+            escape_xml(value)
+        ));
+    }
+    out.push_str("    </datafield>\n");
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // XML 1.0 で使えない制御文字は出力しない
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
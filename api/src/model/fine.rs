@@ -0,0 +1,124 @@
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    fine::{
+        event::{CreateFineCredit, FineCreditKind},
+        FineEntry, FineEntryKind, UserFines,
+    },
+    id::{CheckoutId, FineEntryId, UserId},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinesResponse {
+    pub user_id: UserId,
+    pub balance: i64,
+    pub items: Vec<FineEntryResponse>,
+}
+
+impl From<UserFines> for FinesResponse {
+    fn from(value: UserFines) -> Self {
+        let UserFines {
+            user_id,
+            balance,
+            entries,
+        } = value;
+        Self {
+            user_id,
+            balance,
+            items: entries.into_iter().map(FineEntryResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FineEntryKindName {
+    Fine,
+    Replacement,
+    Payment,
+    Waiver,
+}
+
+impl From<FineEntryKind> for FineEntryKindName {
+    fn from(value: FineEntryKind) -> Self {
+        match value {
+            FineEntryKind::Fine => Self::Fine,
+            FineEntryKind::Replacement => Self::Replacement,
+            FineEntryKind::Payment => Self::Payment,
+            FineEntryKind::Waiver => Self::Waiver,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FineEntryResponse {
+    pub id: FineEntryId,
+    pub checkout_id: Option<CheckoutId>,
+    pub kind: FineEntryKindName,
+    pub amount: i64,
+    pub note: Option<String>,
+    pub recorded_by: Option<UserId>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl From<FineEntry> for FineEntryResponse {
+    fn from(value: FineEntry) -> Self {
+        let FineEntry {
+            id,
+            user_id: _,
+            checkout_id,
+            kind,
+            amount,
+            note,
+            recorded_by,
+            recorded_at,
+        } = value;
+        Self {
+            id,
+            checkout_id,
+            kind: kind.into(),
+            amount,
+            note,
+            recorded_by,
+            recorded_at,
+        }
+    }
+}
+
+// 延滞料金の支払い・免除の記録用の型
+// 金額は 1 円以上とし、残高を超えていないかはリポジトリ側で調べる
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateFineCreditRequest {
+    #[garde(range(min = 1))]
+    pub amount: i64,
+    #[garde(inner(length(min = 1)))]
+    pub note: Option<String>,
+}
+
+// パスパラメータからの UserId、記録する管理者の UserId、
+// 支払いか免除かの種別と CreateFineCreditRequest をまとめて CreateFineCredit 型に変換するための型
+#[derive(new)]
+pub struct CreateFineCreditRequestWithIds(UserId, UserId, FineCreditKind, CreateFineCreditRequest);
+impl From<CreateFineCreditRequestWithIds> for CreateFineCredit {
+    fn from(value: CreateFineCreditRequestWithIds) -> Self {
+        let CreateFineCreditRequestWithIds(
+            user_id,
+            recorded_by,
+            kind,
+            CreateFineCreditRequest { amount, note },
+        ) = value;
+        Self {
+            user_id,
+            kind,
+            amount,
+            note,
+            recorded_by,
+            recorded_at: Utc::now(),
+        }
+    }
+}
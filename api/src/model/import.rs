@@ -0,0 +1,227 @@
+use super::book::CreateBookRequest;
+use crate::{
+    csv::{self, CsvRecord},
+    marc::{self, MarcRecord},
+};
+use garde::Validate;
+use kernel::model::book::{event::CreateBook, isbn::Isbn, IsbnConflict, IsbnConflictKind};
+use serde::{Deserialize, Serialize};
+use shared::error::{AppError, AppResult};
+
+// 蔵書を一括登録する際のクエリ
+// dryRun が true の場合は検証のみ行い、蔵書は登録しない
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookImportQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// 一括登録するファイルを読み取った結果
+// 検証を通ったレコードは books に、通らなかったレコードは errors に入れる
+pub struct BookImportRows {
+    pub total: usize,
+    pub books: Vec<BookImportRow>,
+    pub errors: Vec<BookImportError>,
+}
+
+// 検証を通ったレコードと、そのファイル中の位置
+pub struct BookImportRow {
+    pub record: usize,
+    pub line: Option<usize>,
+    pub book: CreateBook,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookImportError {
+    // ファイル中で何件目のレコードか（CSV の見出し行は数えない）
+    pub record: usize,
+    // CSV の場合の、ファイル中の行番号（見出し行を 1 行目とする）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl BookImportError {
+    // ISBN の重複により登録しなかったレコードのエラー
+    pub fn isbn_conflict(record: usize, line: Option<usize>, conflict: &IsbnConflict) -> Self {
+        let isbn = conflict.isbn.hyphenated();
+        let message = match conflict.kind {
+            IsbnConflictKind::Registered => format!("ISBN {} の蔵書はすでに登録されています", isbn),
+            IsbnConflictKind::Batch => format!("ISBN {} の蔵書がファイル内で重複しています", isbn),
+        };
+        Self {
+            record,
+            line,
+            message,
+        }
+    }
+}
+
+impl BookImportRows {
+    // 見出し行のある CSV を読み取る
+    // 見出しは title・author・isbn・description（省略可）で、列の順序は問わず、それ以外の列は無視する
+    // 各行は蔵書の登録 API と同じ検証を行う
+    pub fn from_csv(input: &str) -> AppResult<Self> {
+        let mut records = csv::parse(input)?.into_iter();
+        let header = records
+            .next()
+            .ok_or_else(|| AppError::InvalidCsv("見出し行がありません".into()))?;
+        let columns = BookCsvColumns::from_header(&header)?;
+
+        let mut rows = Self::new();
+        for record in records {
+            rows.push(Some(record.line), columns.to_create_book(&record));
+        }
+        Ok(rows)
+    }
+
+    // MARC21（バイナリ形式または MARCXML）のレコードを読み取る
+    // 020 $a を ISBN、100 $a を著者名、245 $a・$b をタイトル、520 $a を説明文とし、
+    // 蔵書の登録 API と同じ検証を行う
+    pub fn from_marc(input: &[u8]) -> AppResult<Self> {
+        let mut rows = Self::new();
+        for record in marc::parse(input)? {
+            rows.push(None, record.and_then(|r| marc_to_create_book(&r)));
+        }
+        Ok(rows)
+    }
+
+    fn new() -> Self {
+        Self {
+            total: 0,
+            books: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, line: Option<usize>, result: Result<CreateBook, String>) {
+        self.total += 1;
+        match result {
+            Ok(book) => self.books.push(BookImportRow {
+                record: self.total,
+                line,
+                book,
+            }),
+            Err(message) => self.errors.push(BookImportError {
+                record: self.total,
+                line,
+                message,
+            }),
+        }
+    }
+}
+
+// 蔵書の登録 API と同じ検証を行い、CreateBook に変換する
+fn validate_create_book(req: CreateBookRequest) -> Result<CreateBook, String> {
+    req.validate(&()).map_err(|e| e.to_string())?;
+    CreateBook::try_from(req).map_err(|e| e.to_string())
+}
+
+fn marc_to_create_book(record: &MarcRecord) -> Result<CreateBook, String> {
+    // 020 $a には「9784065369579 (pbk.)」のように補足が付くことがあるため、最初の語のみを使う
+    // 020 が複数ある場合は、ISBN として正しい最初のものを使う
+    let isbns = record
+        .subfields("020", 'a')
+        .filter_map(|isbn| isbn.split_whitespace().next())
+        .collect::<Vec<_>>();
+    let isbn = isbns
+        .iter()
+        .find(|isbn| isbn.parse::<Isbn>().is_ok())
+        .or(isbns.first())
+        .ok_or("020 $a（ISBN）がありません")?;
+    let author = record
+        .subfields("100", 'a')
+        .next()
+        .ok_or("100 $a（著者名）がありません")?;
+    let title = record
+        .subfields("245", 'a')
+        .chain(record.subfields("245", 'b'))
+        .map(trim_isbd_punctuation)
+        .collect::<Vec<_>>();
+    if title.is_empty() {
+        return Err("245 $a（タイトル）がありません".into());
+    }
+    let description = record
+        .subfields("520", 'a')
+        .map(str::trim)
+        .collect::<Vec<_>>();
+
+    validate_create_book(CreateBookRequest {
+        title: title.join(" : "),
+        author: trim_isbd_punctuation(author).to_string(),
+        isbn: isbn.to_string(),
+        description: description.join("\n"),
+    })
+}
+
+// MARC の値の末尾に付く区切り記号（ISBD の区切り記号）を取り除く
+fn trim_isbd_punctuation(value: &str) -> &str {
+    value
+        .trim()
+        .trim_end_matches([' ', ',', '/', ':', ';', '.', '='])
+}
+
+// 見出し行から求めた、各項目の列の位置
+struct BookCsvColumns {
+    len: usize,
+    title: usize,
+    author: usize,
+    isbn: usize,
+    description: Option<usize>,
+}
+
+impl BookCsvColumns {
+    fn from_header(header: &CsvRecord) -> AppResult<Self> {
+        let position = |name: &str| {
+            header
+                .fields
+                .iter()
+                .position(|f| f.trim().eq_ignore_ascii_case(name))
+        };
+        let required = |name: &str| {
+            position(name).ok_or_else(|| {
+                AppError::InvalidCsv(format!("見出し行に {} の列がありません", name))
+            })
+        };
+        Ok(Self {
+            len: header.fields.len(),
+            title: required("title")?,
+            author: required("author")?,
+            isbn: required("isbn")?,
+            description: position("description"),
+        })
+    }
+
+    fn to_create_book(&self, record: &CsvRecord) -> Result<CreateBook, String> {
+        if record.fields.len() != self.len {
+            return Err(format!(
+                "列の数（{}）が見出し行の列の数（{}）と一致しません",
+                record.fields.len(),
+                self.len
+            ));
+        }
+        let field = |i: usize| record.fields[i].trim().to_string();
+        let req = CreateBookRequest {
+            title: field(self.title),
+            author: field(self.author),
+            isbn: field(self.isbn),
+            description: self.description.map(field).unwrap_or_default(),
+        };
+        validate_create_book(req)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookImportResponse {
+    pub dry_run: bool,
+    // 見出し行を除いた行数
+    pub total_rows: usize,
+    // 検証を通り、ISBN も重複していなかった行数
+    pub valid_rows: usize,
+    // 登録した蔵書の数。dryRun の場合は 0 になる
+    pub imported_rows: usize,
+    pub errors: Vec<BookImportError>,
+}
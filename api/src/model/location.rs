@@ -0,0 +1,180 @@
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    book::event::UpdateBookLocation,
+    id::{BookId, BranchId, ShelfId, UserId},
+    location::{
+        event::{CreateBranch, CreateShelf, UpdateBranch, UpdateShelf},
+        BookLocation, Branch, Shelf,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBranchRequest {
+    #[garde(length(min = 1, max = 64))]
+    pub name: String,
+}
+
+impl From<CreateBranchRequest> for CreateBranch {
+    fn from(value: CreateBranchRequest) -> Self {
+        let CreateBranchRequest { name } = value;
+        CreateBranch { name }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateBranchRequest {
+    #[garde(length(min = 1, max = 64))]
+    pub name: String,
+}
+
+#[derive(new)]
+pub struct UpdateBranchRequestWithIds(BranchId, UpdateBranchRequest);
+impl From<UpdateBranchRequestWithIds> for UpdateBranch {
+    fn from(value: UpdateBranchRequestWithIds) -> Self {
+        let UpdateBranchRequestWithIds(branch_id, UpdateBranchRequest { name }) = value;
+        UpdateBranch { branch_id, name }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateShelfRequest {
+    #[garde(length(min = 1, max = 64))]
+    pub name: String,
+}
+
+#[derive(new)]
+pub struct CreateShelfRequestWithIds(BranchId, CreateShelfRequest);
+impl From<CreateShelfRequestWithIds> for CreateShelf {
+    fn from(value: CreateShelfRequestWithIds) -> Self {
+        let CreateShelfRequestWithIds(branch_id, CreateShelfRequest { name }) = value;
+        CreateShelf { branch_id, name }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateShelfRequest {
+    #[garde(length(min = 1, max = 64))]
+    pub name: String,
+}
+
+#[derive(new)]
+pub struct UpdateShelfRequestWithIds(BranchId, ShelfId, UpdateShelfRequest);
+impl From<UpdateShelfRequestWithIds> for UpdateShelf {
+    fn from(value: UpdateShelfRequestWithIds) -> Self {
+        let UpdateShelfRequestWithIds(branch_id, shelf_id, UpdateShelfRequest { name }) = value;
+        UpdateShelf {
+            shelf_id,
+            branch_id,
+            name,
+        }
+    }
+}
+
+// 蔵書を配架する書架の指定
+// 館は書架から決まるため、書架のみを指定する。null を指定すると配架場所が未設定に戻る
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateBookLocationRequest {
+    pub shelf_id: Option<ShelfId>,
+}
+
+#[derive(new)]
+pub struct UpdateBookLocationRequestWithIds(BookId, UserId, UpdateBookLocationRequest);
+impl From<UpdateBookLocationRequestWithIds> for UpdateBookLocation {
+    fn from(value: UpdateBookLocationRequestWithIds) -> Self {
+        let UpdateBookLocationRequestWithIds(
+            book_id,
+            user_id,
+            UpdateBookLocationRequest { shelf_id },
+        ) = value;
+        UpdateBookLocation::new(book_id, shelf_id, user_id)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchesResponse {
+    pub items: Vec<BranchResponse>,
+}
+
+impl From<Vec<Branch>> for BranchesResponse {
+    fn from(value: Vec<Branch>) -> Self {
+        Self {
+            items: value.into_iter().map(BranchResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchResponse {
+    pub id: BranchId,
+    pub name: String,
+}
+
+impl From<Branch> for BranchResponse {
+    fn from(value: Branch) -> Self {
+        let Branch { id, name } = value;
+        Self { id, name }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShelvesResponse {
+    pub items: Vec<ShelfResponse>,
+}
+
+impl From<Vec<Shelf>> for ShelvesResponse {
+    fn from(value: Vec<Shelf>) -> Self {
+        Self {
+            items: value.into_iter().map(ShelfResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShelfResponse {
+    pub id: ShelfId,
+    pub branch_id: BranchId,
+    pub name: String,
+}
+
+impl From<Shelf> for ShelfResponse {
+    fn from(value: Shelf) -> Self {
+        let Shelf {
+            id,
+            branch_id,
+            name,
+        } = value;
+        Self {
+            id,
+            branch_id,
+            name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookLocationResponse {
+    pub branch: BranchResponse,
+    pub shelf: ShelfResponse,
+}
+
+impl From<BookLocation> for BookLocationResponse {
+    fn from(value: BookLocation) -> Self {
+        let BookLocation { branch, shelf } = value;
+        Self {
+            branch: branch.into(),
+            shelf: shelf.into(),
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use crate::csv;
+use chrono::NaiveDate;
+use garde::Validate;
+use kernel::model::{
+    id::BookId,
+    report::{BookLoanCount, CirculationStats, ReportGrouping, ReportOptions},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportGroupingName {
+    Day,
+    Week,
+    #[default]
+    Month,
+}
+
+impl From<ReportGroupingName> for ReportGrouping {
+    fn from(value: ReportGroupingName) -> Self {
+        match value {
+            ReportGroupingName::Day => Self::Day,
+            ReportGroupingName::Week => Self::Week,
+            ReportGroupingName::Month => Self::Month,
+        }
+    }
+}
+
+// レポートの出力形式
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+// レポートを取得する際のクエリ
+// from から to まで（両端の日付を含む）の期間を groupBy の単位で集計する
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportQuery {
+    #[garde(skip)]
+    pub from: NaiveDate,
+    #[garde(custom(validate_report_range(&self.from)))]
+    pub to: NaiveDate,
+    #[garde(skip)]
+    #[serde(default)]
+    pub group_by: ReportGroupingName,
+    #[garde(skip)]
+    #[serde(default)]
+    pub format: ReportFormat,
+    // 期間ごとに返す蔵書の件数（貸出回数の多い蔵書のレポートでのみ使用する）
+    #[garde(range(min = 1, max = 100))]
+    #[serde(default = "default_ranking_limit")]
+    pub limit: i64,
+}
+
+const fn default_ranking_limit() -> i64 {
+    10
+}
+
+// 期間の終わりが始まりより前になっていないかを検証する
+fn validate_report_range(from: &NaiveDate) -> impl FnOnce(&NaiveDate, &()) -> garde::Result + '_ {
+    move |to, _| {
+        if from > to {
+            return Err(garde::Error::new("to must not be earlier than from"));
+        }
+        Ok(())
+    }
+}
+
+impl From<&ReportQuery> for ReportOptions {
+    fn from(value: &ReportQuery) -> Self {
+        // to の日付は翌日の 0 時未満までを含める
+        Self {
+            from: value.from,
+            to: value.to.succ_opt().unwrap_or(value.to),
+            grouping: value.group_by.into(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CirculationReportResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub group_by: ReportGroupingName,
+    pub items: Vec<CirculationStatsResponse>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CirculationStatsResponse {
+    pub period_start: NaiveDate,
+    pub loans: i64,
+    pub returns: i64,
+    pub active_borrowers: i64,
+    pub overdue_returns: i64,
+    pub overdue_outstanding: i64,
+    pub overdue_rate: Option<f64>,
+    pub average_loan_days: Option<f64>,
+}
+
+impl From<CirculationStats> for CirculationStatsResponse {
+    fn from(value: CirculationStats) -> Self {
+        let overdue_rate = value.overdue_rate();
+        let CirculationStats {
+            period_start,
+            loans,
+            returns,
+            active_borrowers,
+            overdue_returns,
+            overdue_outstanding,
+            average_loan_days,
+        } = value;
+        Self {
+            period_start,
+            loans,
+            returns,
+            active_borrowers,
+            overdue_returns,
+            overdue_outstanding,
+            overdue_rate,
+            average_loan_days,
+        }
+    }
+}
+
+impl CirculationReportResponse {
+    pub fn new(query: &ReportQuery, stats: Vec<CirculationStats>) -> Self {
+        Self {
+            from: query.from,
+            to: query.to,
+            group_by: query.group_by,
+            items: stats
+                .into_iter()
+                .map(CirculationStatsResponse::from)
+                .collect(),
+        }
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        csv::push_record(
+            &mut out,
+            [
+                "periodStart",
+                "loans",
+                "returns",
+                "activeBorrowers",
+                "overdueReturns",
+                "overdueOutstanding",
+                "overdueRate",
+                "averageLoanDays",
+            ],
+        );
+        for item in &self.items {
+            csv::push_record(
+                &mut out,
+                [
+                    item.period_start.to_string(),
+                    item.loans.to_string(),
+                    item.returns.to_string(),
+                    item.active_borrowers.to_string(),
+                    item.overdue_returns.to_string(),
+                    item.overdue_outstanding.to_string(),
+                    optional_to_string(item.overdue_rate),
+                    optional_to_string(item.average_loan_days),
+                ],
+            );
+        }
+        out
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MostBorrowedReportResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub group_by: ReportGroupingName,
+    pub items: Vec<BookLoanCountResponse>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookLoanCountResponse {
+    pub period_start: NaiveDate,
+    pub book_id: BookId,
+    pub title: String,
+    pub author: String,
+    pub loans: i64,
+}
+
+impl From<BookLoanCount> for BookLoanCountResponse {
+    fn from(value: BookLoanCount) -> Self {
+        let BookLoanCount {
+            period_start,
+            book_id,
+            title,
+            author,
+            loans,
+        } = value;
+        Self {
+            period_start,
+            book_id,
+            title,
+            author,
+            loans,
+        }
+    }
+}
+
+impl MostBorrowedReportResponse {
+    pub fn new(query: &ReportQuery, ranking: Vec<BookLoanCount>) -> Self {
+        Self {
+            from: query.from,
+            to: query.to,
+            group_by: query.group_by,
+            items: ranking
+                .into_iter()
+                .map(BookLoanCountResponse::from)
+                .collect(),
+        }
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        csv::push_record(
+            &mut out,
+            ["periodStart", "bookId", "title", "author", "loans"],
+        );
+        for item in &self.items {
+            csv::push_record(
+                &mut out,
+                [
+                    item.period_start.to_string(),
+                    item.book_id.to_string(),
+                    item.title.clone(),
+                    item.author.clone(),
+                    item.loans.to_string(),
+                ],
+            );
+        }
+        out
+    }
+}
+
+// 値がない場合は空のフィールドとして出力する
+fn optional_to_string(value: Option<f64>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
@@ -0,0 +1,100 @@
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    book::event::UpdateBookTags,
+    id::{BookId, TagId, UserId},
+    tag::{
+        event::{CreateTag, UpdateTag},
+        Tag,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+// タグの登録・更新用の型
+// 蔵書一覧の絞り込みではカンマ区切りで複数の名前を指定するため、名前にカンマは使えない
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTagRequest {
+    #[garde(length(min = 1, max = 64), custom(validate_tag_name))]
+    pub name: String,
+}
+
+impl From<CreateTagRequest> for CreateTag {
+    fn from(value: CreateTagRequest) -> Self {
+        let CreateTagRequest { name } = value;
+        CreateTag { name }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTagRequest {
+    #[garde(length(min = 1, max = 64), custom(validate_tag_name))]
+    pub name: String,
+}
+
+#[derive(new)]
+pub struct UpdateTagRequestWithIds(TagId, UpdateTagRequest);
+impl From<UpdateTagRequestWithIds> for UpdateTag {
+    fn from(value: UpdateTagRequestWithIds) -> Self {
+        let UpdateTagRequestWithIds(tag_id, UpdateTagRequest { name }) = value;
+        UpdateTag { tag_id, name }
+    }
+}
+
+// 前後の空白だけが異なる名前のタグができないよう、前後の空白も受け付けない
+fn validate_tag_name(value: &str, _context: &()) -> garde::Result {
+    if value.contains(',') {
+        return Err(garde::Error::new("タグの名前にカンマは使えません"));
+    }
+    if value.trim() != value {
+        return Err(garde::Error::new("タグの名前の前後に空白は使えません"));
+    }
+    Ok(())
+}
+
+// 蔵書に付けるタグの指定
+// 指定したタグの組み合わせで置き換えるため、空の配列を指定するとすべてのタグが外れる
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateBookTagsRequest {
+    pub tag_ids: Vec<TagId>,
+}
+
+#[derive(new)]
+pub struct UpdateBookTagsRequestWithIds(BookId, UserId, UpdateBookTagsRequest);
+impl From<UpdateBookTagsRequestWithIds> for UpdateBookTags {
+    fn from(value: UpdateBookTagsRequestWithIds) -> Self {
+        let UpdateBookTagsRequestWithIds(book_id, user_id, UpdateBookTagsRequest { tag_ids }) =
+            value;
+        UpdateBookTags::new(book_id, tag_ids, user_id)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagsResponse {
+    pub items: Vec<TagResponse>,
+}
+
+impl From<Vec<Tag>> for TagsResponse {
+    fn from(value: Vec<Tag>) -> Self {
+        Self {
+            items: value.into_iter().map(TagResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagResponse {
+    pub id: TagId,
+    pub name: String,
+}
+
+impl From<Tag> for TagResponse {
+    fn from(value: Tag) -> Self {
+        let Tag { id, name } = value;
+        Self { id, name }
+    }
+}
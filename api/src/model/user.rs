@@ -4,13 +4,15 @@ use kernel::model::{
     id::UserId,
     role::Role,
     user::{
-        event::{CreateUser, UpdateUserPassword, UpdateUserRole},
+        event::{CreateUser, PatchUser, UpdateUserPassword, UpdateUserRole},
         User,
     },
 };
 use serde::{Deserialize, Serialize};
 use strum::VariantNames;
 
+use super::book::deserialize_non_null;
+
 #[derive(Serialize, Deserialize, VariantNames)]
 #[strum(serialize_all = "kebab-case")]
 pub enum RoleName {
@@ -58,6 +60,7 @@ impl From<User> for UserResponse {
             name,
             email,
             role,
+            ..
         } = value;
         Self {
             id,
@@ -122,6 +125,34 @@ impl From<CreateUserRequest> for CreateUser {
     }
 }
 
+// ユーザー情報の部分更新（JSON Merge Patch）用の型
+// 送られた項目だけを更新し、省略された項目は現在の値のままにする
+// name・email は必須の項目のため、null を指定した場合はエラーにする
+#[derive(Debug, Default, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchUserRequest {
+    #[garde(inner(length(min = 1)))]
+    #[serde(default, deserialize_with = "deserialize_non_null")]
+    pub name: Option<String>,
+    #[garde(inner(email))]
+    #[serde(default, deserialize_with = "deserialize_non_null")]
+    pub email: Option<String>,
+}
+
+#[derive(new)]
+pub struct PatchUserRequestWithUserId(UserId, PatchUserRequest);
+impl From<PatchUserRequestWithUserId> for PatchUser {
+    fn from(value: PatchUserRequestWithUserId) -> Self {
+        let PatchUserRequestWithUserId(user_id, PatchUserRequest { name, email }) = value;
+        Self {
+            user_id,
+            name,
+            email,
+            expected_version: None,
+        }
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateUserRoleRequest {
@@ -136,6 +167,7 @@ impl From<UpdateUserRoleRequestWithUserId> for UpdateUserRole {
         Self {
             user_id,
             role: Role::from(role),
+            expected_version: None,
         }
     }
 }
@@ -1,31 +1,96 @@
 use axum::{
-    routing::{delete, get, post, put},
+    extract::DefaultBodyLimit,
+    routing::{delete, get, patch, post, put},
     Router,
 };
 use registry::AppRegistry;
 
-use crate::handler::{
-    book::{delete_book, register_book, show_book, show_book_list, update_book},
-    checkout::{checkout_book, checkout_history, return_book, show_checked_out_list},
+use crate::{
+    cover::MAX_COVER_BYTES,
+    handler::{
+        book::{
+            delete_book, export_books, import_books, import_marc_books, lookup_book, patch_book,
+            purge_book, register_book, restore_book, show_book, show_book_cover,
+            show_book_cover_thumbnail, show_book_history, show_book_list, update_book,
+            upload_book_cover,
+        },
+        checkout::{
+            checkout_book, checkout_copy, checkout_history, renew_checkout, return_book,
+            show_checked_out_list,
+        },
+        copy::{delete_copy, register_copy, show_copy_list, update_copy},
+        location::update_book_location,
+        reservation::{cancel_reservation, reserve_book, show_reservation_list},
+        tag::update_book_tags,
+    },
 };
 
 pub fn build_book_routers() -> Router<AppRegistry> {
     let books_routers = Router::new()
         .route("/", post(register_book))
         .route("/", get(show_book_list))
+        .route("/export", get(export_books))
+        .route("/lookup", get(lookup_book))
+        .route("/import", post(import_books))
+        .route("/import/marc", post(import_marc_books))
         .route("/:book_id", get(show_book))
         .route("/:book_id", put(update_book))
-        .route("/:book_id", delete(delete_book));
+        .route("/:book_id", patch(patch_book))
+        .route("/:book_id", delete(delete_book))
+        .route("/:book_id/history", get(show_book_history))
+        .route("/:book_id/restore", put(restore_book))
+        .route("/:book_id/purge", delete(purge_book));
+
+    // 上限を超える画像はハンドラーで 413 を返すため、multipart の区切りなどの分も含めて受け付ける
+    let cover_router = Router::new()
+        .route(
+            "/:book_id/cover",
+            put(upload_book_cover).layer(DefaultBodyLimit::max(MAX_COVER_BYTES + 64 * 1024)),
+        )
+        .route("/:book_id/cover", get(show_book_cover))
+        .route("/:book_id/cover/thumbnail", get(show_book_cover_thumbnail));
+
+    let tag_router = Router::new().route("/:book_id/tags", put(update_book_tags));
+
+    let location_router = Router::new().route("/:book_id/location", put(update_book_location));
+
+    let copy_router = Router::new()
+        .route("/:book_id/copies", get(show_copy_list))
+        .route("/:book_id/copies", post(register_copy))
+        .route("/:book_id/copies/:copy_id", put(update_copy))
+        .route("/:book_id/copies/:copy_id", delete(delete_copy));
 
     let checkout_router = Router::new()
         .route("/checkouts", get(show_checked_out_list))
         .route("/:book_id/checkouts", post(checkout_book))
+        .route("/:book_id/copies/:copy_id/checkouts", post(checkout_copy))
         .route(
             "/:book_id/checkouts/:checkout_id/returned",
             put(return_book),
         )
+        .route(
+            "/:book_id/checkouts/:checkout_id/renewal",
+            put(renew_checkout),
+        )
         .route("/:book_id/checkout-history", get(checkout_history));
 
+    let reservation_router = Router::new()
+        .route("/:book_id/reservations", get(show_reservation_list))
+        .route("/:book_id/reservations", post(reserve_book))
+        .route(
+            "/:book_id/reservations/:reservation_id",
+            delete(cancel_reservation),
+        );
+
     // merge メソッドで router を結合する
-    Router::new().nest("/books", books_routers.merge(checkout_router))
+    Router::new().nest(
+        "/books",
+        books_routers
+            .merge(cover_router)
+            .merge(tag_router)
+            .merge(location_router)
+            .merge(copy_router)
+            .merge(checkout_router)
+            .merge(reservation_router),
+    )
 }
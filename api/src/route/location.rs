@@ -0,0 +1,26 @@
+use crate::handler::location::{
+    delete_branch, delete_shelf, register_branch, register_shelf, show_branch_list,
+    show_shelf_list, update_branch, update_shelf,
+};
+use axum::{
+    routing::{get, put},
+    Router,
+};
+use registry::AppRegistry;
+
+pub fn build_location_routers() -> Router<AppRegistry> {
+    Router::new()
+        .route("/branches", get(show_branch_list).post(register_branch))
+        .route(
+            "/branches/:branch_id",
+            put(update_branch).delete(delete_branch),
+        )
+        .route(
+            "/branches/:branch_id/shelves",
+            get(show_shelf_list).post(register_shelf),
+        )
+        .route(
+            "/branches/:branch_id/shelves/:shelf_id",
+            put(update_shelf).delete(delete_shelf),
+        )
+}
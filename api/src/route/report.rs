@@ -0,0 +1,12 @@
+use axum::{routing::get, Router};
+use registry::AppRegistry;
+
+use crate::handler::report::{show_circulation_report, show_most_borrowed_report};
+
+pub fn build_report_routers() -> Router<AppRegistry> {
+    let routers = Router::new()
+        .route("/circulation", get(show_circulation_report))
+        .route("/most-borrowed", get(show_most_borrowed_report));
+
+    Router::new().nest("/reports", routers)
+}
@@ -0,0 +1,12 @@
+use crate::handler::tag::{delete_tag, register_tag, show_tag_list, update_tag};
+use axum::{
+    routing::{get, put},
+    Router,
+};
+use registry::AppRegistry;
+
+pub fn build_tag_routers() -> Router<AppRegistry> {
+    Router::new()
+        .route("/tags", get(show_tag_list).post(register_tag))
+        .route("/tags/:tag_id", put(update_tag).delete(delete_tag))
+}
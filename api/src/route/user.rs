@@ -1,19 +1,32 @@
+use crate::handler::checkout::checkout_book_on_behalf;
+use crate::handler::fine::{get_my_fines, get_user_fines, record_fine_payment, record_fine_waiver};
 use crate::handler::user::{
-    change_password, change_role, delete_user, get_checkouts, get_current_user, list_users,
+    change_password, change_role, delete_user, get_checkout_history, get_checkouts,
+    get_current_user, get_user, get_user_checkout_history, list_users, patch_current_user,
     register_user,
 };
 use axum::{
-    routing::{delete, get, put},
+    routing::{get, post, put},
     Router,
 };
 use registry::AppRegistry;
 
 pub fn build_user_router() -> Router<AppRegistry> {
     Router::new()
-        .route("/users/me", get(get_current_user))
+        .route("/users/me", get(get_current_user).patch(patch_current_user))
         .route("/users/me/password", put(change_password))
         .route("/users/me/checkouts", get(get_checkouts))
+        .route("/users/me/checkout-history", get(get_checkout_history))
+        .route("/users/me/fines", get(get_my_fines))
         .route("/users", get(list_users).post(register_user))
-        .route("/users/:user_id", delete(delete_user))
+        .route("/users/:user_id", get(get_user).delete(delete_user))
         .route("/users/:user_id/role", put(change_role))
+        .route("/users/:user_id/checkouts", post(checkout_book_on_behalf))
+        .route(
+            "/users/:user_id/checkout-history",
+            get(get_user_checkout_history),
+        )
+        .route("/users/:user_id/fines", get(get_user_fines))
+        .route("/users/:user_id/fines/payments", post(record_fine_payment))
+        .route("/users/:user_id/fines/waivers", post(record_fine_waiver))
 }
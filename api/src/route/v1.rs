@@ -1,5 +1,6 @@
 use super::{
-    book::build_book_routers, health::build_health_check_routers, user::build_user_router,
+    book::build_book_routers, health::build_health_check_routers, location::build_location_routers,
+    report::build_report_routers, tag::build_tag_routers, user::build_user_router,
 };
 use axum::Router;
 use registry::AppRegistry;
@@ -8,6 +9,9 @@ pub fn routes() -> Router<AppRegistry> {
     let router = Router::new()
         .merge(build_health_check_routers())
         .merge(build_book_routers())
-        .merge(build_user_router());
+        .merge(build_user_router())
+        .merge(build_report_routers())
+        .merge(build_tag_routers())
+        .merge(build_location_routers());
     Router::new().nest("/api/v1", router)
 }
@@ -6,18 +6,37 @@ use tower::ServiceExt;
 
 use crate::{
     deserialize_json,
-    helper::{fixture, make_router, v1, TestRequestExt},
+    helper::{fixture, make_router, registry_for_user, v1, TestRequestExt},
 };
-use api::model::book::PaginatedBookResponse;
+use api::model::{
+    book::{
+        BookFieldChangeResponse, BookFieldName, BookHistoryActionName, BookHistoryResponse,
+        CreateBookRequest, CursorPaginatedBookResponse, PaginatedBookResponse,
+    },
+    import::BookImportResponse,
+};
+use chrono::Utc;
 use kernel::{
     model::{
-        book::Book,
-        id::{BookId, UserId},
-        list::PaginatedList,
+        book::{
+            history::{
+                BookField, BookFieldChange, BookHistory, BookHistoryAction, BookHistoryEntry,
+            },
+            isbn::Isbn,
+            metadata::BookMetadata,
+            Book, BookSortKey, BookTagFilter, IsbnConflict, IsbnConflictKind, TagMatchMode,
+        },
+        id::{BookHistoryId, BookId, UserId},
+        list::{CursorPaginatedList, PaginatedList, SortOrder},
+        role::Role,
         user::BookOwner,
     },
-    repository::book::MockBookRepository,
+    repository::{
+        book::MockBookRepository, book_metadata::MockBookMetadataProvider,
+        storage::MockObjectStorage,
+    },
 };
+use shared::error::AppError;
 
 #[rstest]
 #[case("/books", 20, 0)]
@@ -41,14 +60,19 @@ async fn show_book_list_with_query_200(
             let items = vec![Book {
                 id: book_id,
                 title: "RustによるWebアプリケーション開発".to_string(),
-                isbn: "".to_string(),
+                isbn: "9784065369579".parse().unwrap(),
                 author: "Yuki Toyoda".to_string(),
                 description: "RustによるWebアプリケーション開発".to_string(),
                 owner: BookOwner {
                     id: UserId::new(),
                     name: "Yuki Toyoda".to_string(),
                 },
-                checkout: None,
+                copies: Default::default(),
+                checkouts: vec![],
+                cover: None,
+                tags: vec![],
+                location: None,
+                version: 1,
             }];
             Ok(PaginatedList {
                 total: 1,
@@ -79,7 +103,17 @@ async fn show_book_list_with_query_200(
 
 #[rstest]
 #[case("/books?limit=-1")]
+#[case("/books?limit=101")]
+#[case("/books?limit=9223372036854775807")]
 #[case("/books?offset=aaa")]
+#[case("/books?checkedOut=yes")]
+#[case("/books?owner=aaa")]
+#[case("/books?q=")]
+#[case("/books?sort=price")]
+#[case("/books?order=random")]
+#[case("/books?isbn=9784065369570")]
+#[case("/books?tags=")]
+#[case("/books?tags=Rust&tagMatch=some")]
 #[tokio::test]
 async fn show_book_list_with_query_400(
     mut fixture: registry::MockAppRegistryExt,
@@ -95,14 +129,19 @@ async fn show_book_list_with_query_400(
             let items = vec![Book {
                 id: book_id,
                 title: "RustによるWebアプリケーション開発".to_string(),
-                isbn: "".to_string(),
+                isbn: "9784065369579".parse().unwrap(),
                 author: "Yuki Toyoda".to_string(),
                 description: "RustによるWebアプリケーション開発".to_string(),
                 owner: BookOwner {
                     id: UserId::new(),
                     name: "Yuki Toyoda".to_string(),
                 },
-                checkout: None,
+                copies: Default::default(),
+                checkouts: vec![],
+                cover: None,
+                tags: vec![],
+                location: None,
+                version: 1,
             }];
             Ok(PaginatedList {
                 total: 1,
@@ -125,3 +164,1308 @@ async fn show_book_list_with_query_400(
 
     Ok(())
 }
+
+#[rstest]
+#[case("/books?q=Rust", Some("Rust"), None, None)]
+#[case(
+    "/books?author=Toyoda&checkedOut=false",
+    None,
+    Some("Toyoda"),
+    Some(false)
+)]
+#[case("/books?checkedOut=true", None, None, Some(true))]
+#[tokio::test]
+async fn show_book_list_with_filter_200(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] expected_keyword: Option<&'static str>,
+    #[case] expected_author: Option<&'static str>,
+    #[case] expected_checked_out: Option<bool>,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        // クエリパラメータが絞り込み条件として repository に渡されることを検証する
+        mock.expect_find_all()
+            .withf(move |opt| {
+                opt.filter.keyword.as_deref() == expected_keyword
+                    && opt.filter.author.as_deref() == expected_author
+                    && opt.filter.checked_out == expected_checked_out
+            })
+            .returning(|opt| {
+                Ok(PaginatedList {
+                    total: 0,
+                    limit: opt.limit,
+                    offset: opt.offset,
+                    items: vec![],
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, PaginatedBookResponse);
+    assert_eq!(result.total, 0);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/books", BookSortKey::CreatedAt, SortOrder::Desc)]
+#[case("/books?sort=title", BookSortKey::Title, SortOrder::Asc)]
+#[case(
+    "/books?sort=updatedAt&order=desc",
+    BookSortKey::UpdatedAt,
+    SortOrder::Desc
+)]
+#[case("/books?order=asc", BookSortKey::CreatedAt, SortOrder::Asc)]
+#[tokio::test]
+async fn show_book_list_with_sort_200(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] expected_key: BookSortKey,
+    #[case] expected_order: SortOrder,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_all()
+            .withf(move |opt| opt.sort.key == expected_key && opt.sort.order == expected_order)
+            .returning(|opt| {
+                Ok(PaginatedList {
+                    total: 0,
+                    limit: opt.limit,
+                    offset: opt.offset,
+                    items: vec![],
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/books", None)]
+#[case(
+    "/books?tags=Rust,Database",
+    Some((vec!["Rust", "Database"], TagMatchMode::Any))
+)]
+#[case(
+    "/books?tags=Rust,%20,Design&tagMatch=all",
+    Some((vec!["Rust", "Design"], TagMatchMode::All))
+)]
+#[tokio::test]
+async fn show_book_list_with_tags_200(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] expected: Option<(Vec<&'static str>, TagMatchMode)>,
+) -> anyhow::Result<()> {
+    let expected = expected.map(|(names, mode)| BookTagFilter {
+        names: names.into_iter().map(String::from).collect(),
+        mode,
+    });
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        let expected = expected.clone();
+        // カンマ区切りのタグの名前が、空の名前を除いて repository に渡されることを検証する
+        mock.expect_find_all()
+            .withf(move |opt| opt.filter.tags == expected)
+            .returning(|opt| {
+                Ok(PaginatedList {
+                    total: 0,
+                    limit: opt.limit,
+                    offset: opt.offset,
+                    items: vec![],
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/books?cursor=", None)]
+#[case("/books?cursor=abc&limit=10", Some("abc"))]
+#[tokio::test]
+async fn show_book_list_with_cursor_200(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] expected_cursor: Option<&'static str>,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_all_by_cursor()
+            .withf(move |opt| opt.cursor.as_deref() == expected_cursor)
+            .returning(|opt| {
+                Ok(CursorPaginatedList {
+                    limit: opt.limit,
+                    items: vec![],
+                    next_cursor: Some("next".into()),
+                    prev_cursor: None,
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, CursorPaginatedBookResponse);
+    assert_eq!(result.next_cursor.as_deref(), Some("next"));
+    assert!(result.prev_cursor.is_none());
+
+    Ok(())
+}
+
+#[rstest]
+#[case("978-4-06-536957-9", axum::http::StatusCode::CREATED)]
+#[case("4065369576", axum::http::StatusCode::CREATED)]
+#[case("Test ISBN", axum::http::StatusCode::BAD_REQUEST)]
+#[case("9784065369570", axum::http::StatusCode::BAD_REQUEST)]
+#[case("9784798061702", axum::http::StatusCode::CONFLICT)]
+#[tokio::test]
+async fn register_book_with_isbn(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] isbn: &str,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        // ISBN は正規化されて repository に渡される
+        // 9784798061702 は登録済みとして重複エラーを返す
+        mock.expect_create()
+            .returning(|event, _| match event.isbn.as_str() {
+                "9784065369579" => Ok(()),
+                "9784798061702" => Err(AppError::Conflict("duplicated".into())),
+                _ => unreachable!(),
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let body = serde_json::json!({
+        "title": "RustによるWebアプリケーション開発",
+        "author": "Yuki Toyoda",
+        "isbn": isbn,
+        "description": "",
+    });
+    let req = Request::post(&v1("/books"))
+        .bearer()
+        .application_json()
+        .body(Body::from(body.to_string()))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+
+    Ok(())
+}
+
+// 9784065369579 の書誌情報のみを返すモック
+fn metadata_provider_mock() -> MockBookMetadataProvider {
+    let mut mock = MockBookMetadataProvider::new();
+    mock.expect_find_by_isbn()
+        .returning(|isbn| match isbn.as_str() {
+            "9784065369579" => Ok(Some(BookMetadata {
+                title: Some("RustによるWebアプリケーション開発".into()),
+                author: Some("Yuki Toyoda".into()),
+                description: None,
+            })),
+            "9784798061702" => Err(AppError::ExternalServiceError("timeout".into())),
+            _ => Ok(None),
+        });
+    mock
+}
+
+#[rstest]
+#[case("/books/lookup?isbn=978-4-06-536957-9", axum::http::StatusCode::OK)]
+#[case("/books/lookup?isbn=4065369576", axum::http::StatusCode::OK)]
+#[case("/books/lookup?isbn=9784297141905", axum::http::StatusCode::NOT_FOUND)]
+#[case(
+    "/books/lookup?isbn=9784065369570",
+    axum::http::StatusCode::BAD_REQUEST
+)]
+#[case("/books/lookup", axum::http::StatusCode::BAD_REQUEST)]
+#[case(
+    "/books/lookup?isbn=9784798061702",
+    axum::http::StatusCode::BAD_GATEWAY
+)]
+#[tokio::test]
+async fn lookup_book(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    fixture
+        .expect_book_metadata_provider()
+        .returning(|| Arc::new(metadata_provider_mock()));
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+
+    // 蔵書登録のリクエストと同じ形式で返り、書誌情報にない項目は空文字になる
+    if expected_status == axum::http::StatusCode::OK {
+        let result = deserialize_json!(resp, CreateBookRequest);
+        assert_eq!(result.title, "RustによるWebアプリケーション開発");
+        assert_eq!(result.author, "Yuki Toyoda");
+        assert_eq!(result.isbn, "9784065369579");
+        assert_eq!(result.description, "");
+    }
+
+    Ok(())
+}
+
+#[rstest]
+#[case(
+    serde_json::json!({"isbn": "978-4-06-536957-9", "description": "入門書"}),
+    axum::http::StatusCode::CREATED
+)]
+#[case(
+    serde_json::json!({"isbn": "978-4-06-536957-9", "title": "Rust入門", "author": "著者A"}),
+    axum::http::StatusCode::CREATED
+)]
+#[case(
+    serde_json::json!({"isbn": "978-4-297-14190-5"}),
+    axum::http::StatusCode::BAD_REQUEST
+)]
+#[tokio::test]
+async fn register_book_with_fill_missing(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] body: serde_json::Value,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let expected = body.clone();
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        let expected = expected.clone();
+        // リクエストで指定した項目はそのまま、省略した項目は書誌情報の値で登録される
+        mock.expect_create().returning(move |event, _| {
+            assert_eq!(
+                event.title,
+                expected["title"]
+                    .as_str()
+                    .unwrap_or("RustによるWebアプリケーション開発")
+            );
+            assert_eq!(
+                event.author,
+                expected["author"].as_str().unwrap_or("Yuki Toyoda")
+            );
+            assert_eq!(
+                event.description,
+                expected["description"].as_str().unwrap_or("")
+            );
+            Ok(())
+        });
+        Arc::new(mock)
+    });
+    fixture
+        .expect_book_metadata_provider()
+        .returning(|| Arc::new(metadata_provider_mock()));
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::post(&v1("/books?fillMissing=true"))
+        .bearer()
+        .application_json()
+        .body(Body::from(body.to_string()))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+
+    Ok(())
+}
+
+const MULTIPART_BOUNDARY: &str = "test-boundary";
+
+// file フィールドにファイルを添付した multipart/form-data のリクエストを組み立てる
+fn import_request(path: &str, file: impl AsRef<[u8]>) -> axum::http::Result<Request<Body>> {
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"books\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+        MULTIPART_BOUNDARY
+    )
+    .into_bytes();
+    body.extend_from_slice(file.as_ref());
+    body.extend_from_slice(format!("\r\n--{}--\r\n", MULTIPART_BOUNDARY).as_bytes());
+    Request::post(&v1(path))
+        .bearer()
+        .header(
+            "Content-Type",
+            format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY),
+        )
+        .body(Body::from(body))
+}
+
+#[rstest]
+#[case("/books/import", false)]
+#[case("/books/import?dryRun=true", true)]
+#[tokio::test]
+async fn import_books_200(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] dry_run: bool,
+) -> anyhow::Result<()> {
+    // 3 件目に渡される蔵書は、1 件目と ISBN が重複している
+    let conflicts = vec![IsbnConflict {
+        index: 2,
+        isbn: "9784065369579".parse()?,
+        kind: IsbnConflictKind::Batch,
+    }];
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        // 検証を通った行だけが、ISBN を正規化したうえでまとめて渡される
+        // dryRun の場合は登録せずに、ISBN の重複だけを調べる
+        mock.expect_create_many()
+            .withf(|events, _| {
+                events
+                    .iter()
+                    .map(|e| (e.title.as_str(), e.isbn.as_str(), e.description.as_str()))
+                    .eq([
+                        ("Rust入門", "9784065369579", ""),
+                        ("Web開発", "9784798061702", "1 行目\n2 行目, \"引用\""),
+                        ("ISBN 重複", "9784065369579", ""),
+                    ])
+            })
+            .times(if dry_run { 0 } else { 1 })
+            .returning({
+                let conflicts = conflicts.clone();
+                move |_, _| Ok(conflicts.clone())
+            });
+        mock.expect_find_isbn_conflicts()
+            .withf(|isbns, _| {
+                isbns.iter().map(Isbn::as_str).eq([
+                    "9784065369579",
+                    "9784798061702",
+                    "9784065369579",
+                ])
+            })
+            .times(if dry_run { 1 } else { 0 })
+            .returning({
+                let conflicts = conflicts.clone();
+                move |_, _| Ok(conflicts.clone())
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    // 列の順序は問わず、引用符で囲んだ値にはカンマや改行を含められる
+    let csv = [
+        "isbn,title,author,description",
+        "4065369576,Rust入門,著者A,",
+        ",タイトルなし,著者B,",
+        "Test ISBN,ISBN 不正,著者C,",
+        "9784798061702,Web開発,著者D,\"1 行目\n2 行目, \"\"引用\"\"\"",
+        "",
+        "9784798061702,列不足,著者E",
+        "978-4-06-536957-9,ISBN 重複,著者F,",
+    ]
+    .join("\r\n");
+    let resp = app.oneshot(import_request(path, &csv)?).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    // ISBN が重複する行も、ファイル中の位置とともにエラーとして返る
+    let result = deserialize_json!(resp, BookImportResponse);
+    assert_eq!(result.total_rows, 6);
+    assert_eq!(result.valid_rows, 2);
+    assert_eq!(result.dry_run, dry_run);
+    assert_eq!(result.imported_rows, if dry_run { 0 } else { 2 });
+    assert_eq!(
+        result
+            .errors
+            .iter()
+            .map(|e| (e.record, e.line))
+            .collect::<Vec<_>>(),
+        vec![(2, Some(3)), (3, Some(4)), (5, Some(8)), (6, Some(9))]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[case("title,author\r\nRust入門,著者A")]
+#[case("")]
+#[case("isbn,title,author\r\n4065369576,\"Rust入門,著者A")]
+#[tokio::test]
+async fn import_books_400(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] csv: &str,
+) -> anyhow::Result<()> {
+    fixture
+        .expect_book_repository()
+        .returning(|| Arc::new(MockBookRepository::new()));
+
+    let app: axum::Router = make_router(fixture);
+
+    let resp = app.oneshot(import_request("/books/import", csv)?).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+fn export_book(title: &str) -> Book {
+    Book {
+        id: BookId::new(),
+        title: title.to_string(),
+        isbn: "9784065369579".parse().unwrap(),
+        author: "Yuki Toyoda".to_string(),
+        description: "".to_string(),
+        owner: BookOwner {
+            id: UserId::new(),
+            name: "Yuki Toyoda".to_string(),
+        },
+        copies: Default::default(),
+        checkouts: vec![],
+        cover: None,
+        tags: vec![],
+        location: None,
+        version: 1,
+    }
+}
+
+#[rstest]
+#[case("csv", "text/csv; charset=utf-8")]
+#[case("jsonl", "application/jsonl; charset=utf-8")]
+#[case("marcxml", "application/marcxml+xml; charset=utf-8")]
+#[tokio::test]
+async fn export_books_200(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] format: &str,
+    #[case] expected_content_type: &str,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        // 蔵書一覧と同じ絞り込み条件で、カーソルをたどって最後のページまで取得する
+        mock.expect_find_all_by_cursor()
+            .withf(|opt| opt.filter.author.as_deref() == Some("Yuki Toyoda"))
+            .returning(|opt| {
+                let (title, next_cursor) = match opt.cursor.as_deref() {
+                    None => ("Book <1>, \"first\"", Some("next".to_string())),
+                    Some("next") => ("Book 2", None),
+                    _ => unreachable!(),
+                };
+                Ok(CursorPaginatedList {
+                    limit: opt.limit,
+                    items: vec![export_book(title)],
+                    next_cursor,
+                    prev_cursor: None,
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1(&format!(
+        "/books/export?format={}&author=Yuki%20Toyoda&limit=1",
+        format
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+    assert_eq!(
+        resp.headers()[axum::http::header::CONTENT_TYPE],
+        expected_content_type
+    );
+
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await?;
+    let body = std::str::from_utf8(&body)?;
+    match format {
+        "csv" => {
+            let lines = body.split("\r\n").collect::<Vec<_>>();
+            assert_eq!(lines.len(), 4);
+            assert!(lines[0].starts_with("id,title,author,isbn,"));
+            assert!(lines[1].contains(r#","Book <1>, ""first""",Yuki Toyoda,9784065369579,"#));
+            assert!(lines[2].contains(",Book 2,"));
+        }
+        "jsonl" => {
+            let books = body
+                .lines()
+                .map(serde_json::from_str::<serde_json::Value>)
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(books.len(), 2);
+            assert_eq!(books[0]["title"], "Book <1>, \"first\"");
+            assert_eq!(books[1]["owner"]["name"], "Yuki Toyoda");
+        }
+        _ => {
+            assert!(body.starts_with("<?xml"));
+            assert_eq!(body.matches("<record>").count(), 2);
+            assert!(
+                body.contains(r#"<subfield code="a">Book &lt;1&gt;, &quot;first&quot;</subfield>"#)
+            );
+            assert!(body.trim_end().ends_with("</collection>"));
+        }
+    }
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/books/export")]
+#[case("/books/export?format=xlsx")]
+#[tokio::test]
+async fn export_books_400(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+) -> anyhow::Result<()> {
+    fixture
+        .expect_book_repository()
+        .returning(|| Arc::new(MockBookRepository::new()));
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+// ISO 2709 形式の MARC21 レコードを組み立てる
+// 値はインディケーターとサブフィールド区切り文字（\x1f）を含めて指定する
+fn marc21_record(fields: &[(&str, &str)]) -> Vec<u8> {
+    let mut directory = String::new();
+    let mut data = Vec::new();
+    for (tag, value) in fields {
+        let value = format!("{}\x1e", value).into_bytes();
+        directory.push_str(&format!("{}{:04}{:05}", tag, value.len(), data.len()));
+        data.extend(value);
+    }
+    directory.push('\x1e');
+    let base_address = 24 + directory.len();
+    let leader = format!(
+        "{:05}nam a22{:05}   4500",
+        base_address + data.len() + 1,
+        base_address
+    );
+    [leader.as_bytes(), directory.as_bytes(), &data, b"\x1d"].concat()
+}
+
+#[rstest]
+#[case::binary([
+    marc21_record(&[
+        ("001", "0000001"),
+        ("020", "  \x1fa9784065369579 (pbk.)"),
+        ("100", "1 \x1faToyoda, Yuki,"),
+        ("245", "10\x1faRust入門 :\x1fb基礎から /"),
+        ("520", "  \x1faRust の入門書。"),
+    ]),
+    // 著者名がない
+    marc21_record(&[
+        ("020", "  \x1fa9784065369579"),
+        ("245", "10\x1faタイトル"),
+    ]),
+    // リーダーが壊れている
+    b"00042nam\x1d".to_vec(),
+].concat())]
+#[case::marcxml(r#"<?xml version="1.0" encoding="UTF-8"?>
+<marc:collection xmlns:marc="http://www.loc.gov/MARC21/slim">
+  <marc:record>
+    <marc:leader>00000nam a2200000   4500</marc:leader>
+    <marc:controlfield tag="001">0000001</marc:controlfield>
+    <marc:datafield tag="020" ind1=" " ind2=" ">
+      <marc:subfield code="a">invalid</marc:subfield>
+    </marc:datafield>
+    <marc:datafield tag="020" ind1=" " ind2=" ">
+      <marc:subfield code="a">4065369576</marc:subfield>
+    </marc:datafield>
+    <marc:datafield tag="100" ind1="1" ind2=" ">
+      <marc:subfield code="a">Toyoda, Yuki,</marc:subfield>
+    </marc:datafield>
+    <marc:datafield tag="245" ind1="1" ind2="0">
+      <marc:subfield code="a">Rust入門 :</marc:subfield>
+      <marc:subfield code="b">基礎から /</marc:subfield>
+    </marc:datafield>
+    <marc:datafield tag="520" ind1=" " ind2=" ">
+      <marc:subfield code="a"><![CDATA[Rust の入門書。]]></marc:subfield>
+    </marc:datafield>
+  </marc:record>
+  <marc:record>
+    <marc:datafield tag="020" ind1=" " ind2=" ">
+      <marc:subfield code="a">9784065369579</marc:subfield>
+    </marc:datafield>
+    <marc:datafield tag="245" ind1="1" ind2="0">
+      <marc:subfield code="a">タイトル</marc:subfield>
+    </marc:datafield>
+  </marc:record>
+  <marc:record>
+    <marc:datafield tag="020" ind1=" " ind2=" ">
+      <marc:subfield code="a">9784065369570</marc:subfield>
+    </marc:datafield>
+    <marc:datafield tag="100" ind1="1" ind2=" ">
+      <marc:subfield code="a">Toyoda, Yuki</marc:subfield>
+    </marc:datafield>
+    <marc:datafield tag="245" ind1="1" ind2="0">
+      <marc:subfield code="a">Tom &amp; Jerry</marc:subfield>
+    </marc:datafield>
+  </marc:record>
+</marc:collection>
+"#.as_bytes().to_vec())]
+#[tokio::test]
+async fn import_marc_books_200(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] file: Vec<u8>,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        // 020 $a を ISBN、100 $a を著者名、245 $a・$b をタイトル、520 $a を説明文とする
+        // 末尾の区切り記号は取り除く
+        mock.expect_create_many()
+            .withf(|events, _| {
+                events
+                    .iter()
+                    .map(|e| {
+                        (
+                            e.title.as_str(),
+                            e.author.as_str(),
+                            e.isbn.as_str(),
+                            e.description.as_str(),
+                        )
+                    })
+                    .eq([(
+                        "Rust入門 : 基礎から",
+                        "Toyoda, Yuki",
+                        "9784065369579",
+                        "Rust の入門書。",
+                    )])
+            })
+            .times(1)
+            .returning(|_, _| Ok(vec![]));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let resp = app
+        .oneshot(import_request("/books/import/marc", file)?)
+        .await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, BookImportResponse);
+    assert_eq!(result.total_rows, 3);
+    assert_eq!(result.imported_rows, 1);
+    assert_eq!(
+        result
+            .errors
+            .iter()
+            .map(|e| (e.record, e.line))
+            .collect::<Vec<_>>(),
+        vec![(2, None), (3, None)]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn import_marc_books_with_non_ascii_directory(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_create_many()
+            .withf(|events, _| events.len() == 1)
+            .times(1)
+            .returning(|_, _| Ok(vec![]));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    // 2 件目のレコードは、ディレクトリの項目のタグの途中で文字（é）が区切られる
+    let file = [
+        marc21_record(&[
+            ("020", "  \x1fa9784065369579"),
+            ("100", "1 \x1faToyoda, Yuki"),
+            ("245", "10\x1faタイトル"),
+        ]),
+        b"00039nam a2200037   4500".to_vec(),
+        "24é00010000\x1e".as_bytes().to_vec(),
+        b"x\x1d".to_vec(),
+    ]
+    .concat();
+    let resp = app
+        .oneshot(import_request("/books/import/marc", file)?)
+        .await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, BookImportResponse);
+    assert_eq!(result.total_rows, 2);
+    assert_eq!(result.imported_rows, 1);
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.errors[0].record, 2);
+    assert_eq!(
+        result.errors[0].message,
+        "ディレクトリに不正な文字があります"
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn import_marc_books_400(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture
+        .expect_book_repository()
+        .returning(|| Arc::new(MockBookRepository::new()));
+
+    let app: axum::Router = make_router(fixture);
+
+    let file = "<collection><record><datafield tag=\"245\"></record></collection>";
+    let resp = app
+        .oneshot(import_request("/books/import/marc", file)?)
+        .await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(Role::User, axum::http::StatusCode::FORBIDDEN)]
+#[case(Role::Admin, axum::http::StatusCode::OK)]
+#[tokio::test]
+async fn show_deleted_book_list(
+    #[case] role: Role,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let mut fixture = registry_for_user(UserId::new(), role);
+    // 削除済みの蔵書は Admin のみ取得できる
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_all()
+            .withf(|opt| opt.filter.deleted)
+            .times(usize::from(role == Role::Admin))
+            .returning(|opt| {
+                Ok(PaginatedList {
+                    total: 0,
+                    limit: opt.limit,
+                    offset: opt.offset,
+                    items: vec![],
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1("/books?deleted=true"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn delete_book_keeps_cover(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    let mut book_repository = MockBookRepository::new();
+    book_repository.expect_find_by_id().returning(move |_| {
+        Ok(Some(Book {
+            id: book_id,
+            ..export_book("title")
+        }))
+    });
+    book_repository
+        .expect_delete()
+        .withf(move |event| event.book_id == book_id && event.expected_version == Some(1))
+        .times(1)
+        .returning(|_| Ok(()));
+    let book_repository = Arc::new(book_repository);
+    fixture
+        .expect_book_repository()
+        .returning(move || book_repository.clone());
+    // 元に戻せるよう、削除済みにしただけでは表紙画像を削除しない
+    fixture.expect_object_storage().returning(|| {
+        let mut mock = MockObjectStorage::new();
+        mock.expect_delete().never();
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::delete(&v1(&format!("/books/{}", book_id)))
+        .bearer()
+        .header("If-Match", "\"1\"")
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(Role::User, axum::http::StatusCode::FORBIDDEN)]
+#[case(Role::Admin, axum::http::StatusCode::OK)]
+#[tokio::test]
+async fn restore_book(
+    #[case] role: Role,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    let mut fixture = registry_for_user(UserId::new(), role);
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_restore()
+            .withf(move |event| event.book_id == book_id)
+            .times(usize::from(role == Role::Admin))
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::put(&v1(&format!("/books/{}/restore", book_id)))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+
+    Ok(())
+}
+
+#[rstest]
+#[case::purged(Ok(()), axum::http::StatusCode::OK)]
+#[case::checked_out(
+    Err(AppError::UnprocessableEntity("貸出中".into())),
+    axum::http::StatusCode::UNPROCESSABLE_ENTITY
+)]
+#[tokio::test]
+async fn purge_book(
+    #[case] result: Result<(), AppError>,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let book_id = BookId::new();
+    let purged = result.is_ok();
+
+    let mut fixture = registry_for_user(user_id, Role::Admin);
+    let mut book_repository = MockBookRepository::new();
+    book_repository
+        .expect_purge()
+        .withf(move |event| event.book_id == book_id && event.requested_user == user_id)
+        .times(1)
+        .return_once(move |_| result);
+    let book_repository = Arc::new(book_repository);
+    fixture
+        .expect_book_repository()
+        .returning(move || book_repository.clone());
+
+    // 完全に削除できた場合のみ、表紙画像（元の画像とサムネイル）も削除する
+    let mut object_storage = MockObjectStorage::new();
+    object_storage
+        .expect_delete()
+        .times(if purged { 2 } else { 0 })
+        .returning(|_| Ok(()));
+    let object_storage = Arc::new(object_storage);
+    fixture
+        .expect_object_storage()
+        .returning(move || object_storage.clone());
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::delete(&v1(&format!("/books/{}/purge", book_id)))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+
+    Ok(())
+}
+
+#[rstest]
+#[case::without_condition(None, axum::http::StatusCode::OK)]
+#[case::modified(Some("\"2\""), axum::http::StatusCode::OK)]
+#[case::not_modified(Some("\"3\""), axum::http::StatusCode::NOT_MODIFIED)]
+#[case::any(Some("*"), axum::http::StatusCode::NOT_MODIFIED)]
+#[tokio::test]
+async fn show_book_with_etag(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] if_none_match: Option<&str>,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id().returning(move |_| {
+            Ok(Some(Book {
+                id: book_id,
+                version: 3,
+                ..export_book("title")
+            }))
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let mut req = Request::get(&v1(&format!("/books/{}", book_id))).bearer();
+    if let Some(if_none_match) = if_none_match {
+        req = req.header("If-None-Match", if_none_match);
+    }
+    let resp = app.oneshot(req.body(Body::empty())?).await?;
+    assert_eq!(resp.status(), expected_status);
+    // 304 の場合も ETag を返す
+    assert_eq!(resp.headers()["etag"], "\"3\"");
+
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await?;
+    assert_eq!(
+        body.is_empty(),
+        expected_status == axum::http::StatusCode::NOT_MODIFIED
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[case::matched(Some("\"3\""), Ok(()), axum::http::StatusCode::OK)]
+#[case::any(Some("*"), Ok(()), axum::http::StatusCode::OK)]
+#[case::missing(None, Ok(()), axum::http::StatusCode::PRECONDITION_REQUIRED)]
+#[case::stale(Some("\"2\""), Ok(()), axum::http::StatusCode::PRECONDITION_FAILED)]
+// 確認した後、更新する前に他の操作で更新された場合
+#[case::raced(
+    Some("\"3\""),
+    Err(AppError::PreconditionFailed("更新済み".into())),
+    axum::http::StatusCode::PRECONDITION_FAILED
+)]
+#[tokio::test]
+async fn update_book_with_if_match(
+    #[case] if_match: Option<&str>,
+    #[case] result: Result<(), AppError>,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let book_id = BookId::new();
+    // 更新できた場合と、リポジトリで更新が拒否された場合のみ更新を試みる
+    let updated = expected_status == axum::http::StatusCode::OK || result.is_err();
+
+    let mut book_repository = MockBookRepository::new();
+    book_repository.expect_find_by_id().returning(move |_| {
+        Ok(Some(Book {
+            id: book_id,
+            version: 3,
+            ..export_book("title")
+        }))
+    });
+    // リポジトリでも更新の直前に同じバージョンと照合させる
+    book_repository
+        .expect_update()
+        .withf(move |event| event.book_id == book_id && event.expected_version == Some(3))
+        .times(usize::from(updated))
+        .return_once(move |_| result);
+    let book_repository = Arc::new(book_repository);
+
+    let mut fixture = registry_for_user(user_id, Role::User);
+    fixture
+        .expect_book_repository()
+        .returning(move || book_repository.clone());
+
+    let app: axum::Router = make_router(fixture);
+
+    let mut req = Request::put(&v1(&format!("/books/{}", book_id)))
+        .bearer()
+        .header("Content-Type", "application/json");
+    if let Some(if_match) = if_match {
+        req = req.header("If-Match", if_match);
+    }
+    let body = serde_json::json!({
+        "title": "title",
+        "author": "author",
+        "isbn": "9784065369579",
+        "description": "",
+    });
+    let resp = app
+        .oneshot(req.body(Body::from(serde_json::to_vec(&body)?))?)
+        .await?;
+    assert_eq!(resp.status(), expected_status);
+
+    Ok(())
+}
+
+#[rstest]
+#[case::missing(None, axum::http::StatusCode::PRECONDITION_REQUIRED)]
+#[case::stale(Some("\"2\""), axum::http::StatusCode::PRECONDITION_FAILED)]
+#[tokio::test]
+async fn delete_book_with_stale_if_match(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] if_match: Option<&str>,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id().returning(move |_| {
+            Ok(Some(Book {
+                id: book_id,
+                version: 3,
+                ..export_book("title")
+            }))
+        });
+        mock.expect_delete().never();
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let mut req = Request::delete(&v1(&format!("/books/{}", book_id))).bearer();
+    if let Some(if_match) = if_match {
+        req = req.header("If-Match", if_match);
+    }
+    let resp = app.oneshot(req.body(Body::empty())?).await?;
+    assert_eq!(resp.status(), expected_status);
+
+    Ok(())
+}
+
+fn patch_request(
+    book_id: BookId,
+    if_match: Option<&str>,
+    body: &str,
+) -> axum::http::Result<Request<Body>> {
+    let mut req = Request::patch(&v1(&format!("/books/{}", book_id)))
+        .bearer()
+        .header("Content-Type", "application/merge-patch+json");
+    if let Some(if_match) = if_match {
+        req = req.header("If-Match", if_match);
+    }
+    req.body(Body::from(body.to_string()))
+}
+
+#[rstest]
+#[case::description(r#"{"description":"説明"}"#, None, None, Some("説明"))]
+#[case::title(r#"{"title":"タイトル"}"#, Some("タイトル"), None, None)]
+#[case::isbn(r#"{"isbn":"978-4-06-536957-9"}"#, None, Some("9784065369579"), None)]
+// null を指定した description は、項目を削除したものとして空文字にする
+#[case::null_description(r#"{"description":null}"#, None, None, Some(""))]
+#[case::empty("{}", None, None, None)]
+#[tokio::test]
+async fn patch_book_200(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] body: &str,
+    #[case] title: Option<&'static str>,
+    #[case] isbn: Option<&'static str>,
+    #[case] description: Option<&'static str>,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        // 事前に蔵書を取得せず、If-Match のバージョンをリポジトリに渡して照合させる
+        mock.expect_find_by_id().never();
+        mock.expect_patch()
+            .withf(move |event| {
+                event.book_id == book_id
+                    && event.title.as_deref() == title
+                    && event.author.is_none()
+                    && event.isbn.as_ref().map(|isbn| isbn.as_str()) == isbn
+                    && event.description.as_deref() == description
+                    && event.expected_version == Some(1)
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let resp = app
+        .oneshot(patch_request(book_id, Some("\"1\""), body)?)
+        .await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[case::empty_title(r#"{"title":""}"#, axum::http::StatusCode::BAD_REQUEST)]
+#[case::invalid_isbn(r#"{"isbn":"9784065369570"}"#, axum::http::StatusCode::BAD_REQUEST)]
+// 必須の項目は削除できない
+#[case::null_title(r#"{"title":null}"#, axum::http::StatusCode::UNPROCESSABLE_ENTITY)]
+#[case::null_isbn(r#"{"isbn":null}"#, axum::http::StatusCode::UNPROCESSABLE_ENTITY)]
+#[tokio::test]
+async fn patch_book_invalid(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] body: &str,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_patch().never();
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let resp = app
+        .oneshot(patch_request(book_id, Some("\"1\""), body)?)
+        .await?;
+    assert_eq!(resp.status(), expected_status);
+
+    Ok(())
+}
+
+#[rstest]
+#[case::matched(Some("\"3\""), Some(Some(3)), axum::http::StatusCode::OK)]
+#[case::stale(
+    Some("\"2\""),
+    Some(Some(2)),
+    axum::http::StatusCode::PRECONDITION_FAILED
+)]
+// "*" の場合はバージョンを照合しない
+#[case::any(Some("*"), Some(None), axum::http::StatusCode::OK)]
+// 取得時の ETag ではない値は、リポジトリを呼ばずに 412 を返す
+#[case::weak(Some("W/\"3\""), None, axum::http::StatusCode::PRECONDITION_FAILED)]
+#[case::missing(None, None, axum::http::StatusCode::PRECONDITION_REQUIRED)]
+#[tokio::test]
+async fn patch_book_with_if_match(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] if_match: Option<&str>,
+    #[case] expected_version: Option<Option<i64>>,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id().never();
+        // 現在のバージョンが 3 の蔵書として照合する
+        mock.expect_patch()
+            .withf(move |event| {
+                event.book_id == book_id && Some(event.expected_version) == expected_version
+            })
+            .times(usize::from(expected_version.is_some()))
+            .returning(|event| match event.expected_version {
+                Some(version) if version != 3 => Err(AppError::PreconditionFailed("stale".into())),
+                _ => Ok(()),
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let resp = app
+        .oneshot(patch_request(
+            book_id,
+            if_match,
+            r#"{"description":"説明"}"#,
+        )?)
+        .await?;
+    assert_eq!(resp.status(), expected_status);
+
+    Ok(())
+}
+
+#[rstest]
+#[case::owner(Role::User, true, false, axum::http::StatusCode::OK)]
+#[case::admin(Role::Admin, false, false, axum::http::StatusCode::OK)]
+#[case::other_user(Role::User, false, false, axum::http::StatusCode::FORBIDDEN)]
+// 完全に削除された蔵書は所有者がわからないため、Admin のみ取得できる
+#[case::purged_admin(Role::Admin, false, true, axum::http::StatusCode::OK)]
+#[case::purged_user(Role::User, true, true, axum::http::StatusCode::FORBIDDEN)]
+#[tokio::test]
+async fn show_book_history(
+    #[case] role: Role,
+    #[case] is_owner: bool,
+    #[case] purged: bool,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let owner_id = if is_owner { user_id } else { UserId::new() };
+    let book_id = BookId::new();
+    let changed_at = Utc::now();
+
+    let mut fixture = registry_for_user(user_id, role);
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_history()
+            .withf(move |id| *id == book_id)
+            .returning(move |_| {
+                Ok(Some(BookHistory {
+                    book_id,
+                    owned_by: (!purged).then_some(owner_id),
+                    entries: vec![BookHistoryEntry {
+                        id: BookHistoryId::new(),
+                        action: BookHistoryAction::Update,
+                        changed_by: Some(owner_id),
+                        changed_at,
+                        changes: vec![BookFieldChange {
+                            field: BookField::Title,
+                            before: Some("旧タイトル".into()),
+                            after: Some("新タイトル".into()),
+                        }],
+                    }],
+                }))
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1(&format!("/books/{}/history", book_id)))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+
+    if expected_status == axum::http::StatusCode::OK {
+        let result = deserialize_json!(resp, BookHistoryResponse);
+        assert_eq!(result.book_id, book_id);
+        assert_eq!(result.items.len(), 1);
+        let entry = &result.items[0];
+        assert_eq!(entry.action, BookHistoryActionName::Update);
+        assert_eq!(entry.changed_by, Some(owner_id));
+        assert_eq!(
+            entry.changes,
+            vec![BookFieldChangeResponse {
+                field: BookFieldName::Title,
+                before: Some("旧タイトル".into()),
+                after: Some("新タイトル".into()),
+            }]
+        );
+    }
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_book_history_404(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_history().returning(|_| Ok(None));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1(&format!("/books/{}/history", BookId::new())))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::NOT_FOUND);
+
+    Ok(())
+}
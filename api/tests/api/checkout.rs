@@ -0,0 +1,336 @@
+use std::sync::Arc;
+
+use axum::{body::Body, http::Request};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture, make_router, v1, TestRequestExt},
+};
+use kernel::{
+    model::{
+        checkout::ReturnOutcome,
+        id::{BookId, BranchId, CheckoutId, UserId},
+        list::CursorPaginatedList,
+    },
+    repository::checkout::MockCheckoutRepository,
+};
+use shared::error::{AppError, LoanPolicyViolation};
+
+#[rstest]
+#[case("/books/checkouts", false, None)]
+#[case("/books/checkouts?overdue=false", false, None)]
+#[case("/books/checkouts?limit=5", false, Some(5))]
+#[case("/books/checkouts?cursor=abc", false, Some(20))]
+#[case("/books/checkouts?overdue=true", true, None)]
+#[case("/books/checkouts?overdue=true&limit=5", true, Some(5))]
+#[tokio::test]
+async fn show_checked_out_list_with_overdue_200(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] overdue: bool,
+    #[case] limit: Option<i64>,
+) -> anyhow::Result<()> {
+    // overdue=true の場合のみ、返却期限を過ぎた貸出を取得するメソッドが呼ばれる
+    // limit と cursor のどちらも指定しない場合は、件数を制限しない
+    fixture.expect_checkout_repository().returning(move || {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_find_unreturned_all()
+            .withf(move |opt| opt.limit == limit)
+            .times(if overdue { 0 } else { 1 })
+            .returning(|opt| {
+                Ok(CursorPaginatedList {
+                    limit: opt.limit.unwrap_or_default(),
+                    items: vec![],
+                    next_cursor: None,
+                    prev_cursor: None,
+                })
+            });
+        mock.expect_find_overdue_all()
+            .withf(move |_, opt| opt.limit == limit)
+            .times(if overdue { 1 } else { 0 })
+            .returning(|_, opt| {
+                Ok(CursorPaginatedList {
+                    limit: opt.limit.unwrap_or_default(),
+                    items: vec![],
+                    next_cursor: None,
+                    prev_cursor: None,
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/books/checkouts?limit=-1")]
+#[case("/books/checkouts?limit=101")]
+#[case("/books/checkouts?overdue=true&limit=9223372036854775807")]
+#[tokio::test]
+async fn show_checked_out_list_400(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+) -> anyhow::Result<()> {
+    // 件数の指定が範囲外の場合は、貸出を取得せずにエラーを返す
+    fixture.expect_checkout_repository().returning(|| {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_find_unreturned_all().never();
+        mock.expect_find_overdue_all().never();
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(true, axum::http::StatusCode::OK)]
+#[case(false, axum::http::StatusCode::UNPROCESSABLE_ENTITY)]
+#[tokio::test]
+async fn renew_checkout(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] renewable: bool,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    let checkout_id = CheckoutId::new();
+
+    // 延長回数の上限に達している場合などは延長できない
+    fixture.expect_checkout_repository().returning(move || {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_renew()
+            .withf(move |event| event.book_id == book_id && event.checkout_id == checkout_id)
+            .times(1)
+            .returning(move |_| {
+                if renewable {
+                    Ok(())
+                } else {
+                    Err(AppError::UnprocessableEntity("renewal limit".into()))
+                }
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::put(&v1(&format!(
+        "/books/{book_id}/checkouts/{checkout_id}/renewal"
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn checkout_book_loan_policy_violation_422(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+
+    fixture.expect_checkout_repository().returning(move || {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_create().returning(|_| {
+            Err(AppError::LoanPolicyViolation(
+                LoanPolicyViolation::MaxLoansExceeded { limit: 5 },
+            ))
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::post(&v1(&format!("/books/{book_id}/checkouts")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+
+    // 違反の理由をクライアントが判別できる
+    let body = deserialize_json!(resp, serde_json::Value);
+    assert_eq!(body["code"], "max_loans_exceeded");
+    assert_eq!(body["limit"], 5);
+
+    Ok(())
+}
+
+#[rstest]
+#[case::without_body(false)]
+#[case::with_branch(true)]
+#[tokio::test]
+async fn checkout_book_at_branch(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] with_branch: bool,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    let branch_id = with_branch.then(BranchId::new);
+
+    // ボディを省略した場合は、手続きをした館を記録しない
+    fixture.expect_checkout_repository().returning(move || {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_create()
+            .withf(move |event| event.book_id == book_id && event.branch_id == branch_id)
+            .times(1)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::post(&v1(&format!("/books/{book_id}/checkouts"))).bearer();
+    let req = match branch_id {
+        Some(branch_id) => req
+            .application_json()
+            .body(Body::from(format!(r#"{{"branchId":"{branch_id}"}}"#)))?,
+        None => req.body(Body::empty())?,
+    };
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::CREATED);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(None, ReturnOutcome::Ok, None)]
+#[case(
+    Some(r#"{"outcome":"lost","note":"紛失"}"#),
+    ReturnOutcome::Lost,
+    Some("紛失")
+)]
+#[case(Some(r#"{"outcome":"damaged"}"#), ReturnOutcome::Damaged, None)]
+#[tokio::test]
+async fn return_book_with_outcome(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] body: Option<&'static str>,
+    #[case] outcome: ReturnOutcome,
+    #[case] note: Option<&'static str>,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    let checkout_id = CheckoutId::new();
+
+    // ボディを省略した場合は通常の返却として扱う
+    fixture.expect_checkout_repository().returning(move || {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_update_returned()
+            .withf(move |event| {
+                event.checkout_id == checkout_id
+                    && !event.by_admin
+                    && event.outcome == outcome
+                    && event.note.as_deref() == note
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::put(&v1(&format!(
+        "/books/{book_id}/checkouts/{checkout_id}/returned"
+    )))
+    .bearer();
+    let req = match body {
+        Some(body) => req.application_json().body(Body::from(body))?,
+        None => req.body(Body::empty())?,
+    };
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn checkout_book_on_behalf_forbidden_403(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    // 一般ユーザーは他のユーザーに代わって借りることはできない
+    fixture
+        .expect_checkout_repository()
+        .returning(|| Arc::new(MockCheckoutRepository::new()));
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::post(&v1(&format!("/users/{}/checkouts", UserId::new())))
+        .bearer()
+        .application_json()
+        .body(Body::from(format!(r#"{{"bookId":"{}"}}"#, BookId::new())))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/users/me/checkout-history", axum::http::StatusCode::OK)]
+#[case(
+    "/users/me/checkout-history?from=2024-01-01&to=2024-12-31",
+    axum::http::StatusCode::OK
+)]
+#[case(
+    "/users/me/checkout-history?from=2024-12-31&to=2024-01-01",
+    axum::http::StatusCode::BAD_REQUEST
+)]
+#[case(
+    "/users/me/checkout-history?limit=9223372036854775807",
+    axum::http::StatusCode::BAD_REQUEST
+)]
+#[case(
+    &format!("/users/{}/checkout-history", UserId::new()),
+    axum::http::StatusCode::FORBIDDEN
+)]
+#[tokio::test]
+async fn show_checkout_history(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    // to の日付は、その日の終わりまでを範囲に含める
+    fixture.expect_checkout_repository().returning(|| {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_find_history_by_user_id()
+            .withf(|_, opt| {
+                let day = |y, m, d| {
+                    chrono::NaiveDate::from_ymd_opt(y, m, d)
+                        .unwrap()
+                        .and_time(chrono::NaiveTime::MIN)
+                        .and_utc()
+                };
+                opt.checked_out_from.unwrap_or(day(2024, 1, 1)) == day(2024, 1, 1)
+                    && opt.checked_out_to.unwrap_or(day(2025, 1, 1)) == day(2025, 1, 1)
+            })
+            .returning(|_, opt| {
+                Ok(CursorPaginatedList {
+                    limit: opt.limit.unwrap_or_default(),
+                    items: vec![],
+                    next_cursor: None,
+                    prev_cursor: None,
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
@@ -0,0 +1,283 @@
+use std::{io::Cursor, sync::Arc};
+
+use axum::{body::Body, http::Request};
+use chrono::Utc;
+use image::{DynamicImage, ImageFormat};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{make_router, registry_for_user, v1, TestRequestExt},
+};
+use api::{cover::MAX_COVER_BYTES, model::book::BookResponse};
+use kernel::{
+    model::{
+        book::{cover::BookCover, Book},
+        id::{BookId, UserId},
+        role::Role,
+        user::BookOwner,
+    },
+    repository::{book::MockBookRepository, storage::MockObjectStorage},
+};
+
+const MULTIPART_BOUNDARY: &str = "test-boundary";
+
+fn encode_image(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+    let mut body = Cursor::new(Vec::new());
+    DynamicImage::new_rgb8(width, height)
+        .write_to(&mut body, format)
+        .unwrap();
+    body.into_inner()
+}
+
+fn book(book_id: BookId, owner_id: UserId, cover: Option<BookCover>) -> Book {
+    Book {
+        id: book_id,
+        title: "RustによるWebアプリケーション開発".to_string(),
+        isbn: "9784065369579".parse().unwrap(),
+        author: "Yuki Toyoda".to_string(),
+        description: "".to_string(),
+        owner: BookOwner {
+            id: owner_id,
+            name: "Yuki Toyoda".to_string(),
+        },
+        copies: Default::default(),
+        checkouts: vec![],
+        cover,
+        tags: vec![],
+        location: None,
+        version: 1,
+    }
+}
+
+// file フィールドに画像を添付した multipart/form-data のリクエストを組み立てる
+fn upload_request(
+    book_id: BookId,
+    content_type: &str,
+    file: &[u8],
+) -> axum::http::Result<Request<Body>> {
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"cover\"\r\nContent-Type: {}\r\n\r\n",
+        MULTIPART_BOUNDARY, content_type
+    )
+    .into_bytes();
+    body.extend_from_slice(file);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", MULTIPART_BOUNDARY).as_bytes());
+    Request::put(&v1(&format!("/books/{}/cover", book_id)))
+        .bearer()
+        .header(
+            "Content-Type",
+            format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY),
+        )
+        .body(Body::from(body))
+}
+
+#[rstest]
+#[case::owner(Role::User, true, axum::http::StatusCode::OK)]
+#[case::admin(Role::Admin, false, axum::http::StatusCode::OK)]
+#[case::other_user(Role::User, false, axum::http::StatusCode::FORBIDDEN)]
+#[tokio::test]
+async fn upload_book_cover(
+    #[case] role: Role,
+    #[case] is_owner: bool,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let owner_id = if is_owner { user_id } else { UserId::new() };
+    let book_id = BookId::new();
+    let uploaded = expected_status == axum::http::StatusCode::OK;
+
+    // 蔵書の取得と表紙画像の記録で同じモックを使い、呼び出し回数を検証する
+    let mut book_repository = MockBookRepository::new();
+    book_repository
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(book(book_id, owner_id, None))));
+    // 管理者が差し替えた場合は by_admin が立つ
+    book_repository
+        .expect_update_cover()
+        .times(usize::from(uploaded))
+        .returning(move |event| {
+            assert_eq!(event.book_id, book_id);
+            assert_eq!(event.content_type, "image/png");
+            assert_eq!(event.requested_user, user_id);
+            assert_eq!(event.by_admin, role == Role::Admin);
+            Ok(())
+        });
+    let book_repository = Arc::new(book_repository);
+
+    let mut object_storage = MockObjectStorage::new();
+    // 権限がない場合はストレージに保存しない
+    object_storage
+        .expect_put()
+        .times(usize::from(uploaded))
+        .withf(move |key, _, content_type| {
+            key == format!("covers/{}/original", book_id) && content_type == "image/png"
+        })
+        .returning(|_, _, _| Ok(()));
+    object_storage
+        .expect_put()
+        .times(usize::from(uploaded))
+        .withf(move |key, body, _| {
+            let thumbnail = image::load_from_memory(body).unwrap();
+            key == format!("covers/{}/thumbnail", book_id)
+                && (thumbnail.width(), thumbnail.height()) == (200, 100)
+        })
+        .returning(|_, _, _| Ok(()));
+    let object_storage = Arc::new(object_storage);
+
+    let mut fixture = registry_for_user(user_id, role);
+    fixture
+        .expect_book_repository()
+        .returning(move || book_repository.clone());
+    fixture
+        .expect_object_storage()
+        .returning(move || object_storage.clone());
+
+    let app: axum::Router = make_router(fixture);
+
+    let file = encode_image(800, 400, ImageFormat::Png);
+    let resp = app
+        .oneshot(upload_request(book_id, "image/png", &file)?)
+        .await?;
+    assert_eq!(resp.status(), expected_status);
+
+    Ok(())
+}
+
+#[rstest]
+#[case::unsupported_type(
+    "image/gif",
+    encode_image(10, 10, ImageFormat::Png),
+    axum::http::StatusCode::BAD_REQUEST
+)]
+#[case::mismatched_type(
+    "image/jpeg",
+    encode_image(10, 10, ImageFormat::Png),
+    axum::http::StatusCode::BAD_REQUEST
+)]
+#[case::not_an_image("image/png", b"not an image".to_vec(), axum::http::StatusCode::BAD_REQUEST)]
+#[case::too_large("image/png", vec![0; MAX_COVER_BYTES + 1], axum::http::StatusCode::PAYLOAD_TOO_LARGE)]
+#[tokio::test]
+async fn upload_book_cover_invalid_file(
+    #[case] content_type: &str,
+    #[case] file: Vec<u8>,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let book_id = BookId::new();
+
+    let mut fixture = registry_for_user(user_id, Role::User);
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id()
+            .returning(move |_| Ok(Some(book(book_id, user_id, None))));
+        Arc::new(mock)
+    });
+    fixture
+        .expect_object_storage()
+        .returning(|| Arc::new(MockObjectStorage::new()));
+
+    let app: axum::Router = make_router(fixture);
+
+    let resp = app
+        .oneshot(upload_request(book_id, content_type, &file)?)
+        .await?;
+    assert_eq!(resp.status(), expected_status);
+
+    Ok(())
+}
+
+#[rstest]
+#[case::original("/cover", "covers/{}/original")]
+#[case::thumbnail("/cover/thumbnail", "covers/{}/thumbnail")]
+#[tokio::test]
+async fn show_book_cover(#[case] path: &str, #[case] key: &str) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    let key = key.replace("{}", &book_id.to_string());
+    let cover = BookCover {
+        content_type: "image/jpeg".into(),
+        updated_at: Utc::now(),
+    };
+    let image = encode_image(10, 10, ImageFormat::Jpeg);
+
+    let mut fixture = registry_for_user(UserId::new(), Role::User);
+    let book_cover = cover.clone();
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        let book_cover = book_cover.clone();
+        mock.expect_find_by_id()
+            .returning(move |_| Ok(Some(book(book_id, UserId::new(), Some(book_cover.clone())))));
+        Arc::new(mock)
+    });
+    let body = image.clone();
+    fixture.expect_object_storage().returning(move || {
+        let mut mock = MockObjectStorage::new();
+        let (key, body) = (key.clone(), body.clone());
+        mock.expect_get()
+            .withf(move |k| k == key)
+            .returning(move |_| Ok(Some(body.clone())));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    // img 要素から参照できるよう、認証なしで取得できる
+    let req = Request::get(&v1(&format!("/books/{}{}", book_id, path))).body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "image/jpeg");
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await?;
+    assert_eq!(bytes.to_vec(), image);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_book_with_cover_url() -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    let updated_at = Utc::now();
+
+    let mut fixture = registry_for_user(UserId::new(), Role::User);
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id().returning(move |_| {
+            let cover = BookCover {
+                content_type: "image/png".into(),
+                updated_at,
+            };
+            Ok(Some(book(book_id, UserId::new(), Some(cover))))
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1(&format!("/books/{}", book_id)))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    // 差し替えると URL が変わるよう、更新日時が含まれる
+    let result = deserialize_json!(resp, BookResponse);
+    assert_eq!(
+        result.cover_url,
+        Some(format!(
+            "/api/v1/books/{}/cover?v={}",
+            book_id,
+            updated_at.timestamp_millis()
+        ))
+    );
+    assert_eq!(
+        result.cover_thumbnail_url,
+        Some(format!(
+            "/api/v1/books/{}/cover/thumbnail?v={}",
+            book_id,
+            updated_at.timestamp_millis()
+        ))
+    );
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use api::model::fine::FinesResponse;
+use axum::{body::Body, http::Request};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture, make_router, v1, TestRequestExt},
+};
+use kernel::{
+    model::{fine::UserFines, id::UserId},
+    repository::fine::MockFineRepository,
+};
+
+#[rstest]
+#[tokio::test]
+async fn show_my_fines_200(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture.expect_fine_repository().returning(|| {
+        let mut mock = MockFineRepository::new();
+        mock.expect_find_by_user_id().returning(|user_id| {
+            Ok(UserFines {
+                user_id,
+                balance: 120,
+                entries: vec![],
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1("/users/me/fines"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, FinesResponse);
+    assert_eq!(result.balance, 120);
+    assert!(result.items.is_empty());
+
+    Ok(())
+}
+
+// 一般ユーザーは他のユーザーの延滞料金を参照できず、支払い・免除も記録できない
+#[rstest]
+#[case(
+    Request::get(&v1(&format!("/users/{}/fines", UserId::new())))
+        .bearer()
+        .body(Body::empty())
+)]
+#[case(
+    Request::post(&v1(&format!("/users/{}/fines/payments", UserId::new())))
+        .bearer()
+        .application_json()
+        .body(Body::from(r#"{"amount":100}"#))
+)]
+#[case(
+    Request::post(&v1(&format!("/users/{}/fines/waivers", UserId::new())))
+        .bearer()
+        .application_json()
+        .body(Body::from(r#"{"amount":100}"#))
+)]
+#[tokio::test]
+async fn fines_forbidden_403(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] req: axum::http::Result<Request<Body>>,
+) -> anyhow::Result<()> {
+    fixture
+        .expect_fine_repository()
+        .returning(|| Arc::new(MockFineRepository::new()));
+
+    let app: axum::Router = make_router(fixture);
+
+    let resp = app.oneshot(req?).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::FORBIDDEN);
+
+    Ok(())
+}
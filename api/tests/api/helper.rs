@@ -26,40 +26,65 @@ pub fn fixture_registry() -> MockAppRegistryExt {
 }
 
 #[fixture]
-pub fn fixture_auth(mut fixture_registry: MockAppRegistryExt) -> MockAppRegistryExt {
-    fixture_registry.expect_auth_repository().returning(|| {
+pub fn fixture_auth(fixture_registry: MockAppRegistryExt) -> MockAppRegistryExt {
+    with_auth(fixture_registry, UserId::new)
+}
+
+// トークンから取得されるユーザーの ID を user_id で決める
+fn with_auth(
+    mut registry: MockAppRegistryExt,
+    user_id: impl Fn() -> UserId + Copy + Send + Sync + 'static,
+) -> MockAppRegistryExt {
+    registry.expect_auth_repository().returning(move || {
         let mut mock_auth_repository = MockAuthRepository::new();
         mock_auth_repository
             .expect_fetch_user_id_from_token()
-            .returning(|_| Ok(Some(UserId::new())));
+            .returning(move |_| Ok(Some(user_id())));
         mock_auth_repository
             .expect_verify_user()
-            .returning(|_, _| Ok(UserId::new()));
+            .returning(move |_, _| Ok(user_id()));
         mock_auth_repository
             .expect_create_token()
             .returning(|_| Ok(AccessToken("dummy".into())));
         Arc::new(mock_auth_repository)
     });
-    fixture_registry
+    registry
+}
+
+#[fixture]
+pub fn fixture(fixture_auth: MockAppRegistryExt) -> MockAppRegistryExt {
+    with_current_user_role(fixture_auth, Role::User)
 }
 
+// ログイン中のユーザーが Admin の場合の fixture
 #[fixture]
-pub fn fixture(mut fixture_auth: MockAppRegistryExt) -> MockAppRegistryExt {
-    fixture_auth.expect_user_repository().returning(|| {
+pub fn fixture_admin(fixture_auth: MockAppRegistryExt) -> MockAppRegistryExt {
+    with_current_user_role(fixture_auth, Role::Admin)
+}
+
+// ログイン中のユーザーの ID とロールを指定する場合に使う
+pub fn registry_for_user(user_id: UserId, role: Role) -> MockAppRegistryExt {
+    let registry = with_auth(MockAppRegistryExt::new(), move || user_id);
+    with_current_user_role(registry, role)
+}
+
+fn with_current_user_role(mut registry: MockAppRegistryExt, role: Role) -> MockAppRegistryExt {
+    registry.expect_user_repository().returning(move || {
         let mut mock_user_repository = MockUserRepository::new();
         mock_user_repository
             .expect_find_current_user()
-            .returning(|id| {
+            .returning(move |id| {
                 Ok(Some(User {
                     id,
                     name: "dummy-user".to_string(),
                     email: "dummy@example.com".to_string(),
-                    role: Role::User,
+                    role,
+                    version: 1,
                 }))
             });
         Arc::new(mock_user_repository)
     });
-    fixture_auth
+    registry
 }
 
 pub trait TestRequestExt {
@@ -0,0 +1,238 @@
+use std::sync::Arc;
+
+use axum::{body::Body, http::Request};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture, fixture_admin, make_router, registry_for_user, v1, TestRequestExt},
+};
+use api::model::location::{BranchesResponse, ShelfResponse};
+use kernel::{
+    model::{
+        id::{BookId, BranchId, ShelfId, UserId},
+        list::PaginatedList,
+        location::{Branch, Shelf},
+        role::Role,
+    },
+    repository::{book::MockBookRepository, location::MockLocationRepository},
+};
+use shared::error::AppError;
+
+#[rstest]
+#[tokio::test]
+async fn show_branch_list(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let branch_id = BranchId::new();
+    fixture.expect_location_repository().returning(move || {
+        let mut mock = MockLocationRepository::new();
+        mock.expect_find_branches().returning(move || {
+            Ok(vec![Branch {
+                id: branch_id,
+                name: "Main".into(),
+            }])
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1("/branches"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, BranchesResponse);
+    assert_eq!(result.items.len(), 1);
+    assert_eq!(result.items[0].id, branch_id);
+    assert_eq!(result.items[0].name, "Main");
+
+    Ok(())
+}
+
+#[rstest]
+#[case(Role::Admin, r#"{"name":""}"#, axum::http::StatusCode::BAD_REQUEST)]
+#[case(Role::User, r#"{"name":"Main"}"#, axum::http::StatusCode::FORBIDDEN)]
+#[tokio::test]
+async fn register_branch_rejected(
+    #[case] role: Role,
+    #[case] body: &'static str,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let mut fixture = registry_for_user(UserId::new(), role);
+    // 検証や権限の確認で弾かれるため、館は登録されない
+    fixture.expect_location_repository().returning(|| {
+        let mut mock = MockLocationRepository::new();
+        mock.expect_create_branch().never();
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::post(&v1("/branches"))
+        .bearer()
+        .application_json()
+        .body(Body::from(body))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn register_shelf_201(mut fixture_admin: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let branch_id = BranchId::new();
+    let shelf_id = ShelfId::new();
+    fixture_admin
+        .expect_location_repository()
+        .returning(move || {
+            let mut mock = MockLocationRepository::new();
+            mock.expect_create_shelf()
+                .withf(move |event| event.branch_id == branch_id && event.name == "A-1")
+                .returning(move |event| {
+                    Ok(Shelf {
+                        id: shelf_id,
+                        branch_id: event.branch_id,
+                        name: event.name,
+                    })
+                });
+            Arc::new(mock)
+        });
+
+    let app: axum::Router = make_router(fixture_admin);
+
+    let req = Request::post(&v1(&format!("/branches/{}/shelves", branch_id)))
+        .bearer()
+        .application_json()
+        .body(Body::from(r#"{"name":"A-1"}"#))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::CREATED);
+
+    let result = deserialize_json!(resp, ShelfResponse);
+    assert_eq!(result.id, shelf_id);
+    assert_eq!(result.branch_id, branch_id);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn update_shelf_404(mut fixture_admin: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let branch_id = BranchId::new();
+    let shelf_id = ShelfId::new();
+    // 別の館の書架を指定した場合は見つからない
+    fixture_admin
+        .expect_location_repository()
+        .returning(move || {
+            let mut mock = MockLocationRepository::new();
+            mock.expect_update_shelf()
+                .withf(move |event| event.branch_id == branch_id && event.shelf_id == shelf_id)
+                .returning(|_| {
+                    Err(AppError::EntityNotFound(
+                        "指定された書架が見つかりませんでした".into(),
+                    ))
+                });
+            Arc::new(mock)
+        });
+
+    let app: axum::Router = make_router(fixture_admin);
+
+    let req = Request::put(&v1(&format!(
+        "/branches/{}/shelves/{}",
+        branch_id, shelf_id
+    )))
+    .bearer()
+    .application_json()
+    .body(Body::from(r#"{"name":"B-1"}"#))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[rstest]
+#[case::owner(Role::User, Some(ShelfId::new()))]
+#[case::admin(Role::Admin, Some(ShelfId::new()))]
+#[case::unset(Role::User, None)]
+#[tokio::test]
+async fn update_book_location(
+    #[case] role: Role,
+    #[case] shelf_id: Option<ShelfId>,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let book_id = BookId::new();
+    let body = serde_json::json!({ "shelfId": shelf_id }).to_string();
+
+    let mut fixture = registry_for_user(user_id, role);
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        // 管理者が変更した場合は by_admin が立つ
+        mock.expect_update_location()
+            .withf(move |event| {
+                event.book_id == book_id
+                    && event.shelf_id == shelf_id
+                    && event.requested_user == user_id
+                    && event.by_admin == (role == Role::Admin)
+            })
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::put(&v1(&format!("/books/{}/location", book_id)))
+        .bearer()
+        .application_json()
+        .body(Body::from(body))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(true, false)]
+#[case(false, true)]
+#[case(true, true)]
+#[tokio::test]
+async fn show_book_list_by_location_200(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] with_branch: bool,
+    #[case] with_shelf: bool,
+) -> anyhow::Result<()> {
+    let branch = with_branch.then(BranchId::new);
+    let shelf = with_shelf.then(ShelfId::new);
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_all()
+            .withf(move |opt| opt.filter.branch == branch && opt.filter.shelf == shelf)
+            .returning(|opt| {
+                Ok(PaginatedList {
+                    total: 0,
+                    limit: opt.limit,
+                    offset: opt.offset,
+                    items: vec![],
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let mut query = vec![];
+    if let Some(branch) = branch {
+        query.push(format!("branch={}", branch));
+    }
+    if let Some(shelf) = shelf {
+        query.push(format!("shelf={}", shelf));
+    }
+    let req = Request::get(&v1(&format!("/books?{}", query.join("&"))))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
@@ -1,2 +1,10 @@
 mod book;
+mod checkout;
+mod cover;
+mod fine;
 mod helper;
+mod location;
+mod report;
+mod reservation;
+mod tag;
+mod user;
@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use axum::{body::Body, http::Request};
+use chrono::NaiveDate;
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::helper::{fixture, fixture_admin, make_router, v1, TestRequestExt};
+use kernel::{
+    model::{
+        id::BookId,
+        report::{BookLoanCount, CirculationStats, ReportGrouping},
+    },
+    repository::report::MockReportRepository,
+};
+
+fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_circulation_report_200(
+    mut fixture_admin: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_admin.expect_report_repository().returning(|| {
+        let mut mock = MockReportRepository::new();
+        // to の日付を含むよう、翌日を期間の終わりとして渡す
+        mock.expect_find_circulation_stats()
+            .withf(|options| {
+                options.from == day(2024, 1, 1)
+                    && options.to == day(2024, 2, 1)
+                    && options.grouping == ReportGrouping::Week
+            })
+            .returning(|_| {
+                Ok(vec![CirculationStats {
+                    period_start: day(2024, 1, 1),
+                    loans: 3,
+                    returns: 4,
+                    active_borrowers: 2,
+                    overdue_returns: 1,
+                    overdue_outstanding: 1,
+                    average_loan_days: Some(7.5),
+                }])
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_admin);
+
+    let req = Request::get(&v1(
+        "/reports/circulation?from=2024-01-01&to=2024-01-31&groupBy=week",
+    ))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await?;
+    let result: serde_json::Value = serde_json::from_slice(&body)?;
+    assert_eq!(result["groupBy"], "week");
+    assert_eq!(result["items"][0]["periodStart"], "2024-01-01");
+    assert_eq!(result["items"][0]["activeBorrowers"], 2);
+    // 延滞したまま返却されていない貸出も、延滞として割合に含める
+    assert_eq!(result["items"][0]["overdueOutstanding"], 1);
+    assert_eq!(result["items"][0]["overdueRate"], 0.4);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_most_borrowed_report_csv(
+    mut fixture_admin: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    fixture_admin.expect_report_repository().returning(move || {
+        let mut mock = MockReportRepository::new();
+        mock.expect_find_most_borrowed()
+            .withf(|_, limit| *limit == 3)
+            .returning(move |_, _| {
+                Ok(vec![BookLoanCount {
+                    period_start: day(2024, 1, 1),
+                    book_id,
+                    title: "Rust, \"実践\"".into(),
+                    author: "dummy-author".into(),
+                    loans: 5,
+                }])
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_admin);
+
+    let req = Request::get(&v1(
+        "/reports/most-borrowed?from=2024-01-01&to=2024-01-31&format=csv&limit=3",
+    ))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+    assert_eq!(
+        resp.headers()[axum::http::header::CONTENT_TYPE],
+        "text/csv; charset=utf-8"
+    );
+
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await?;
+    assert_eq!(
+        std::str::from_utf8(&body)?,
+        format!(
+            "periodStart,bookId,title,author,loans\r\n2024-01-01,{},\"Rust, \"\"実践\"\"\",dummy-author,5\r\n",
+            book_id
+        )
+    );
+
+    Ok(())
+}
+
+// 一般ユーザーはレポートを取得できない
+#[rstest]
+#[case("/reports/circulation?from=2024-01-01&to=2024-01-31")]
+#[case("/reports/most-borrowed?from=2024-01-01&to=2024-01-31")]
+#[tokio::test]
+async fn show_report_forbidden_403(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+) -> anyhow::Result<()> {
+    fixture
+        .expect_report_repository()
+        .returning(|| Arc::new(MockReportRepository::new()));
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/reports/circulation?from=2024-02-01&to=2024-01-31")]
+#[case("/reports/circulation?from=2024-01-01&to=2024-01-31&groupBy=year")]
+#[case("/reports/circulation?to=2024-01-31")]
+#[case("/reports/most-borrowed?from=2024-01-01&to=2024-01-31&limit=0")]
+#[tokio::test]
+async fn show_report_bad_request_400(
+    mut fixture_admin: registry::MockAppRegistryExt,
+    #[case] path: &str,
+) -> anyhow::Result<()> {
+    fixture_admin
+        .expect_report_repository()
+        .returning(|| Arc::new(MockReportRepository::new()));
+
+    let app: axum::Router = make_router(fixture_admin);
+
+    let req = Request::get(&v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
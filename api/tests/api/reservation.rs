@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use axum::{body::Body, http::Request};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::helper::{fixture, make_router, v1, TestRequestExt};
+use kernel::{model::id::BookId, repository::reservation::MockReservationRepository};
+use shared::error::AppError;
+
+#[rstest]
+#[case(None, axum::http::StatusCode::CREATED)]
+#[case(
+    Some(AppError::Conflict("already reserved".into())),
+    axum::http::StatusCode::CONFLICT
+)]
+#[case(
+    Some(AppError::UnprocessableEntity("available".into())),
+    axum::http::StatusCode::UNPROCESSABLE_ENTITY
+)]
+#[tokio::test]
+async fn reserve_book(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] error: Option<AppError>,
+    #[case] expected: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    let error = std::sync::Mutex::new(error);
+
+    // 予約済みの場合や、貸出可能な所蔵資料がある場合は予約できない
+    fixture
+        .expect_reservation_repository()
+        .return_once(move || {
+            let mut mock = MockReservationRepository::new();
+            mock.expect_create()
+                .withf(move |event| event.book_id == book_id)
+                .times(1)
+                .returning(move |_| match error.lock().unwrap().take() {
+                    Some(e) => Err(e),
+                    None => Ok(()),
+                });
+            Arc::new(mock)
+        });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::post(&v1(&format!("/books/{book_id}/reservations")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use std::sync::Arc;
+
+use axum::{body::Body, http::Request};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture, fixture_admin, make_router, registry_for_user, v1, TestRequestExt},
+};
+use api::model::tag::{TagResponse, TagsResponse};
+use kernel::{
+    model::{
+        id::{BookId, TagId, UserId},
+        role::Role,
+        tag::Tag,
+    },
+    repository::{book::MockBookRepository, tag::MockTagRepository},
+};
+use shared::error::AppError;
+
+#[rstest]
+#[tokio::test]
+async fn show_tag_list(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let tags = vec![
+        Tag {
+            id: TagId::new(),
+            name: "Rust".into(),
+        },
+        Tag {
+            id: TagId::new(),
+            name: "データベース".into(),
+        },
+    ];
+    let expected = tags
+        .iter()
+        .cloned()
+        .map(TagResponse::from)
+        .collect::<Vec<_>>();
+    fixture.expect_tag_repository().returning(move || {
+        let mut mock = MockTagRepository::new();
+        let tags = tags.clone();
+        mock.expect_find_all().returning(move || Ok(tags.clone()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1("/tags")).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, TagsResponse);
+    assert_eq!(result.items, expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn register_tag_201(mut fixture_admin: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let tag_id = TagId::new();
+    fixture_admin.expect_tag_repository().returning(move || {
+        let mut mock = MockTagRepository::new();
+        mock.expect_create()
+            .withf(|event| event.name == "Rust")
+            .returning(move |event| {
+                Ok(Tag {
+                    id: tag_id,
+                    name: event.name,
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_admin);
+
+    let req = Request::post(&v1("/tags"))
+        .bearer()
+        .application_json()
+        .body(Body::from(r#"{"name":"Rust"}"#))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::CREATED);
+
+    let result = deserialize_json!(resp, TagResponse);
+    assert_eq!(result.id, tag_id);
+    assert_eq!(result.name, "Rust");
+
+    Ok(())
+}
+
+#[rstest]
+#[case(Role::Admin, r#"{"name":""}"#, axum::http::StatusCode::BAD_REQUEST)]
+#[case(
+    Role::Admin,
+    r#"{"name":"Rust,Go"}"#,
+    axum::http::StatusCode::BAD_REQUEST
+)]
+#[case(
+    Role::Admin,
+    r#"{"name":" Rust"}"#,
+    axum::http::StatusCode::BAD_REQUEST
+)]
+#[case(Role::User, r#"{"name":"Rust"}"#, axum::http::StatusCode::FORBIDDEN)]
+#[tokio::test]
+async fn register_tag_rejected(
+    #[case] role: Role,
+    #[case] body: &'static str,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let mut fixture = registry_for_user(UserId::new(), role);
+    // 検証や権限の確認で弾かれるため、タグは登録されない
+    fixture.expect_tag_repository().returning(|| {
+        let mut mock = MockTagRepository::new();
+        mock.expect_create().never();
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::post(&v1("/tags"))
+        .bearer()
+        .application_json()
+        .body(Body::from(body))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected_status);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn update_tag_409(mut fixture_admin: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let tag_id = TagId::new();
+    fixture_admin.expect_tag_repository().returning(move || {
+        let mut mock = MockTagRepository::new();
+        mock.expect_update()
+            .withf(move |event| event.tag_id == tag_id && event.name == "データベース")
+            .returning(|_| {
+                Err(AppError::Conflict(
+                    "同じ名前のタグがすでに登録されています".into(),
+                ))
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_admin);
+
+    let req = Request::put(&v1(&format!("/tags/{}", tag_id)))
+        .bearer()
+        .application_json()
+        .body(Body::from(r#"{"name":"データベース"}"#))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::CONFLICT);
+
+    Ok(())
+}
+
+#[rstest]
+#[case::owner(Role::User)]
+#[case::admin(Role::Admin)]
+#[tokio::test]
+async fn update_book_tags(#[case] role: Role) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let book_id = BookId::new();
+    let tag_ids = vec![TagId::new(), TagId::new()];
+    let body = serde_json::json!({ "tagIds": tag_ids }).to_string();
+
+    let mut fixture = registry_for_user(user_id, role);
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        let tag_ids = tag_ids.clone();
+        // 管理者が付け替えた場合は by_admin が立つ
+        mock.expect_update_tags()
+            .withf(move |event| {
+                event.book_id == book_id
+                    && event.tag_ids == tag_ids
+                    && event.requested_user == user_id
+                    && event.by_admin == (role == Role::Admin)
+            })
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::put(&v1(&format!("/books/{}/tags", book_id)))
+        .bearer()
+        .application_json()
+        .body(Body::from(body))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn update_book_tags_422(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_update_tags().returning(|_| {
+            Err(AppError::UnprocessableEntity(
+                "存在しないタグが指定されています".into(),
+            ))
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::put(&v1(&format!("/books/{}/tags", book_id)))
+        .bearer()
+        .application_json()
+        .body(Body::from(format!(r#"{{"tagIds":["{}"]}}"#, TagId::new())))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use std::sync::Arc;
+
+use axum::{body::Body, http::Request};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture_auth, make_router, v1, TestRequestExt},
+};
+use api::model::user::UserResponse;
+use kernel::{
+    model::{id::UserId, role::Role, user::User},
+    repository::user::MockUserRepository,
+};
+use registry::MockAppRegistryExt;
+
+// 操作対象のユーザーはバージョン 3 の一般ユーザー、それ以外（ログイン中のユーザー）は Admin とする
+fn registry_with_target_user(
+    fixture_auth: MockAppRegistryExt,
+    target_id: UserId,
+    setup: impl FnOnce(&mut MockUserRepository),
+) -> MockAppRegistryExt {
+    let mut registry = fixture_auth;
+    let mut user_repository = MockUserRepository::new();
+    user_repository
+        .expect_find_current_user()
+        .returning(move |id| {
+            let (role, version) = if id == target_id {
+                (Role::User, 3)
+            } else {
+                (Role::Admin, 1)
+            };
+            Ok(Some(User {
+                id,
+                name: "dummy-user".to_string(),
+                email: "dummy@example.com".to_string(),
+                role,
+                version,
+            }))
+        });
+    setup(&mut user_repository);
+    let user_repository = Arc::new(user_repository);
+    registry
+        .expect_user_repository()
+        .returning(move || user_repository.clone());
+    registry
+}
+
+#[rstest]
+#[case::without_condition(None, axum::http::StatusCode::OK)]
+#[case::modified(Some("\"2\""), axum::http::StatusCode::OK)]
+#[case::not_modified(Some("\"3\""), axum::http::StatusCode::NOT_MODIFIED)]
+#[tokio::test]
+async fn get_user_with_etag(
+    fixture_auth: MockAppRegistryExt,
+    #[case] if_none_match: Option<&str>,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let app = make_router(registry_with_target_user(fixture_auth, user_id, |_| {}));
+
+    let mut req = Request::get(&v1(&format!("/users/{}", user_id))).bearer();
+    if let Some(if_none_match) = if_none_match {
+        req = req.header("If-None-Match", if_none_match);
+    }
+    let resp = app.oneshot(req.body(Body::empty())?).await?;
+    assert_eq!(resp.status(), expected_status);
+    assert_eq!(resp.headers()["etag"], "\"3\"");
+
+    if expected_status == axum::http::StatusCode::OK {
+        let result = deserialize_json!(resp, UserResponse);
+        assert_eq!(result.id, user_id);
+    }
+
+    Ok(())
+}
+
+#[rstest]
+#[case::without_condition(None, axum::http::StatusCode::OK)]
+#[case::not_modified(Some("\"1\""), axum::http::StatusCode::NOT_MODIFIED)]
+#[tokio::test]
+async fn get_current_user_with_etag(
+    fixture_auth: MockAppRegistryExt,
+    #[case] if_none_match: Option<&str>,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let app = make_router(registry_with_target_user(
+        fixture_auth,
+        UserId::new(),
+        |_| {},
+    ));
+
+    let mut req = Request::get(&v1("/users/me")).bearer();
+    if let Some(if_none_match) = if_none_match {
+        req = req.header("If-None-Match", if_none_match);
+    }
+    let resp = app.oneshot(req.body(Body::empty())?).await?;
+    assert_eq!(resp.status(), expected_status);
+    assert_eq!(resp.headers()["etag"], "\"1\"");
+
+    Ok(())
+}
+
+#[rstest]
+#[case::matched(Some("\"3\""), axum::http::StatusCode::OK)]
+#[case::missing(None, axum::http::StatusCode::PRECONDITION_REQUIRED)]
+#[case::stale(Some("\"2\""), axum::http::StatusCode::PRECONDITION_FAILED)]
+#[tokio::test]
+async fn change_role_with_if_match(
+    fixture_auth: MockAppRegistryExt,
+    #[case] if_match: Option<&str>,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let updated = expected_status == axum::http::StatusCode::OK;
+    let app = make_router(registry_with_target_user(fixture_auth, user_id, |mock| {
+        mock.expect_update_role()
+            .withf(move |event| {
+                event.user_id == user_id
+                    && event.role == Role::Admin
+                    && event.expected_version == Some(3)
+            })
+            .times(usize::from(updated))
+            .returning(|_| Ok(()));
+    }));
+
+    let mut req = Request::put(&v1(&format!("/users/{}/role", user_id)))
+        .bearer()
+        .header("Content-Type", "application/json");
+    if let Some(if_match) = if_match {
+        req = req.header("If-Match", if_match);
+    }
+    let resp = app
+        .oneshot(req.body(Body::from(r#"{"role":"Admin"}"#))?)
+        .await?;
+    assert_eq!(resp.status(), expected_status);
+
+    Ok(())
+}
+
+#[rstest]
+#[case::matched(Some("\"3\""), axum::http::StatusCode::OK)]
+#[case::missing(None, axum::http::StatusCode::PRECONDITION_REQUIRED)]
+#[case::stale(Some("\"2\""), axum::http::StatusCode::PRECONDITION_FAILED)]
+#[tokio::test]
+async fn delete_user_with_if_match(
+    fixture_auth: MockAppRegistryExt,
+    #[case] if_match: Option<&str>,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let deleted = expected_status == axum::http::StatusCode::OK;
+    let app = make_router(registry_with_target_user(fixture_auth, user_id, |mock| {
+        mock.expect_delete()
+            .withf(move |event| event.user_id == user_id && event.expected_version == Some(3))
+            .times(usize::from(deleted))
+            .returning(|_| Ok(()));
+    }));
+
+    let mut req = Request::delete(&v1(&format!("/users/{}", user_id))).bearer();
+    if let Some(if_match) = if_match {
+        req = req.header("If-Match", if_match);
+    }
+    let resp = app.oneshot(req.body(Body::empty())?).await?;
+    assert_eq!(resp.status(), expected_status);
+
+    Ok(())
+}
+
+#[rstest]
+#[case::matched(Some("\"1\""), r#"{"name":"Renamed"}"#, axum::http::StatusCode::OK)]
+#[case::any(Some("*"), r#"{"name":"Renamed"}"#, axum::http::StatusCode::OK)]
+#[case::missing(
+    None,
+    r#"{"name":"Renamed"}"#,
+    axum::http::StatusCode::PRECONDITION_REQUIRED
+)]
+// 必須の項目は削除できない
+#[case::null_name(
+    Some("\"1\""),
+    r#"{"name":null}"#,
+    axum::http::StatusCode::UNPROCESSABLE_ENTITY
+)]
+#[case::invalid_email(
+    Some("\"1\""),
+    r#"{"email":"not-an-email"}"#,
+    axum::http::StatusCode::BAD_REQUEST
+)]
+#[tokio::test]
+async fn patch_current_user_with_if_match(
+    fixture_auth: MockAppRegistryExt,
+    #[case] if_match: Option<&str>,
+    #[case] body: &'static str,
+    #[case] expected_status: axum::http::StatusCode,
+) -> anyhow::Result<()> {
+    let patched = expected_status == axum::http::StatusCode::OK;
+    let expected_version = if_match.and_then(|v| v.trim_matches('"').parse().ok());
+    let app = make_router(registry_with_target_user(
+        fixture_auth,
+        UserId::new(),
+        |mock| {
+            mock.expect_patch()
+                .withf(move |event| {
+                    event.name.as_deref() == Some("Renamed")
+                        && event.email.is_none()
+                        && event.expected_version == expected_version
+                })
+                .times(usize::from(patched))
+                .returning(|_| Ok(()));
+        },
+    ));
+
+    let mut req = Request::patch(&v1("/users/me"))
+        .bearer()
+        .header("Content-Type", "application/merge-patch+json");
+    if let Some(if_match) = if_match {
+        req = req.header("If-Match", if_match);
+    }
+    let resp = app.oneshot(req.body(Body::from(body))?).await?;
+    assert_eq!(resp.status(), expected_status);
+
+    Ok(())
+}
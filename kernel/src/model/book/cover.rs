@@ -0,0 +1,28 @@
+use crate::model::id::BookId;
+use chrono::{DateTime, Utc};
+
+// 蔵書の表紙画像の情報
+// 画像そのものはオブジェクトストレージに保存し、蔵書には画像の形式と更新日時のみを記録する
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookCover {
+    pub content_type: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 表紙画像の大きさ
+// 登録時に原寸の画像とあわせてサムネイルを作り、それぞれ別のオブジェクトとして保存する
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookCoverSize {
+    Original,
+    Thumbnail,
+}
+
+impl BookCoverSize {
+    // オブジェクトストレージ上の保存先のキー
+    pub fn storage_key(self, book_id: BookId) -> String {
+        match self {
+            Self::Original => format!("covers/{book_id}/original"),
+            Self::Thumbnail => format!("covers/{book_id}/thumbnail"),
+        }
+    }
+}
@@ -1,9 +1,13 @@
-use crate::model::id::{BookId, UserId};
+use crate::model::{
+    book::isbn::Isbn,
+    id::{BookId, ShelfId, TagId, UserId},
+};
+use derive_new::new;
 
 pub struct CreateBook {
     pub title: String,
     pub author: String,
-    pub isbn: String,
+    pub isbn: Isbn,
     pub description: String,
 }
 
@@ -12,13 +16,85 @@ pub struct UpdateBook {
     pub book_id: BookId,
     pub title: String,
     pub author: String,
-    pub isbn: String,
+    pub isbn: Isbn,
     pub description: String,
     pub requested_user: UserId,
+    // 指定した場合は、蔵書の現在のバージョンと一致しなければ更新しない
+    pub expected_version: Option<i64>,
 }
 
+// 蔵書の一部の項目だけを更新する
+// None の項目は更新せず、現在の値のままにする
+#[derive(Debug)]
+pub struct PatchBook {
+    pub book_id: BookId,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub isbn: Option<Isbn>,
+    pub description: Option<String>,
+    pub requested_user: UserId,
+    // 指定した場合は、蔵書の現在のバージョンと一致しなければ更新しない
+    pub expected_version: Option<i64>,
+}
+
+// 蔵書を削除済みにする（論理削除）
+// 貸出や貸出履歴は残し、蔵書の一覧や取得の対象から外す
 #[derive(Debug)]
 pub struct DeleteBook {
     pub book_id: BookId,
     pub requested_user: UserId,
+    // 指定した場合は、蔵書の現在のバージョンと一致しなければ削除しない
+    pub expected_version: Option<i64>,
+}
+
+// 削除済みの蔵書を元に戻す
+#[derive(Debug)]
+pub struct RestoreBook {
+    pub book_id: BookId,
+    pub requested_user: UserId,
+}
+
+// 蔵書を完全に削除する（物理削除）
+// 所蔵資料・予約・タグの付与もあわせて削除し、返却済みの貸出履歴と変更履歴は残す
+#[derive(Debug)]
+pub struct PurgeBook {
+    pub book_id: BookId,
+    pub requested_user: UserId,
+}
+
+// 蔵書の表紙画像を差し替える
+// 画像はあらかじめオブジェクトストレージに保存しておき、ここではその形式を蔵書に記録する
+#[derive(Debug, new)]
+pub struct UpdateBookCover {
+    pub book_id: BookId,
+    pub content_type: String,
+    pub requested_user: UserId,
+    // 管理者が蔵書の所有者に代わって差し替える場合は true
+    // false の場合は、蔵書の所有者しか差し替えられない
+    #[new(default)]
+    pub by_admin: bool,
+}
+
+// 蔵書に付けるタグを、指定したタグの組み合わせに置き換える
+// tag_ids が空の場合は、蔵書からすべてのタグを外す
+#[derive(Debug, new)]
+pub struct UpdateBookTags {
+    pub book_id: BookId,
+    pub tag_ids: Vec<TagId>,
+    pub requested_user: UserId,
+    // 管理者が蔵書の所有者に代わって付け替える場合は true
+    #[new(default)]
+    pub by_admin: bool,
+}
+
+// 蔵書の配架場所を変更する
+// shelf_id が None の場合は、配架場所を未設定に戻す
+#[derive(Debug, new)]
+pub struct UpdateBookLocation {
+    pub book_id: BookId,
+    pub shelf_id: Option<ShelfId>,
+    pub requested_user: UserId,
+    // 管理者が蔵書の所有者に代わって変更する場合は true
+    #[new(default)]
+    pub by_admin: bool,
 }
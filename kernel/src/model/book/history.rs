@@ -0,0 +1,162 @@
+use crate::model::{
+    book::isbn::Isbn,
+    id::{BookHistoryId, BookId, UserId},
+};
+use chrono::{DateTime, Utc};
+use strum::{AsRefStr, EnumString};
+
+// 蔵書の変更履歴
+// 削除済みの蔵書の履歴も取得できるよう、閲覧の可否を判断するための所有者もあわせて持つ
+#[derive(Debug)]
+pub struct BookHistory {
+    pub book_id: BookId,
+    // 蔵書が完全に削除され、所有者がわからない場合は None
+    pub owned_by: Option<UserId>,
+    // 変更日時の新しい順に並べる
+    pub entries: Vec<BookHistoryEntry>,
+}
+
+// 蔵書の変更履歴の 1 件
+#[derive(Debug)]
+pub struct BookHistoryEntry {
+    pub id: BookHistoryId,
+    pub action: BookHistoryAction,
+    // 変更したユーザー。ユーザーが削除された場合は None
+    pub changed_by: Option<UserId>,
+    pub changed_at: DateTime<Utc>,
+    // 値が変わった項目の変更前後の値。削除・復元の場合は空になる
+    // 完全に削除した場合は、削除した時点のすべての項目の値を変更前の値として持つ
+    pub changes: Vec<BookFieldChange>,
+}
+
+#[derive(Debug, EnumString, AsRefStr, Clone, Copy, PartialEq, Eq)]
+pub enum BookHistoryAction {
+    Create,
+    Update,
+    Delete,
+    Restore,
+    Purge,
+}
+
+// 変更履歴に値を記録する蔵書の項目
+// 変更履歴の 1 件の中では、この定義の順に並べる
+#[derive(Debug, EnumString, AsRefStr, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BookField {
+    Title,
+    Author,
+    Isbn,
+    Description,
+    // 表紙画像の形式。表紙画像がない場合は None
+    Cover,
+    // 付けているタグの ID をカンマ区切りで並べたもの。タグがない場合は None
+    Tags,
+    // 配架している書架の ID。配架場所が未設定の場合は None
+    Shelf,
+}
+
+// 項目の変更前後の値。登録時は変更前の値が、完全に削除した時は変更後の値が None になる
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookFieldChange {
+    pub field: BookField,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+// 変更履歴に値を記録する蔵書の項目の値の組
+#[derive(Debug, Clone)]
+pub struct BookFieldValues {
+    pub title: String,
+    pub author: String,
+    pub isbn: Isbn,
+    pub description: String,
+}
+
+impl BookFieldValues {
+    fn values(&self) -> [(BookField, &str); 4] {
+        [
+            (BookField::Title, &self.title),
+            (BookField::Author, &self.author),
+            (BookField::Isbn, self.isbn.as_str()),
+            (BookField::Description, &self.description),
+        ]
+    }
+
+    // 変更前の値と比べて、値が変わった項目の変更前後の値を求める
+    // 変更前の値がない場合（登録時）は、すべての項目を返す
+    pub fn changes_from(&self, before: Option<&Self>) -> Vec<BookFieldChange> {
+        let before = before.map(Self::values);
+        self.values()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, (field, after))| {
+                let before = before.map(|values| values[i].1);
+                (before != Some(after)).then(|| BookFieldChange {
+                    field,
+                    before: before.map(String::from),
+                    after: Some(after.to_string()),
+                })
+            })
+            .collect()
+    }
+
+    // 完全に削除した時の変更前後の値として、すべての項目を変更後の値なしで返す
+    pub fn removed(&self) -> Vec<BookFieldChange> {
+        self.values()
+            .into_iter()
+            .map(|(field, before)| BookFieldChange {
+                field,
+                before: Some(before.to_string()),
+                after: None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(title: &str, description: &str) -> BookFieldValues {
+        BookFieldValues {
+            title: title.into(),
+            author: "author".into(),
+            isbn: "9784065369579".parse().unwrap(),
+            description: description.into(),
+        }
+    }
+
+    #[test]
+    fn test_changes_from() {
+        // 登録時はすべての項目が変更前の値なしで記録される
+        let created = values("title", "").changes_from(None);
+        assert_eq!(created.len(), 4);
+        assert!(created.iter().all(|change| change.before.is_none()));
+        assert_eq!(
+            created[2],
+            BookFieldChange {
+                field: BookField::Isbn,
+                before: None,
+                after: Some("9784065369579".into()),
+            }
+        );
+
+        // 値が変わった項目のみが記録される
+        let before = values("title", "");
+        let changes = values("new title", "").changes_from(Some(&before));
+        assert_eq!(
+            changes,
+            vec![BookFieldChange {
+                field: BookField::Title,
+                before: Some("title".into()),
+                after: Some("new title".into()),
+            }]
+        );
+        assert!(before.changes_from(Some(&before)).is_empty());
+
+        // 完全に削除した時はすべての項目が変更後の値なしで記録される
+        let removed = before.removed();
+        assert_eq!(removed.len(), 4);
+        assert!(removed.iter().all(|change| change.after.is_none()));
+        assert_eq!(removed[0].before.as_deref(), Some("title"));
+    }
+}
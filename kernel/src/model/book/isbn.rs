@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+use shared::error::AppError;
+use std::str::FromStr;
+
+// ISBN を表す型
+// ハイフン・空白を取り除き、ISBN-10 は ISBN-13 に変換した 13 桁の数字で保持する
+// ただし、ISBN の正規化以前に登録された不正な値は、データベースから読み出した値をそのまま保持する
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Isbn(String);
+
+impl Isbn {
+    // データベースに保存されている値から生成する
+    // 正規化できない値はエラーにせず、保存されている値をそのまま保持する
+    pub fn from_stored(value: String) -> Self {
+        value.parse().unwrap_or(Self(value))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    // ハイフン区切りの表記を返す（例: 978-4-06-536957-9）
+    // 出版者記号の桁数は登録グループごとの範囲表で決まるため、
+    // 範囲表を持っていない登録グループの場合は接頭記号とチェックディジットのみ区切る
+    // 13 桁の数字でない値は、区切らずにそのまま返す
+    pub fn hyphenated(&self) -> String {
+        if self.0.len() != 13 || !self.0.bytes().all(|b| b.is_ascii_digit()) {
+            return self.0.clone();
+        }
+        let (prefix, rest) = self.0.split_at(3);
+        let (body, check) = rest.split_at(9);
+        match split_group_and_registrant(prefix, body) {
+            Some((group, registrant, publication)) => {
+                format!("{prefix}-{group}-{registrant}-{publication}-{check}")
+            }
+            None => format!("{prefix}-{body}-{check}"),
+        }
+    }
+}
+
+// 登録グループ（国・言語圏）ごとの出版者記号の範囲
+// (範囲の下限, 範囲の上限, 出版者記号の桁数) の組を、先頭 7 桁の値で表す
+const REGISTRANT_RANGES_ENGLISH_0: &[(u32, u32, usize)] = &[
+    (0, 1999999, 2),
+    (2000000, 6999999, 3),
+    (7000000, 8499999, 4),
+    (8500000, 8999999, 5),
+    (9000000, 9499999, 6),
+    (9500000, 9999999, 7),
+];
+const REGISTRANT_RANGES_ENGLISH_1: &[(u32, u32, usize)] = &[
+    (0, 999999, 2),
+    (1000000, 3999999, 3),
+    (4000000, 5499999, 4),
+    (5500000, 8697999, 5),
+    (8698000, 9989999, 6),
+    (9990000, 9999999, 7),
+];
+const REGISTRANT_RANGES_JAPAN: &[(u32, u32, usize)] = &[
+    (0, 1999999, 2),
+    (2000000, 6999999, 3),
+    (7000000, 8499999, 4),
+    (8500000, 8999999, 5),
+    (9000000, 9499999, 6),
+    (9500000, 9999999, 7),
+];
+
+fn split_group_and_registrant<'a>(
+    prefix: &str,
+    body: &'a str,
+) -> Option<(&'a str, &'a str, &'a str)> {
+    let ranges = match (prefix, &body[..1]) {
+        ("978", "0") => REGISTRANT_RANGES_ENGLISH_0,
+        ("978", "1") => REGISTRANT_RANGES_ENGLISH_1,
+        ("978", "4") => REGISTRANT_RANGES_JAPAN,
+        _ => return None,
+    };
+    let (group, rest) = body.split_at(1);
+    let head: u32 = rest[..7].parse().ok()?;
+    let (_, _, len) = ranges
+        .iter()
+        .find(|(min, max, _)| (*min..=*max).contains(&head))?;
+    let (registrant, publication) = rest.split_at(*len);
+    Some((group, registrant, publication))
+}
+
+// ISBN-13 のチェックディジットを求める
+// 先頭から奇数桁目に 1、偶数桁目に 3 の重みをかけた和から計算する
+fn isbn13_check_digit(digits: &[u32]) -> u32 {
+    let sum: u32 = digits
+        .iter()
+        .take(12)
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { *d } else { d * 3 })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+// ISBN-10 のチェックディジットが正しいかを検証する
+// 先頭から 10, 9, ..., 1 の重みをかけた和が 11 で割り切れれば正しい
+fn is_valid_isbn10(digits: &[u32]) -> bool {
+    let sum: u32 = digits.iter().zip((1..=10).rev()).map(|(d, w)| d * w).sum();
+    sum % 11 == 0
+}
+
+impl FromStr for Isbn {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || AppError::InvalidIsbn(s.to_string());
+        let normalized = s
+            .chars()
+            .filter(|c| *c != '-' && !c.is_whitespace())
+            .collect::<String>();
+        let len = normalized.chars().count();
+        // ISBN-10 のチェックディジットのみ X（10 を表す）を許容する
+        let digits = normalized
+            .chars()
+            .enumerate()
+            .map(|(i, c)| match c {
+                'X' | 'x' if len == 10 && i == 9 => Some(10),
+                c => c.to_digit(10),
+            })
+            .collect::<Option<Vec<u32>>>()
+            .ok_or_else(invalid)?;
+
+        match digits.len() {
+            10 if is_valid_isbn10(&digits) => {
+                let mut digits13 = vec![9, 7, 8];
+                digits13.extend_from_slice(&digits[..9]);
+                digits13.push(isbn13_check_digit(&digits13));
+                Ok(Self(
+                    digits13.iter().map(|d| d.to_string()).collect::<String>(),
+                ))
+            }
+            13 if (digits.starts_with(&[9, 7, 8]) || digits.starts_with(&[9, 7, 9]))
+                && isbn13_check_digit(&digits) == digits[12] =>
+            {
+                Ok(Self(normalized))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl TryFrom<String> for Isbn {
+    type Error = AppError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Isbn> for String {
+    fn from(isbn: Isbn) -> Self {
+        isbn.0
+    }
+}
+
+impl std::fmt::Display for Isbn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_isbn() -> anyhow::Result<()> {
+        // ハイフン・空白を取り除く
+        let isbn: Isbn = "978-4-06-536957-9".parse()?;
+        assert_eq!(isbn.as_str(), "9784065369579");
+        let isbn: Isbn = " 978 4798061702 ".parse()?;
+        assert_eq!(isbn.as_str(), "9784798061702");
+
+        // ISBN-10 は ISBN-13 に変換する
+        let isbn: Isbn = "4-06-536957-6".parse()?;
+        assert_eq!(isbn.as_str(), "9784065369579");
+        let isbn: Isbn = "080442957X".parse()?;
+        assert_eq!(isbn.as_str(), "9780804429573");
+
+        // チェックディジットや桁数が正しくないものはエラーとする
+        assert!("Test ISBN".parse::<Isbn>().is_err());
+        assert!("9784065369570".parse::<Isbn>().is_err());
+        assert!("4065369577".parse::<Isbn>().is_err());
+        assert!("97840653695".parse::<Isbn>().is_err());
+        assert!("1234567890128".parse::<Isbn>().is_err());
+        assert!("978406536957X".parse::<Isbn>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_hyphenated_isbn() -> anyhow::Result<()> {
+        let isbn: Isbn = "9784065369579".parse()?;
+        assert_eq!(isbn.hyphenated(), "978-4-06-536957-9");
+        let isbn: Isbn = "9784798061702".parse()?;
+        assert_eq!(isbn.hyphenated(), "978-4-7980-6170-2");
+        let isbn: Isbn = "9780804429573".parse()?;
+        assert_eq!(isbn.hyphenated(), "978-0-8044-2957-3");
+        // 範囲表を持たない登録グループ
+        let isbn: Isbn = "9791032305690".parse()?;
+        assert_eq!(isbn.hyphenated(), "979-103230569-0");
+        Ok(())
+    }
+
+    #[test]
+    fn test_stored_isbn() {
+        // 保存されている値も正規化できるものは正規化する
+        let isbn = Isbn::from_stored("4-06-536957-6".into());
+        assert_eq!(isbn.as_str(), "9784065369579");
+
+        // 正規化できない値はそのまま保持し、ハイフン区切りにもしない
+        let isbn = Isbn::from_stored("Test ISBN".into());
+        assert_eq!(isbn.as_str(), "Test ISBN");
+        assert_eq!(isbn.hyphenated(), "Test ISBN");
+        let isbn = Isbn::from_stored("123".into());
+        assert_eq!(isbn.hyphenated(), "123");
+    }
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+// 外部の書誌情報サービスなどから取得した、ISBN に対応する書誌情報
+// 提供元によっては取得できない項目があるため、いずれも Option で持つ
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookMetadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
+}
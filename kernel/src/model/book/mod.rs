@@ -1,35 +1,148 @@
 use crate::model::{
-    id::{BookId, CheckoutId},
+    id::{BookId, BranchId, CheckoutId, CopyId, ShelfId, UserId},
+    list::SortOrder,
+    location::BookLocation,
+    tag::Tag,
     user::{BookOwner, CheckoutUser},
 };
 use chrono::{DateTime, Utc};
+use cover::BookCover;
+use isbn::Isbn;
 
+pub mod cover;
 pub mod event;
+pub mod history;
+pub mod isbn;
+pub mod metadata;
 
 #[derive(Debug)]
 pub struct Book {
     pub id: BookId,
     pub title: String,
     pub author: String,
-    pub isbn: String,
+    pub isbn: Isbn,
     pub description: String,
     pub owner: BookOwner,
-    pub checkout: Option<Checkout>,
+    pub copies: BookCopyCounts,
+    // 所蔵資料ごとの貸出情報。貸出中の所蔵資料がなければ空になる
+    pub checkouts: Vec<Checkout>,
+    // 表紙画像が登録されていなければ None になる
+    pub cover: Option<BookCover>,
+    // 名前の順に並べる
+    pub tags: Vec<Tag>,
+    // 配架場所が設定されていなければ None になる
+    pub location: Option<BookLocation>,
+    // 楽観的排他制御に使うバージョン。蔵書の内容を変更するたびに増える
+    pub version: i64,
+}
+
+// 蔵書の所蔵資料の数
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BookCopyCounts {
+    pub total: i64,
+    // 貸出中でない所蔵資料の数
+    pub available: i64,
+}
+
+// 一括登録で、ISBN が重複していたため登録しなかった蔵書
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IsbnConflict {
+    // 一括登録に渡した蔵書の中での位置（0 始まり）
+    pub index: usize,
+    pub isbn: Isbn,
+    pub kind: IsbnConflictKind,
+}
+
+// ISBN が何と重複していたか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsbnConflictKind {
+    // 登録済みの蔵書
+    Registered,
+    // 一括登録する蔵書のうち、より前の位置にあるもの
+    Batch,
 }
 
 // ページネーションの範囲を指定するための設定値を格納する型
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct BookListOptions {
     pub limit: i64,
     pub offset: i64,
+    pub filter: BookListFilter,
+    pub sort: BookListSort,
+}
+
+// カーソル方式で蔵書一覧を取得する際の設定値を格納する型
+#[derive(Debug, Default)]
+pub struct BookCursorListOptions {
+    pub limit: i64,
+    pub cursor: Option<String>,
+    pub filter: BookListFilter,
+    pub sort: BookListSort,
+}
+
+// 蔵書一覧の絞り込み条件を格納する型
+// 値が None の条件は絞り込みに使用しない
+#[derive(Debug, Default, Clone)]
+pub struct BookListFilter {
+    // タイトル・著者名・ISBN・説明文のいずれかに部分一致するキーワード
+    pub keyword: Option<String>,
+    pub author: Option<String>,
+    pub isbn: Option<Isbn>,
+    pub owner: Option<UserId>,
+    // true ならすべての所蔵資料が貸出中の蔵書のみ、
+    // false なら貸出可能な所蔵資料がある蔵書のみ
+    pub checked_out: Option<bool>,
+    pub tags: Option<BookTagFilter>,
+    // 指定した館のいずれかの書架に配架されている蔵書のみ
+    pub branch: Option<BranchId>,
+    pub shelf: Option<ShelfId>,
+    // true なら削除済みの蔵書のみ、false なら削除済みでない蔵書のみ
+    pub deleted: bool,
+}
+
+// タグによる絞り込み条件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookTagFilter {
+    // タグの名前。空の場合は絞り込みに使用しない
+    pub names: Vec<String>,
+    pub mode: TagMatchMode,
+}
+
+// 指定したタグのうち、いずれかが付いている蔵書か、すべてが付いている蔵書かを指定する
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TagMatchMode {
+    #[default]
+    Any,
+    All,
+}
+
+// 蔵書一覧の並び替えに使うキー
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BookSortKey {
+    Title,
+    Author,
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    Isbn,
+}
+
+// 蔵書一覧の並び替え条件を格納する型
+// デフォルトは登録日時の新しい順
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BookListSort {
+    pub key: BookSortKey,
+    pub order: SortOrder,
 }
 
 // この型は、model::checkout モジュール側でも同名の型を定義しているが
 // それとは異なるモジュールにあるので別の型として扱われる。
-// 実際、上記 `Book` 型の checkout フィールドとしてのみ使用する。
+// 実際、上記 `Book` 型の checkouts フィールドと、所蔵資料の貸出情報としてのみ使用する。
 #[derive(Debug)]
 pub struct Checkout {
     pub checkout_id: CheckoutId,
+    pub copy_id: CopyId,
     pub checked_out_by: CheckoutUser,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
 }
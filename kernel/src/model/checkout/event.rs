@@ -1,18 +1,52 @@
-use crate::model::id::{BookId, CheckoutId, UserId};
+use crate::model::{
+    checkout::ReturnOutcome,
+    id::{BookId, BranchId, CheckoutId, CopyId, UserId},
+};
 use chrono::{DateTime, Utc};
 use derive_new::new;
 
 #[derive(new)]
 pub struct CreateCheckout {
     pub book_id: BookId,
+    // 指定がない場合は、貸出可能な所蔵資料のいずれかを貸し出す
+    pub copy_id: Option<CopyId>,
+    // 借りるユーザー
     pub checked_out_by: UserId,
     pub checked_out_at: DateTime<Utc>,
+    // 管理者が借りるユーザーに代わって貸し出す場合は、その管理者
+    // None の場合は借りるユーザー自身が手続きしたものとする
+    #[new(default)]
+    pub processed_by: Option<UserId>,
+    // 貸出の手続きをした館。記録しない場合は None
+    #[new(default)]
+    pub branch_id: Option<BranchId>,
 }
 
 #[derive(new)]
 pub struct UpdateReturned {
     pub checkout_id: CheckoutId,
     pub book_id: BookId,
+    // 返却の手続きをしたユーザー
     pub returned_by: UserId,
     pub returned_at: DateTime<Utc>,
+    // 指定がない場合は通常の返却（Ok）として扱う
+    #[new(default)]
+    pub outcome: ReturnOutcome,
+    #[new(default)]
+    pub note: Option<String>,
+    // 管理者が借りたユーザーに代わって返却する場合は true
+    // false の場合は、借りたユーザー自身しか返却できない
+    #[new(default)]
+    pub by_admin: bool,
+    // 返却の手続きをした館。記録しない場合は None
+    #[new(default)]
+    pub branch_id: Option<BranchId>,
+}
+
+#[derive(new)]
+pub struct RenewCheckout {
+    pub checkout_id: CheckoutId,
+    pub book_id: BookId,
+    pub renewed_by: UserId,
+    pub renewed_at: DateTime<Utc>,
 }
@@ -1,17 +1,57 @@
-use crate::model::id::{BookId, CheckoutId, UserId};
+use crate::model::id::{BookId, BranchId, CheckoutId, CopyId, UserId};
 use chrono::{DateTime, Utc};
+use strum::{AsRefStr, EnumIter, EnumString};
 
 pub mod event;
+pub mod policy;
 
 #[derive(Debug)]
 pub struct Checkout {
     pub id: CheckoutId,
+    pub copy_id: CopyId,
     pub checked_out_by: UserId,
     pub checked_out_at: DateTime<Utc>,
+    // 貸出の手続きをしたユーザー。管理者が代わりに貸し出した場合は checked_out_by と異なる
+    pub checkout_processed_by: UserId,
+    // 貸出の手続きをした館。記録していない場合は None
+    pub checkout_branch_id: Option<BranchId>,
+    // 返却期限。貸出日に貸出期間を足した日時
+    pub due_at: DateTime<Utc>,
+    // 貸出を延長した回数
+    pub renewal_count: i32,
     pub returned_at: Option<DateTime<Utc>>,
+    // 返却時の状態と、破損・紛失などについてのメモ。未返却の場合は None
+    pub return_outcome: Option<ReturnOutcome>,
+    pub return_note: Option<String>,
+    // 返却の手続きをしたユーザー。未返却の場合は None
+    pub return_processed_by: Option<UserId>,
+    // 返却の手続きをした館。未返却の場合や記録していない場合は None
+    pub return_branch_id: Option<BranchId>,
     pub book: CheckoutBook,
 }
 
+// 返却時の所蔵資料の状態
+// Damaged の場合は所蔵資料の状態を破損に、Lost の場合は紛失にして貸出対象から外す
+#[derive(Debug, EnumString, AsRefStr, EnumIter, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReturnOutcome {
+    #[default]
+    Ok,
+    Damaged,
+    Lost,
+}
+
+// ユーザーの貸出履歴をカーソル方式で取得する際の条件
+// checked_out_from / checked_out_to を指定した場合は、貸出日がその範囲内のものに絞り込む
+// 範囲は checked_out_from 以上、checked_out_to 未満とする
+// limit が None の場合はページ分割せず、全件を取得する
+#[derive(Debug, Default, Clone)]
+pub struct CheckoutHistoryOptions {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    pub checked_out_from: Option<DateTime<Utc>>,
+    pub checked_out_to: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug)]
 pub struct CheckoutBook {
     pub book_id: BookId,
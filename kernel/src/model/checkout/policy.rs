@@ -0,0 +1,98 @@
+use crate::model::role::Role;
+use shared::{
+    config::{CheckoutConfig, LoanPolicy},
+    error::LoanPolicyViolation,
+};
+
+// ユーザーのロールに応じた貸出ポリシーを返す
+pub fn loan_policy(config: &CheckoutConfig, role: &Role) -> LoanPolicy {
+    match role {
+        Role::Admin => config.admin,
+        Role::User => config.user,
+    }
+}
+
+// 新たに 1 冊借りられるかを判定する
+// loans は借りているユーザーの貸出中の冊数、overdue_loans はそのうち返却期限を過ぎた冊数
+pub fn check_checkout(
+    policy: &LoanPolicy,
+    loans: i64,
+    overdue_loans: i64,
+) -> Result<(), LoanPolicyViolation> {
+    if policy.overdue_blocks_checkout && overdue_loans > 0 {
+        return Err(LoanPolicyViolation::OverdueCheckouts {
+            count: overdue_loans,
+        });
+    }
+    if loans >= policy.max_loans {
+        return Err(LoanPolicyViolation::MaxLoansExceeded {
+            limit: policy.max_loans,
+        });
+    }
+    Ok(())
+}
+
+// 延長回数が renewal_count の貸出をさらに延長できるかを判定する
+pub fn check_renewal(policy: &LoanPolicy, renewal_count: i32) -> Result<(), LoanPolicyViolation> {
+    if renewal_count >= policy.max_renewals {
+        return Err(LoanPolicyViolation::MaxRenewalsExceeded {
+            limit: policy.max_renewals,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_checkout() {
+        let policy = LoanPolicy {
+            max_loans: 2,
+            ..Default::default()
+        };
+        assert_eq!(check_checkout(&policy, 1, 0), Ok(()));
+        assert_eq!(
+            check_checkout(&policy, 2, 0),
+            Err(LoanPolicyViolation::MaxLoansExceeded { limit: 2 })
+        );
+        assert_eq!(
+            check_checkout(&policy, 1, 1),
+            Err(LoanPolicyViolation::OverdueCheckouts { count: 1 })
+        );
+
+        // 返却期限を過ぎた貸出があっても借りられるポリシー
+        let policy = LoanPolicy {
+            overdue_blocks_checkout: false,
+            ..policy
+        };
+        assert_eq!(check_checkout(&policy, 1, 1), Ok(()));
+    }
+
+    #[test]
+    fn test_check_renewal() {
+        let policy = LoanPolicy {
+            max_renewals: 1,
+            ..Default::default()
+        };
+        assert_eq!(check_renewal(&policy, 0), Ok(()));
+        assert_eq!(
+            check_renewal(&policy, 1),
+            Err(LoanPolicyViolation::MaxRenewalsExceeded { limit: 1 })
+        );
+    }
+
+    #[test]
+    fn test_loan_policy_by_role() {
+        let config = CheckoutConfig {
+            admin: LoanPolicy {
+                max_loans: 20,
+                ..Default::default()
+            },
+            user: LoanPolicy::default(),
+        };
+        assert_eq!(loan_policy(&config, &Role::Admin).max_loans, 20);
+        assert_eq!(loan_policy(&config, &Role::User).max_loans, 5);
+    }
+}
@@ -0,0 +1,32 @@
+use crate::model::{
+    copy::CopyCondition,
+    id::{BookId, CopyId, UserId},
+};
+use chrono::NaiveDate;
+
+pub struct CreateCopy {
+    pub book_id: BookId,
+    pub barcode: Option<String>,
+    pub acquired_on: NaiveDate,
+    pub condition: CopyCondition,
+    pub shelf_location: Option<String>,
+    pub requested_user: UserId,
+}
+
+#[derive(Debug)]
+pub struct UpdateCopy {
+    pub copy_id: CopyId,
+    pub book_id: BookId,
+    pub barcode: Option<String>,
+    pub acquired_on: NaiveDate,
+    pub condition: CopyCondition,
+    pub shelf_location: Option<String>,
+    pub requested_user: UserId,
+}
+
+#[derive(Debug)]
+pub struct DeleteCopy {
+    pub copy_id: CopyId,
+    pub book_id: BookId,
+    pub requested_user: UserId,
+}
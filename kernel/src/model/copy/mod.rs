@@ -0,0 +1,35 @@
+use crate::model::{
+    book::Checkout,
+    id::{BookId, CopyId},
+};
+use chrono::NaiveDate;
+use strum::{AsRefStr, EnumIter, EnumString};
+
+pub mod event;
+
+// 蔵書（書誌情報）に属する、貸し出しの単位となる所蔵資料（物理的な 1 冊）
+#[derive(Debug)]
+pub struct BookCopy {
+    pub id: CopyId,
+    pub book_id: BookId,
+    // バーコードを貼付していない所蔵資料もあるため、任意とする
+    pub barcode: Option<String>,
+    pub acquired_on: NaiveDate,
+    pub condition: CopyCondition,
+    pub shelf_location: Option<String>,
+    // 貸出中の場合は貸出情報を持つ
+    pub checkout: Option<Checkout>,
+}
+
+// 所蔵資料の状態
+#[derive(Debug, EnumString, AsRefStr, EnumIter, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CopyCondition {
+    New,
+    #[default]
+    Good,
+    Fair,
+    Poor,
+    Damaged,
+    // 紛失。貸出の対象から外れる
+    Lost,
+}
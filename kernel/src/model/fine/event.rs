@@ -0,0 +1,28 @@
+use crate::model::{fine::FineEntryKind, id::UserId};
+use chrono::{DateTime, Utc};
+
+// 管理者が記録する、延滞料金の支払いまたは免除
+#[derive(Debug)]
+pub struct CreateFineCredit {
+    pub user_id: UserId,
+    pub kind: FineCreditKind,
+    pub amount: i64,
+    pub note: Option<String>,
+    pub recorded_by: UserId,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FineCreditKind {
+    Payment,
+    Waiver,
+}
+
+impl From<FineCreditKind> for FineEntryKind {
+    fn from(value: FineCreditKind) -> Self {
+        match value {
+            FineCreditKind::Payment => Self::Payment,
+            FineCreditKind::Waiver => Self::Waiver,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::model::id::{CheckoutId, FineEntryId, UserId};
+use chrono::{DateTime, Utc};
+use shared::config::FineConfig;
+use strum::{AsRefStr, EnumIter, EnumString};
+
+pub mod event;
+
+// 延滞料金の台帳の 1 行
+// 延滞料金の発生は Fine、紛失時の弁償金は Replacement、支払いは Payment、免除は Waiver として記録する
+// amount は種類によらず正の値で持ち、残高は Fine と Replacement の合計から Payment と Waiver の合計を引いて求める
+#[derive(Debug)]
+pub struct FineEntry {
+    pub id: FineEntryId,
+    pub user_id: UserId,
+    // 延滞料金の発生元の貸出。支払い・免除の場合は None
+    pub checkout_id: Option<CheckoutId>,
+    pub kind: FineEntryKind,
+    pub amount: i64,
+    pub note: Option<String>,
+    // 支払い・免除を記録した管理者。延滞料金の発生時は None
+    pub recorded_by: Option<UserId>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, EnumString, AsRefStr, EnumIter, Clone, Copy, PartialEq, Eq)]
+pub enum FineEntryKind {
+    Fine,
+    Replacement,
+    Payment,
+    Waiver,
+}
+
+// ユーザーの延滞料金の残高と、台帳の記録の一覧
+#[derive(Debug)]
+pub struct UserFines {
+    pub user_id: UserId,
+    pub balance: i64,
+    pub entries: Vec<FineEntry>,
+}
+
+// 返却期限を過ぎて返却された場合の延滞料金を求める
+// 1 日に満たない延滞も 1 日として数え、上限額を超える場合は上限額とする
+pub fn overdue_fine(config: &FineConfig, due_at: DateTime<Utc>, returned_at: DateTime<Utc>) -> i64 {
+    let overdue_seconds = (returned_at - due_at).num_seconds();
+    if overdue_seconds <= 0 {
+        return 0;
+    }
+    let overdue_days = (overdue_seconds + 86_399) / 86_400;
+    (overdue_days * config.daily_rate).min(config.cap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_overdue_fine() {
+        let config = FineConfig {
+            daily_rate: 10,
+            cap: 100,
+            ..Default::default()
+        };
+        let due_at = Utc::now();
+
+        // 返却期限までに返却した場合は延滞料金はかからない
+        assert_eq!(overdue_fine(&config, due_at, due_at), 0);
+        assert_eq!(overdue_fine(&config, due_at, due_at - Duration::days(1)), 0);
+        // 1 日に満たない延滞は 1 日として数える
+        assert_eq!(
+            overdue_fine(&config, due_at, due_at + Duration::minutes(1)),
+            10
+        );
+        assert_eq!(
+            overdue_fine(&config, due_at, due_at + Duration::days(3)),
+            30
+        );
+        // 上限額を超えない
+        assert_eq!(
+            overdue_fine(&config, due_at, due_at + Duration::days(30)),
+            100
+        );
+    }
+}
@@ -54,3 +54,10 @@ macro_rules! define_id {
 define_id!(UserId);
 define_id!(BookId);
 define_id!(CheckoutId);
+define_id!(CopyId);
+define_id!(ReservationId);
+define_id!(FineEntryId);
+define_id!(TagId);
+define_id!(BranchId);
+define_id!(ShelfId);
+define_id!(BookHistoryId);
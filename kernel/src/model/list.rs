@@ -11,3 +11,38 @@ impl<T> PaginatedList<T> {
         self.items
     }
 }
+
+// カーソル（キーセット）方式のページネーションの範囲を指定するための型
+// cursor が None の場合は先頭のページを取得する
+// limit が None の場合はページ分割せず、全件を取得する
+#[derive(Debug, Default, Clone)]
+pub struct CursorListOptions {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+// カーソル方式のページネーションの結果を格納する型
+// カーソルの中身は adapter 側で決まるため、kernel 以降では不透明な文字列として扱う
+#[derive(Debug)]
+pub struct CursorPaginatedList<T> {
+    pub limit: i64,
+    pub items: Vec<T>,
+    // 次のページを取得するためのカーソル。次のページがない場合は None
+    pub next_cursor: Option<String>,
+    // 前のページを取得するためのカーソル。前のページがない場合は None
+    pub prev_cursor: Option<String>,
+}
+
+impl<T> CursorPaginatedList<T> {
+    pub fn into_inner(self) -> Vec<T> {
+        self.items
+    }
+}
+
+// 一覧の並び順を表す型
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
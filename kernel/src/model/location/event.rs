@@ -0,0 +1,34 @@
+use crate::model::id::{BranchId, ShelfId};
+
+pub struct CreateBranch {
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub struct UpdateBranch {
+    pub branch_id: BranchId,
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub struct DeleteBranch {
+    pub branch_id: BranchId,
+}
+
+pub struct CreateShelf {
+    pub branch_id: BranchId,
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub struct UpdateShelf {
+    pub shelf_id: ShelfId,
+    pub branch_id: BranchId,
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub struct DeleteShelf {
+    pub shelf_id: ShelfId,
+    pub branch_id: BranchId,
+}
@@ -0,0 +1,27 @@
+use crate::model::id::{BranchId, ShelfId};
+
+pub mod event;
+
+// 閲覧室などの、蔵書を配架している館
+// 名前は館同士で重複しない
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Branch {
+    pub id: BranchId,
+    pub name: String,
+}
+
+// 館の中にある書架
+// 名前は同じ館の書架同士で重複しない
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shelf {
+    pub id: ShelfId,
+    pub branch_id: BranchId,
+    pub name: String,
+}
+
+// 蔵書の配架場所
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookLocation {
+    pub branch: Branch,
+    pub shelf: Shelf,
+}
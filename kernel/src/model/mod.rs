@@ -1,7 +1,13 @@
 pub mod auth;
 pub mod book;
 pub mod checkout;
+pub mod copy;
+pub mod fine;
 pub mod id;
 pub mod list;
+pub mod location;
+pub mod report;
+pub mod reservation;
 pub mod role;
+pub mod tag;
 pub mod user;
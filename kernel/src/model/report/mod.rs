@@ -0,0 +1,144 @@
+use crate::model::id::BookId;
+use chrono::{Datelike, Days, Months, NaiveDate};
+use strum::{AsRefStr, EnumIter, EnumString};
+
+// 統計を集計する期間の単位
+// 週は月曜日始まりとする
+#[derive(Debug, EnumString, AsRefStr, EnumIter, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReportGrouping {
+    Day,
+    Week,
+    #[default]
+    Month,
+}
+
+impl ReportGrouping {
+    // 指定した日付を含む期間の初日を返す
+    pub fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => date,
+            Self::Week => date - Days::new(date.weekday().num_days_from_monday() as u64),
+            Self::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    // 指定した期間の次の期間の初日を返す
+    pub fn next_period_start(&self, period_start: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => period_start + Days::new(1),
+            Self::Week => period_start + Days::new(7),
+            Self::Month => period_start + Months::new(1),
+        }
+    }
+
+    // from 以上 to 未満の範囲にかかる期間の初日を、古い順に返す
+    pub fn periods(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let mut periods = Vec::new();
+        let mut period = self.period_start(from);
+        while period < to {
+            periods.push(period);
+            period = self.next_period_start(period);
+        }
+        periods
+    }
+}
+
+// 統計を集計する範囲
+// from 以上 to 未満の日付（UTC）を対象とし、grouping の単位で集計する
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub grouping: ReportGrouping,
+}
+
+// 期間ごとの貸出・返却の統計
+#[derive(Debug, Clone, PartialEq)]
+pub struct CirculationStats {
+    pub period_start: NaiveDate,
+    // 期間内に貸し出された冊数
+    pub loans: i64,
+    // 期間内に返却された冊数
+    pub returns: i64,
+    // 期間内に 1 冊以上借りたユーザーの数
+    pub active_borrowers: i64,
+    // 期間内に返却された貸出のうち、返却期限を過ぎてから返却されたものの数
+    pub overdue_returns: i64,
+    // 期間の終わり（期間が終わっていない場合は現在）の時点で、返却期限を過ぎても返却されていない貸出の数
+    pub overdue_outstanding: i64,
+    // 期間内に返却された貸出の平均貸出日数。返却がない場合は None
+    pub average_loan_days: Option<f64>,
+}
+
+impl CirculationStats {
+    pub fn empty(period_start: NaiveDate) -> Self {
+        Self {
+            period_start,
+            loans: 0,
+            returns: 0,
+            active_borrowers: 0,
+            overdue_returns: 0,
+            overdue_outstanding: 0,
+            average_loan_days: None,
+        }
+    }
+
+    // 期間内に返却されたものと期間の終わりに延滞しているものを合わせたうち、延滞したものの割合
+    // 延滞したまま返却されていない貸出も数え、返却されたものだけで割合を求めないようにする
+    // どちらもない場合は None
+    pub fn overdue_rate(&self) -> Option<f64> {
+        let total = self.returns + self.overdue_outstanding;
+        (total > 0).then(|| (self.overdue_returns + self.overdue_outstanding) as f64 / total as f64)
+    }
+}
+
+// 期間ごとの、貸出回数の多い蔵書
+#[derive(Debug, Clone)]
+pub struct BookLoanCount {
+    pub period_start: NaiveDate,
+    pub book_id: BookId,
+    pub title: String,
+    pub author: String,
+    pub loans: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_periods() {
+        // 2024-01-03 は水曜日
+        assert_eq!(
+            ReportGrouping::Day.periods(date(2024, 1, 30), date(2024, 2, 2)),
+            vec![date(2024, 1, 30), date(2024, 1, 31), date(2024, 2, 1)]
+        );
+        assert_eq!(
+            ReportGrouping::Week.periods(date(2024, 1, 3), date(2024, 1, 16)),
+            vec![date(2024, 1, 1), date(2024, 1, 8), date(2024, 1, 15)]
+        );
+        assert_eq!(
+            ReportGrouping::Month.periods(date(2024, 11, 15), date(2025, 2, 1)),
+            vec![date(2024, 11, 1), date(2024, 12, 1), date(2025, 1, 1)]
+        );
+        assert!(ReportGrouping::Month
+            .periods(date(2024, 2, 1), date(2024, 2, 1))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_overdue_rate() {
+        let mut stats = CirculationStats::empty(date(2024, 1, 1));
+        assert_eq!(stats.overdue_rate(), None);
+        stats.returns = 4;
+        stats.overdue_returns = 1;
+        assert_eq!(stats.overdue_rate(), Some(0.25));
+        // 延滞したまま返却されていない貸出も延滞として数える
+        stats.overdue_outstanding = 4;
+        assert_eq!(stats.overdue_rate(), Some(0.625));
+    }
+}
@@ -0,0 +1,19 @@
+use crate::model::id::{BookId, ReservationId, UserId};
+use chrono::{DateTime, Utc};
+
+pub mod event;
+
+// 貸出中の蔵書に対する予約
+// 予約は申し込んだ順（FIFO）に並び、所蔵資料が返却されると先頭の予約から取り置きされる
+#[derive(Debug)]
+pub struct Reservation {
+    pub id: ReservationId,
+    pub book_id: BookId,
+    pub reserved_by: UserId,
+    pub reserved_at: DateTime<Utc>,
+    // 予約の順番。1 始まり
+    pub position: i64,
+    // 取り置き中の場合は、受け取りの期限を持つ
+    // 期限を過ぎると予約は取り消され、次の予約に取り置きが移る
+    pub ready_until: Option<DateTime<Utc>>,
+}
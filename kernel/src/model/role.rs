@@ -1,6 +1,6 @@
 use strum::{AsRefStr, EnumIter, EnumString};
 
-#[derive(Debug, EnumString, AsRefStr, EnumIter, Default, PartialEq, Eq)]
+#[derive(Debug, EnumString, AsRefStr, EnumIter, Default, Clone, Copy, PartialEq, Eq)]
 pub enum Role {
     Admin,
     #[default]
@@ -0,0 +1,16 @@
+use crate::model::id::TagId;
+
+pub struct CreateTag {
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub struct UpdateTag {
+    pub tag_id: TagId,
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub struct DeleteTag {
+    pub tag_id: TagId,
+}
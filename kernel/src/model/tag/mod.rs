@@ -0,0 +1,11 @@
+use crate::model::id::TagId;
+
+pub mod event;
+
+// 蔵書の分類に使うタグ（Rust・データベース・設計など）
+// 名前はタグ同士で重複しない
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub id: TagId,
+    pub name: String,
+}
@@ -11,6 +11,19 @@ pub struct CreateUser {
 pub struct UpdateUserRole {
     pub user_id: UserId,
     pub role: Role,
+    // 指定した場合は、ユーザーの現在のバージョンと一致しなければ更新しない
+    pub expected_version: Option<i64>,
+}
+
+// ユーザー情報の部分更新用のイベント
+// None の項目は現在の値のままにする
+#[derive(Debug)]
+pub struct PatchUser {
+    pub user_id: UserId,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    // 指定した場合は、ユーザーの現在のバージョンと一致しなければ更新しない
+    pub expected_version: Option<i64>,
 }
 
 #[derive(Debug)]
@@ -23,4 +36,6 @@ pub struct UpdateUserPassword {
 #[derive(Debug)]
 pub struct DeleteUser {
     pub user_id: UserId,
+    // 指定した場合は、ユーザーの現在のバージョンと一致しなければ削除しない
+    pub expected_version: Option<i64>,
 }
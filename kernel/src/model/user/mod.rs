@@ -8,6 +8,8 @@ pub struct User {
     pub name: String,
     pub email: String,
     pub role: Role,
+    // 楽観的排他制御に使うバージョン。ユーザー情報を変更するたびに増える
+    pub version: i64,
 }
 
 #[derive(Debug)]
@@ -3,19 +3,76 @@ use shared::error::AppResult;
 
 use crate::model::{
     book::{
-        event::{CreateBook, DeleteBook, UpdateBook},
-        Book, BookListOptions,
+        event::{
+            CreateBook, DeleteBook, PatchBook, PurgeBook, RestoreBook, UpdateBook, UpdateBookCover,
+            UpdateBookLocation, UpdateBookTags,
+        },
+        history::BookHistory,
+        isbn::Isbn,
+        Book, BookCursorListOptions, BookListOptions, IsbnConflict,
     },
     id::{BookId, UserId},
-    list::PaginatedList,
+    list::{CursorPaginatedList, PaginatedList},
 };
 
+// 蔵書の登録・更新（title・author・isbn・description の変更）・削除・復元は、
+// 同じトランザクションの中で変更履歴を記録する
 #[mockall::automock]
 #[async_trait]
 pub trait BookRepository: Send + Sync {
     async fn create(&self, event: CreateBook, user_id: UserId) -> AppResult<()>;
+    // 複数の蔵書を 1 つのトランザクションでまとめて登録する
+    // ISBN が登録済みの蔵書や一括登録する他の蔵書と重複するものは登録せず、その一覧を返す
+    // それ以外の理由で 1 件でも登録できないものがあれば、いずれの蔵書も登録しない
+    async fn create_many(
+        &self,
+        events: Vec<CreateBook>,
+        user_id: UserId,
+    ) -> AppResult<Vec<IsbnConflict>>;
+    // create_many で ISBN の重複により登録されない蔵書を、登録せずに調べる
+    async fn find_isbn_conflicts(
+        &self,
+        isbns: &[Isbn],
+        user_id: UserId,
+    ) -> AppResult<Vec<IsbnConflict>>;
     async fn find_all(&self, options: BookListOptions) -> AppResult<PaginatedList<Book>>;
+    async fn find_all_by_cursor(
+        &self,
+        options: BookCursorListOptions,
+    ) -> AppResult<CursorPaginatedList<Book>>;
     async fn find_by_id(&self, book_id: BookId) -> AppResult<Option<Book>>;
+    // 蔵書の変更履歴を、変更日時の新しい順に取得する
+    // 削除済みの蔵書や完全に削除された蔵書の履歴も取得できる
+    // 蔵書も変更履歴も存在しない場合は None を返す
+    async fn find_history(&self, book_id: BookId) -> AppResult<Option<BookHistory>>;
+    // expected_version が現在のバージョンと一致しない場合はエラーを返し、更新しない
     async fn update(&self, event: UpdateBook) -> AppResult<()>;
+    // 指定された項目だけを更新し、それ以外の項目は現在の値のままにする
+    // expected_version が現在のバージョンと一致しない場合はエラーを返し、更新しない
+    async fn patch(&self, event: PatchBook) -> AppResult<()>;
+    // 表紙画像の形式と更新日時を記録する
+    // 蔵書の所有者か管理者でなければ、蔵書が見つからないものとしてエラーを返す
+    // 表紙画像の差し替えは、変更前後の形式とともに変更履歴に記録する
+    async fn update_cover(&self, event: UpdateBookCover) -> AppResult<()>;
+    // 蔵書に付けるタグを置き換える
+    // 蔵書の所有者か管理者でなければ、蔵書が見つからないものとしてエラーを返す
+    // 存在しないタグが含まれている場合はエラーを返し、タグを変更しない
+    // タグの組み合わせが変わった場合は、変更前後のタグとともに変更履歴に記録する
+    async fn update_tags(&self, event: UpdateBookTags) -> AppResult<()>;
+    // 蔵書の配架場所を変更する
+    // 蔵書の所有者か管理者でなければ、蔵書が見つからないものとしてエラーを返す
+    // 存在しない書架が指定された場合はエラーを返す
+    // 配架場所が変わった場合は、変更前後の書架とともに変更履歴に記録する
+    async fn update_location(&self, event: UpdateBookLocation) -> AppResult<()>;
+    // 蔵書を削除済みにする。削除済みの蔵書は、削除済みの蔵書の一覧以外では見つからないものとして扱う
+    // 貸出中の所蔵資料があっても削除済みにでき、貸出はそのまま返却できる
+    // expected_version が現在のバージョンと一致しない場合はエラーを返し、削除しない
     async fn delete(&self, event: DeleteBook) -> AppResult<()>;
+    // 削除済みの蔵書を元に戻す。削除済みでない蔵書が指定された場合はエラーを返す
+    async fn restore(&self, event: RestoreBook) -> AppResult<()>;
+    // 削除済みの蔵書を完全に削除する
+    // 削除済みでない場合や貸出中の所蔵資料がある場合はエラーを返し、何も削除しない
+    // 返却済みの貸出履歴は、削除した時点の蔵書の情報とともに残す
+    // 変更履歴も残し、削除した時点の値を完全な削除として記録する
+    async fn purge(&self, event: PurgeBook) -> AppResult<()>;
 }
@@ -0,0 +1,11 @@
+use crate::model::book::{isbn::Isbn, metadata::BookMetadata};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait BookMetadataProvider: Send + Sync {
+    // ISBN に対応する書誌情報を取得する
+    // 提供元に該当する書誌情報がない場合は None を返す
+    async fn find_by_isbn(&self, isbn: &Isbn) -> AppResult<Option<BookMetadata>>;
+}
@@ -1,11 +1,13 @@
 use crate::model::{
     checkout::{
-        event::{CreateCheckout, UpdateReturned},
-        Checkout,
+        event::{CreateCheckout, RenewCheckout, UpdateReturned},
+        Checkout, CheckoutHistoryOptions,
     },
     id::{BookId, UserId},
+    list::{CursorListOptions, CursorPaginatedList},
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use shared::error::AppResult;
 
 #[mockall::automock]
@@ -15,10 +17,32 @@ pub trait CheckoutRepository: Send + Sync {
     async fn create(&self, event: CreateCheckout) -> AppResult<()>;
     // 返却操作を行う
     async fn update_returned(&self, event: UpdateReturned) -> AppResult<()>;
-    // すべての未返却の貸出情報を取得する
-    async fn find_unreturned_all(&self) -> AppResult<Vec<Checkout>>;
+    // 貸出の延長操作を行う
+    async fn renew(&self, event: RenewCheckout) -> AppResult<()>;
+    // すべての未返却の貸出情報を、貸出日の古い順にカーソル方式で取得する
+    async fn find_unreturned_all(
+        &self,
+        options: CursorListOptions,
+    ) -> AppResult<CursorPaginatedList<Checkout>>;
+    // 指定日時の時点で返却期限を過ぎている未返却の貸出情報を、
+    // 貸出日の古い順にカーソル方式で取得する
+    async fn find_overdue_all(
+        &self,
+        now: DateTime<Utc>,
+        options: CursorListOptions,
+    ) -> AppResult<CursorPaginatedList<Checkout>>;
     // ユーザー ID に紐づく未返却の貸出情報を取得する
     async fn find_unreturned_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Checkout>>;
-    // 蔵書の貸し出し履歴（返却済みも含む）を取得する
-    async fn find_history_by_book_id(&self, book_id: BookId) -> AppResult<Vec<Checkout>>;
+    // 蔵書の貸し出し履歴（返却済みも含む）を、貸出日の新しい順にカーソル方式で取得する
+    async fn find_history_by_book_id(
+        &self,
+        book_id: BookId,
+        options: CursorListOptions,
+    ) -> AppResult<CursorPaginatedList<Checkout>>;
+    // ユーザーの貸し出し履歴（返却済みも含む）を、貸出日の新しい順にカーソル方式で取得する
+    async fn find_history_by_user_id(
+        &self,
+        user_id: UserId,
+        options: CheckoutHistoryOptions,
+    ) -> AppResult<CursorPaginatedList<Checkout>>;
 }
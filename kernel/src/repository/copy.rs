@@ -0,0 +1,20 @@
+use crate::model::{
+    copy::{
+        event::{CreateCopy, DeleteCopy, UpdateCopy},
+        BookCopy,
+    },
+    id::BookId,
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait BookCopyRepository: Send + Sync {
+    // 蔵書に所蔵資料を追加する
+    async fn create(&self, event: CreateCopy) -> AppResult<()>;
+    // 蔵書に属する所蔵資料を、受け入れ日の古い順に取得する
+    async fn find_by_book_id(&self, book_id: BookId) -> AppResult<Vec<BookCopy>>;
+    async fn update(&self, event: UpdateCopy) -> AppResult<()>;
+    async fn delete(&self, event: DeleteCopy) -> AppResult<()>;
+}
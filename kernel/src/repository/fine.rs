@@ -0,0 +1,15 @@
+use crate::model::{
+    fine::{event::CreateFineCredit, UserFines},
+    id::UserId,
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait FineRepository: Send + Sync {
+    // ユーザーの延滞料金の残高と、台帳の記録を新しい順に取得する
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<UserFines>;
+    // 延滞料金の支払いまたは免除を記録する
+    async fn create_credit(&self, event: CreateFineCredit) -> AppResult<()>;
+}
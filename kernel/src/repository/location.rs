@@ -0,0 +1,28 @@
+use crate::model::{
+    id::BranchId,
+    location::{
+        event::{CreateBranch, CreateShelf, DeleteBranch, DeleteShelf, UpdateBranch, UpdateShelf},
+        Branch, Shelf,
+    },
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait LocationRepository: Send + Sync {
+    // 同じ名前の館がすでにあればエラーを返す
+    async fn create_branch(&self, event: CreateBranch) -> AppResult<Branch>;
+    // 館を名前の順に取得する
+    async fn find_branches(&self) -> AppResult<Vec<Branch>>;
+    async fn update_branch(&self, event: UpdateBranch) -> AppResult<()>;
+    // 館を削除すると、その館の書架もあわせて削除し、配架されていた蔵書の配架場所は未設定に戻す
+    async fn delete_branch(&self, event: DeleteBranch) -> AppResult<()>;
+    // 同じ館に同じ名前の書架がすでにあればエラーを返す
+    async fn create_shelf(&self, event: CreateShelf) -> AppResult<Shelf>;
+    // 館の書架を名前の順に取得する
+    async fn find_shelves_by_branch_id(&self, branch_id: BranchId) -> AppResult<Vec<Shelf>>;
+    async fn update_shelf(&self, event: UpdateShelf) -> AppResult<()>;
+    // 書架を削除すると、配架されていた蔵書の配架場所は未設定に戻す
+    async fn delete_shelf(&self, event: DeleteShelf) -> AppResult<()>;
+}
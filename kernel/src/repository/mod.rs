@@ -1,5 +1,13 @@
 pub mod auth;
 pub mod book;
+pub mod book_metadata;
 pub mod checkout;
+pub mod copy;
+pub mod fine;
 pub mod health;
+pub mod location;
+pub mod report;
+pub mod reservation;
+pub mod storage;
+pub mod tag;
 pub mod user;
@@ -0,0 +1,20 @@
+use crate::model::report::{BookLoanCount, CirculationStats, ReportOptions};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait ReportRepository: Send + Sync {
+    // 期間ごとの貸出・返却の統計を、期間の古い順に取得する
+    // 貸出・返却のない期間も含める
+    async fn find_circulation_stats(
+        &self,
+        options: ReportOptions,
+    ) -> AppResult<Vec<CirculationStats>>;
+    // 期間ごとに、貸出回数の多い蔵書を最大 limit 件ずつ取得する
+    async fn find_most_borrowed(
+        &self,
+        options: ReportOptions,
+        limit: i64,
+    ) -> AppResult<Vec<BookLoanCount>>;
+}
@@ -0,0 +1,20 @@
+use crate::model::{
+    id::BookId,
+    reservation::{
+        event::{CreateReservation, DeleteReservation},
+        Reservation,
+    },
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait ReservationRepository: Send + Sync {
+    // 蔵書を予約する
+    async fn create(&self, event: CreateReservation) -> AppResult<()>;
+    // 蔵書に対する予約を、予約の順番に取得する
+    async fn find_by_book_id(&self, book_id: BookId) -> AppResult<Vec<Reservation>>;
+    // 予約を取り消す
+    async fn delete(&self, event: DeleteReservation) -> AppResult<()>;
+}
@@ -0,0 +1,15 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+// 表紙画像などのファイルを保存するオブジェクトストレージ
+// キーは / 区切りのパスの形式とする（例: covers/{book_id}/original）
+#[mockall::automock]
+#[async_trait]
+pub trait ObjectStorage: Send + Sync {
+    // key の位置にオブジェクトを保存する。すでにある場合は上書きする
+    async fn put(&self, key: &str, body: Vec<u8>, content_type: &str) -> AppResult<()>;
+    // key の位置のオブジェクトを取得する。存在しない場合は None を返す
+    async fn get(&self, key: &str) -> AppResult<Option<Vec<u8>>>;
+    // key の位置のオブジェクトを削除する。存在しない場合も成功とする
+    async fn delete(&self, key: &str) -> AppResult<()>;
+}
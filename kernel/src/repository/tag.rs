@@ -0,0 +1,22 @@
+use crate::model::{
+    id::TagId,
+    tag::{
+        event::{CreateTag, DeleteTag, UpdateTag},
+        Tag,
+    },
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait TagRepository: Send + Sync {
+    // 同じ名前のタグがすでにあればエラーを返す
+    async fn create(&self, event: CreateTag) -> AppResult<Tag>;
+    // タグを名前の順に取得する
+    async fn find_all(&self) -> AppResult<Vec<Tag>>;
+    async fn find_by_id(&self, tag_id: TagId) -> AppResult<Option<Tag>>;
+    async fn update(&self, event: UpdateTag) -> AppResult<()>;
+    // タグを削除すると、蔵書に付けられていたタグもあわせて外れる
+    async fn delete(&self, event: DeleteTag) -> AppResult<()>;
+}
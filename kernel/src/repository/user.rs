@@ -1,7 +1,7 @@
 use crate::model::{
     id::UserId,
     user::{
-        event::{CreateUser, DeleteUser, UpdateUserPassword, UpdateUserRole},
+        event::{CreateUser, DeleteUser, PatchUser, UpdateUserPassword, UpdateUserRole},
         User,
     },
 };
@@ -15,6 +15,9 @@ pub trait UserRepository: Send + Sync {
     async fn find_all(&self) -> AppResult<Vec<User>>;
     async fn create(&self, event: CreateUser) -> AppResult<User>;
     async fn update_password(&self, event: UpdateUserPassword) -> AppResult<()>;
+    // expected_version が現在のバージョンと一致しない場合はエラーを返し、更新・削除しない
     async fn update_role(&self, event: UpdateUserRole) -> AppResult<()>;
+    // 指定された項目だけを更新する。メールアドレスが他のユーザーと重複する場合はエラーを返す
+    async fn patch(&self, event: PatchUser) -> AppResult<()>;
     async fn delete(&self, event: DeleteUser) -> AppResult<()>;
 }
@@ -1,17 +1,37 @@
 use std::sync::Arc;
 
+use adapter::metadata::{
+    cache::CachedBookMetadataProvider, file::FileBookMetadataProvider,
+    open_library::OpenLibraryBookMetadataProvider,
+};
 use adapter::redis::RedisClient;
 use adapter::repository::auth::AuthRepositoryImpl;
 use adapter::repository::book::BookRepositoryImpl;
 use adapter::repository::checkout::CheckoutRepositoryImpl;
+use adapter::repository::copy::BookCopyRepositoryImpl;
+use adapter::repository::fine::FineRepositoryImpl;
+use adapter::repository::location::LocationRepositoryImpl;
+use adapter::repository::report::ReportRepositoryImpl;
+use adapter::repository::reservation::ReservationRepositoryImpl;
+use adapter::repository::tag::TagRepositoryImpl;
 use adapter::repository::user::UserRepositoryImpl;
+use adapter::storage::{local::LocalObjectStorage, s3::S3ObjectStorage};
 use adapter::{database::ConnectionPool, repository::health::HealthCheckRepositoryImpl};
 use kernel::repository::auth::AuthRepository;
 use kernel::repository::book::BookRepository;
+use kernel::repository::book_metadata::BookMetadataProvider;
 use kernel::repository::checkout::CheckoutRepository;
+use kernel::repository::copy::BookCopyRepository;
+use kernel::repository::fine::FineRepository;
 use kernel::repository::health::HealthCheckRepository;
+use kernel::repository::location::LocationRepository;
+use kernel::repository::report::ReportRepository;
+use kernel::repository::reservation::ReservationRepository;
+use kernel::repository::storage::ObjectStorage;
+use kernel::repository::tag::TagRepository;
 use kernel::repository::user::UserRepository;
-use shared::config::AppConfig;
+use shared::config::{AppConfig, BookMetadataSource, StorageConfig};
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct AppRegistryImpl {
@@ -20,6 +40,14 @@ pub struct AppRegistryImpl {
     auth_repository: Arc<dyn AuthRepository>,
     user_repository: Arc<dyn UserRepository>,
     checkout_repository: Arc<dyn CheckoutRepository>,
+    book_copy_repository: Arc<dyn BookCopyRepository>,
+    reservation_repository: Arc<dyn ReservationRepository>,
+    fine_repository: Arc<dyn FineRepository>,
+    report_repository: Arc<dyn ReportRepository>,
+    book_metadata_provider: Arc<dyn BookMetadataProvider>,
+    object_storage: Arc<dyn ObjectStorage>,
+    tag_repository: Arc<dyn TagRepository>,
+    location_repository: Arc<dyn LocationRepository>,
 }
 
 impl AppRegistryImpl {
@@ -29,14 +57,56 @@ impl AppRegistryImpl {
         app_config: AppConfig,
     ) -> Self {
         let health_check_repository = Arc::new(HealthCheckRepositoryImpl::new(pool.clone()));
-        let book_repository = Arc::new(BookRepositoryImpl::new(pool.clone()));
+        let book_repository = Arc::new(BookRepositoryImpl::new(
+            pool.clone(),
+            app_config.book.isbn_uniqueness,
+        ));
         let auth_repository = Arc::new(AuthRepositoryImpl::new(
             pool.clone(),
             redis_client.clone(),
             app_config.auth.ttl,
         ));
         let user_repository = Arc::new(UserRepositoryImpl::new(pool.clone()));
-        let checkout_repository = Arc::new(CheckoutRepositoryImpl::new(pool.clone()));
+        let checkout_repository = Arc::new(CheckoutRepositoryImpl::new(
+            pool.clone(),
+            app_config.checkout,
+            app_config.reservation,
+            app_config.fine,
+        ));
+        let book_copy_repository = Arc::new(BookCopyRepositoryImpl::new(pool.clone()));
+        let reservation_repository = Arc::new(ReservationRepositoryImpl::new(
+            pool.clone(),
+            app_config.reservation,
+        ));
+        let fine_repository = Arc::new(FineRepositoryImpl::new(pool.clone()));
+        let report_repository = Arc::new(ReportRepositoryImpl::new(pool.clone()));
+        let metadata_config = app_config.book_metadata;
+        let book_metadata_provider: Arc<dyn BookMetadataProvider> = match metadata_config.source {
+            BookMetadataSource::OpenLibrary { base_url } => {
+                Arc::new(OpenLibraryBookMetadataProvider::new(
+                    base_url,
+                    Duration::from_secs(metadata_config.timeout_seconds),
+                ))
+            }
+            BookMetadataSource::File { path } => Arc::new(FileBookMetadataProvider::new(path)),
+        };
+        // TTL が 0 の場合はキャッシュせず、毎回取得元から検索する
+        let book_metadata_provider: Arc<dyn BookMetadataProvider> = if metadata_config.cache_ttl > 0
+        {
+            Arc::new(CachedBookMetadataProvider::new(
+                book_metadata_provider,
+                redis_client.clone(),
+                metadata_config.cache_ttl,
+            ))
+        } else {
+            book_metadata_provider
+        };
+        let object_storage: Arc<dyn ObjectStorage> = match app_config.storage {
+            StorageConfig::Local { root } => Arc::new(LocalObjectStorage::new(root)),
+            StorageConfig::S3(config) => Arc::new(S3ObjectStorage::new(config)),
+        };
+        let tag_repository = Arc::new(TagRepositoryImpl::new(pool.clone()));
+        let location_repository = Arc::new(LocationRepositoryImpl::new(pool.clone()));
 
         Self {
             health_check_repository,
@@ -44,6 +114,14 @@ impl AppRegistryImpl {
             auth_repository,
             user_repository,
             checkout_repository,
+            book_copy_repository,
+            reservation_repository,
+            fine_repository,
+            report_repository,
+            book_metadata_provider,
+            object_storage,
+            tag_repository,
+            location_repository,
         }
     }
 
@@ -66,6 +144,38 @@ impl AppRegistryImpl {
     pub fn checkout_repository(&self) -> Arc<dyn CheckoutRepository> {
         self.checkout_repository.clone()
     }
+
+    pub fn book_copy_repository(&self) -> Arc<dyn BookCopyRepository> {
+        self.book_copy_repository.clone()
+    }
+
+    pub fn reservation_repository(&self) -> Arc<dyn ReservationRepository> {
+        self.reservation_repository.clone()
+    }
+
+    pub fn fine_repository(&self) -> Arc<dyn FineRepository> {
+        self.fine_repository.clone()
+    }
+
+    pub fn report_repository(&self) -> Arc<dyn ReportRepository> {
+        self.report_repository.clone()
+    }
+
+    pub fn book_metadata_provider(&self) -> Arc<dyn BookMetadataProvider> {
+        self.book_metadata_provider.clone()
+    }
+
+    pub fn object_storage(&self) -> Arc<dyn ObjectStorage> {
+        self.object_storage.clone()
+    }
+
+    pub fn tag_repository(&self) -> Arc<dyn TagRepository> {
+        self.tag_repository.clone()
+    }
+
+    pub fn location_repository(&self) -> Arc<dyn LocationRepository> {
+        self.location_repository.clone()
+    }
 }
 
 #[mockall::automock]
@@ -75,6 +185,14 @@ pub trait AppRegistryExt {
     fn auth_repository(&self) -> Arc<dyn AuthRepository>;
     fn checkout_repository(&self) -> Arc<dyn CheckoutRepository>;
     fn user_repository(&self) -> Arc<dyn UserRepository>;
+    fn book_copy_repository(&self) -> Arc<dyn BookCopyRepository>;
+    fn reservation_repository(&self) -> Arc<dyn ReservationRepository>;
+    fn fine_repository(&self) -> Arc<dyn FineRepository>;
+    fn report_repository(&self) -> Arc<dyn ReportRepository>;
+    fn book_metadata_provider(&self) -> Arc<dyn BookMetadataProvider>;
+    fn object_storage(&self) -> Arc<dyn ObjectStorage>;
+    fn tag_repository(&self) -> Arc<dyn TagRepository>;
+    fn location_repository(&self) -> Arc<dyn LocationRepository>;
 }
 
 impl AppRegistryExt for AppRegistryImpl {
@@ -97,6 +215,38 @@ impl AppRegistryExt for AppRegistryImpl {
     fn checkout_repository(&self) -> Arc<dyn CheckoutRepository> {
         self.checkout_repository.clone()
     }
+
+    fn book_copy_repository(&self) -> Arc<dyn BookCopyRepository> {
+        self.book_copy_repository.clone()
+    }
+
+    fn reservation_repository(&self) -> Arc<dyn ReservationRepository> {
+        self.reservation_repository.clone()
+    }
+
+    fn fine_repository(&self) -> Arc<dyn FineRepository> {
+        self.fine_repository.clone()
+    }
+
+    fn report_repository(&self) -> Arc<dyn ReportRepository> {
+        self.report_repository.clone()
+    }
+
+    fn book_metadata_provider(&self) -> Arc<dyn BookMetadataProvider> {
+        self.book_metadata_provider.clone()
+    }
+
+    fn object_storage(&self) -> Arc<dyn ObjectStorage> {
+        self.object_storage.clone()
+    }
+
+    fn tag_repository(&self) -> Arc<dyn TagRepository> {
+        self.tag_repository.clone()
+    }
+
+    fn location_repository(&self) -> Arc<dyn LocationRepository> {
+        self.location_repository.clone()
+    }
 }
 
 pub type AppRegistry = Arc<dyn AppRegistryExt + Send + Sync + 'static>;
@@ -1,9 +1,16 @@
 use anyhow::Result;
+use std::{path::PathBuf, str::FromStr};
 
 pub struct AppConfig {
     pub database: DatabaseConfig,
     pub redis: RedisConfig,
     pub auth: AuthConfig,
+    pub book: BookConfig,
+    pub checkout: CheckoutConfig,
+    pub reservation: ReservationConfig,
+    pub fine: FineConfig,
+    pub book_metadata: BookMetadataConfig,
+    pub storage: StorageConfig,
 }
 
 impl AppConfig {
@@ -23,10 +30,74 @@ impl AppConfig {
         let auth = AuthConfig {
             ttl: std::env::var("AUTH_TOKEN_TTL")?.parse::<u64>()?,
         };
+        // 未設定の場合は ISBN の重複を許容する
+        let book = BookConfig {
+            isbn_uniqueness: std::env::var("BOOK_ISBN_UNIQUENESS")
+                .ok()
+                .map(|v| v.parse::<IsbnUniqueness>())
+                .transpose()?
+                .unwrap_or_default(),
+        };
+        // ロールごとの貸出ポリシーは CHECKOUT_{ADMIN,USER}_* で指定する
+        // 未設定の項目は、全ロール共通の CHECKOUT_* の値か既定値を使う
+        let base = LoanPolicy::from_env("CHECKOUT", LoanPolicy::default())?;
+        let checkout = CheckoutConfig {
+            admin: LoanPolicy::from_env("CHECKOUT_ADMIN", base)?,
+            user: LoanPolicy::from_env("CHECKOUT_USER", base)?,
+        };
+        // 未設定の場合は 72 時間を取り置きの期間とする
+        let reservation = ReservationConfig {
+            pickup_window_hours: std::env::var("RESERVATION_PICKUP_WINDOW_HOURS")
+                .ok()
+                .map(|v| v.parse::<i64>())
+                .transpose()?
+                .unwrap_or(DEFAULT_PICKUP_WINDOW_HOURS),
+        };
+        anyhow::ensure!(
+            reservation.pickup_window_hours > 0,
+            "RESERVATION_PICKUP_WINDOW_HOURS must be a positive number"
+        );
+        // 既定では 1 日 10 円、1 件あたり 500 円を上限とし、
+        // 未払いの残高が 1000 円を超えると新たに借りられない
+        // 紛失時の弁償金は 3000 円とする
+        let default = FineConfig::default();
+        let fine = FineConfig {
+            daily_rate: env_or("FINE_DAILY_RATE", default.daily_rate)?,
+            cap: env_or("FINE_CAP", default.cap)?,
+            max_outstanding_balance: env_or(
+                "FINE_MAX_OUTSTANDING_BALANCE",
+                default.max_outstanding_balance,
+            )?,
+            replacement_charge: env_or("FINE_REPLACEMENT_CHARGE", default.replacement_charge)?,
+        };
+        anyhow::ensure!(
+            fine.daily_rate >= 0
+                && fine.cap >= 0
+                && fine.max_outstanding_balance >= 0
+                && fine.replacement_charge >= 0,
+            "FINE_* values must not be negative"
+        );
+        // 既定では Open Library から書誌情報を取得し、1 日の間 Redis にキャッシュする
+        let book_metadata = BookMetadataConfig {
+            source: BookMetadataSource::from_env()?,
+            timeout_seconds: env_or(
+                "BOOK_METADATA_TIMEOUT_SECONDS",
+                DEFAULT_METADATA_TIMEOUT_SECONDS,
+            )?,
+            cache_ttl: env_or("BOOK_METADATA_CACHE_TTL", DEFAULT_METADATA_CACHE_TTL)?,
+        };
+        // 既定ではローカルの storage ディレクトリに保存する
+        let storage = StorageConfig::from_env()?;
         Ok(Self {
             database,
             redis,
             auth,
+            book,
+            checkout,
+            reservation,
+            fine,
+            book_metadata,
+            storage,
         })
     }
 }
@@ -47,3 +118,240 @@ pub struct RedisConfig {
 pub struct AuthConfig {
     pub ttl: u64,
 }
+
+pub struct BookConfig {
+    pub isbn_uniqueness: IsbnUniqueness,
+}
+
+// 蔵書の ISBN の重複をどの範囲で禁止するか
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IsbnUniqueness {
+    // 重複を許容する
+    #[default]
+    Disabled,
+    // 同じ所有者の蔵書の間で重複を禁止する
+    PerOwner,
+    // すべての蔵書の間で重複を禁止する
+    Global,
+}
+
+impl FromStr for IsbnUniqueness {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::Disabled),
+            "owner" => Ok(Self::PerOwner),
+            "global" => Ok(Self::Global),
+            _ => anyhow::bail!("BOOK_ISBN_UNIQUENESS must be one of none, owner, global: {s}"),
+        }
+    }
+}
+
+const DEFAULT_METADATA_TIMEOUT_SECONDS: u64 = 5;
+const DEFAULT_METADATA_CACHE_TTL: u64 = 60 * 60 * 24;
+const DEFAULT_OPEN_LIBRARY_URL: &str = "https://openlibrary.org";
+
+// ISBN から書誌情報を検索する際の設定
+pub struct BookMetadataConfig {
+    pub source: BookMetadataSource,
+    // 書誌情報サービスへのリクエストのタイムアウト秒数
+    pub timeout_seconds: u64,
+    // 検索結果を Redis にキャッシュする秒数。0 の場合はキャッシュしない
+    pub cache_ttl: u64,
+}
+
+// 書誌情報の取得元
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BookMetadataSource {
+    // Open Library 形式の HTTP API から取得する
+    OpenLibrary { base_url: String },
+    // ISBN をキーとした JSON ファイルから取得する
+    File { path: PathBuf },
+}
+
+impl BookMetadataSource {
+    // BOOK_METADATA_PROVIDER が openlibrary（既定）の場合は BOOK_METADATA_BASE_URL を、
+    // file の場合は BOOK_METADATA_FILE を読み込む
+    fn from_env() -> Result<Self> {
+        let provider = std::env::var("BOOK_METADATA_PROVIDER").unwrap_or_default();
+        match provider.as_str() {
+            "" | "openlibrary" => Ok(Self::OpenLibrary {
+                base_url: env_or(
+                    "BOOK_METADATA_BASE_URL",
+                    DEFAULT_OPEN_LIBRARY_URL.to_string(),
+                )?,
+            }),
+            "file" => Ok(Self::File {
+                path: std::env::var("BOOK_METADATA_FILE")
+                    .ok()
+                    .filter(|v| !v.is_empty())
+                    .ok_or_else(|| anyhow::anyhow!("BOOK_METADATA_FILE must be set"))?
+                    .into(),
+            }),
+            _ => {
+                anyhow::bail!("BOOK_METADATA_PROVIDER must be one of openlibrary, file: {provider}")
+            }
+        }
+    }
+}
+
+const DEFAULT_STORAGE_LOCAL_ROOT: &str = "storage";
+const DEFAULT_STORAGE_S3_REGION: &str = "us-east-1";
+
+// 表紙画像などのファイルを保存するオブジェクトストレージの設定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageConfig {
+    // ローカルのファイルシステムのディレクトリに保存する
+    Local { root: PathBuf },
+    // S3 互換のオブジェクトストレージ（AWS S3 や MinIO など）に保存する
+    S3(S3Config),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Config {
+    // 例: https://s3.ap-northeast-1.amazonaws.com、http://localhost:9000
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    pub access_key: String,
+    pub secret_key: String,
+}
+
+impl StorageConfig {
+    // STORAGE_BACKEND が local（既定）の場合は STORAGE_LOCAL_ROOT を、
+    // s3 の場合は STORAGE_S3_* を読み込む
+    fn from_env() -> Result<Self> {
+        let backend = std::env::var("STORAGE_BACKEND").unwrap_or_default();
+        match backend.as_str() {
+            "" | "local" => Ok(Self::Local {
+                root: env_or(
+                    "STORAGE_LOCAL_ROOT",
+                    PathBuf::from(DEFAULT_STORAGE_LOCAL_ROOT),
+                )?,
+            }),
+            "s3" => Ok(Self::S3(S3Config::from_env()?)),
+            _ => anyhow::bail!("STORAGE_BACKEND must be one of local, s3: {backend}"),
+        }
+    }
+}
+
+impl S3Config {
+    pub fn from_env() -> Result<Self> {
+        let required = |key: &str| {
+            std::env::var(key)
+                .ok()
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| anyhow::anyhow!("{key} must be set"))
+        };
+        Ok(Self {
+            endpoint: required("STORAGE_S3_ENDPOINT")?,
+            region: env_or("STORAGE_S3_REGION", DEFAULT_STORAGE_S3_REGION.to_string())?,
+            bucket: required("STORAGE_S3_BUCKET")?,
+            access_key: required("STORAGE_S3_ACCESS_KEY")?,
+            secret_key: required("STORAGE_S3_SECRET_KEY")?,
+        })
+    }
+}
+
+// ユーザーのロールごとの貸出ポリシー
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CheckoutConfig {
+    pub admin: LoanPolicy,
+    pub user: LoanPolicy,
+}
+
+// 貸出ポリシー
+// 既定値は、同時に 5 冊まで、14 日間借りられ、延長は 2 回まで、
+// 返却期限を過ぎた貸出がある間は新たに借りられない
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoanPolicy {
+    // 同時に借りられる冊数の上限
+    pub max_loans: i64,
+    // 貸出日から返却期限までの日数
+    pub loan_period_days: i64,
+    // 1 件の貸出を延長できる回数の上限
+    pub max_renewals: i32,
+    // 返却期限を過ぎた貸出がある場合に、新たな貸出を禁止するか
+    pub overdue_blocks_checkout: bool,
+}
+
+impl Default for LoanPolicy {
+    fn default() -> Self {
+        Self {
+            max_loans: 5,
+            loan_period_days: 14,
+            max_renewals: 2,
+            overdue_blocks_checkout: true,
+        }
+    }
+}
+
+impl LoanPolicy {
+    // {prefix}_MAX_LOANS などの環境変数から読み込む。未設定の項目は default の値を使う
+    fn from_env(prefix: &str, default: LoanPolicy) -> Result<Self> {
+        let policy = Self {
+            max_loans: env_or(&format!("{prefix}_MAX_LOANS"), default.max_loans)?,
+            loan_period_days: env_or(
+                &format!("{prefix}_LOAN_PERIOD_DAYS"),
+                default.loan_period_days,
+            )?,
+            max_renewals: env_or(&format!("{prefix}_MAX_RENEWALS"), default.max_renewals)?,
+            overdue_blocks_checkout: env_or(
+                &format!("{prefix}_OVERDUE_BLOCKS_CHECKOUT"),
+                default.overdue_blocks_checkout,
+            )?,
+        };
+        anyhow::ensure!(
+            policy.max_loans >= 0 && policy.loan_period_days > 0 && policy.max_renewals >= 0,
+            "{prefix}_* loan policy values are out of range"
+        );
+        Ok(policy)
+    }
+}
+
+// 環境変数が未設定または空の場合は default の値を使う
+fn env_or<T>(key: &str, default: T) -> Result<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    Ok(std::env::var(key)
+        .ok()
+        .filter(|v| !v.is_empty())
+        .map(|v| v.parse::<T>())
+        .transpose()?
+        .unwrap_or(default))
+}
+
+const DEFAULT_PICKUP_WINDOW_HOURS: i64 = 72;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ReservationConfig {
+    // 返却された所蔵資料を、予約の先頭のユーザーのために取り置く時間
+    pub pickup_window_hours: i64,
+}
+
+// 延滞料金の設定。金額の単位は円
+#[derive(Debug, Clone, Copy)]
+pub struct FineConfig {
+    // 延滞 1 日あたりの金額
+    pub daily_rate: i64,
+    // 1 件の貸出あたりの上限額
+    pub cap: i64,
+    // 未払いの残高がこの金額を超えると新たに借りられない
+    pub max_outstanding_balance: i64,
+    // 所蔵資料を紛失した場合の弁償金
+    pub replacement_charge: i64,
+}
+
+impl Default for FineConfig {
+    fn default() -> Self {
+        Self {
+            daily_rate: 10,
+            cap: 500,
+            max_outstanding_balance: 1000,
+            replacement_charge: 3000,
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use axum::{http::StatusCode, response::IntoResponse};
+use axum::{http::StatusCode, response::IntoResponse, Json};
+use serde::Serialize;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -29,24 +30,93 @@ pub enum AppError {
     ForbiddenOperation,
     #[error("{0}")]
     ConversionEntityError(String),
+    #[error("カーソルの形式が正しくありません: {0}")]
+    InvalidCursor(String),
+    #[error("ISBN の形式が正しくありません: {0}")]
+    InvalidIsbn(String),
+    #[error("CSV の形式が正しくありません: {0}")]
+    InvalidCsv(String),
+    #[error("MARC レコードの形式が正しくありません: {0}")]
+    InvalidMarc(String),
+    #[error("アップロードされたファイルを読み取れませんでした: {0}")]
+    InvalidUpload(String),
+    #[error("アップロードされたファイルが大きすぎます（上限 {0} バイト）")]
+    UploadTooLarge(usize),
+    #[error("オブジェクトストレージの操作に失敗しました: {0}")]
+    ObjectStorageError(String),
+    #[error("外部サービスからの取得に失敗しました: {0}")]
+    ExternalServiceError(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    PreconditionFailed(String),
+    #[error("If-Match ヘッダーを指定してください")]
+    PreconditionRequired,
+    #[error("{0}")]
+    LoanPolicyViolation(LoanPolicyViolation),
+}
+
+// 貸出ポリシーに違反した理由
+// クライアントが理由を判別できるよう、code を含む JSON をレスポンスとして返す
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum LoanPolicyViolation {
+    #[error("同時に借りられる冊数の上限（{limit} 冊）に達しています")]
+    MaxLoansExceeded { limit: i64 },
+    #[error("返却期限を過ぎた貸出が {count} 件あるため借りられません")]
+    OverdueCheckouts { count: i64 },
+    #[error("延長回数の上限（{limit} 回）に達しています")]
+    MaxRenewalsExceeded { limit: i32 },
+    #[error("未払いの延滞料金（{balance} 円）が上限（{limit} 円）を超えているため借りられません")]
+    OutstandingFines { balance: i64, limit: i64 },
+}
+
+#[derive(Serialize)]
+struct LoanPolicyViolationResponse {
+    #[serde(flatten)]
+    violation: LoanPolicyViolation,
+    message: String,
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> axum::response::Response {
         let status_code = match self {
+            AppError::LoanPolicyViolation(violation) => {
+                let body = LoanPolicyViolationResponse {
+                    violation,
+                    message: violation.to_string(),
+                };
+                return (StatusCode::UNPROCESSABLE_ENTITY, Json(body)).into_response();
+            }
             AppError::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
             AppError::EntityNotFound(_) => StatusCode::NOT_FOUND,
-            AppError::ValidationError(_) | AppError::ConvertToUuidError(_) => {
-                StatusCode::BAD_REQUEST
-            }
+            AppError::ValidationError(_)
+            | AppError::ConvertToUuidError(_)
+            | AppError::InvalidCursor(_)
+            | AppError::InvalidIsbn(_)
+            | AppError::InvalidCsv(_)
+            | AppError::InvalidMarc(_)
+            | AppError::InvalidUpload(_) => StatusCode::BAD_REQUEST,
+            AppError::UploadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            AppError::PreconditionRequired => StatusCode::PRECONDITION_REQUIRED,
             AppError::UnauthenticatedError | AppError::ForbiddenOperation => StatusCode::FORBIDDEN,
             AppError::UnauthorizedError => StatusCode::UNAUTHORIZED,
+            e @ AppError::ExternalServiceError(_) => {
+                tracing::error!(
+                error.message = %e,
+                "External service error happened"
+                );
+                StatusCode::BAD_GATEWAY
+            }
             e @ (AppError::TransactionError(_)
             | AppError::SpecificOperationError(_)
             | AppError::NoRowsAffectedError(_)
             | AppError::KeyValueStoreError(_)
             | AppError::BcryptError(_)
-            | AppError::ConversionEntityError(_)) => {
+            | AppError::ConversionEntityError(_)
+            | AppError::ObjectStorageError(_)) => {
                 tracing::error!(
                 error.cause_chain = ?e,
                 error.message = %e,
@@ -4,10 +4,18 @@ use std::sync::Arc;
 use adapter::database::connect_database_with;
 use adapter::redis::RedisClient;
 use anyhow::Result;
-use api::route::{auth, v1};
-
-use axum::{http::Method, Router};
-use registry::AppRegistryImpl;
+use api::{
+    handler::book::import_book_rows,
+    model::import::BookImportRows,
+    route::{auth, v1},
+};
+
+use axum::{
+    http::{header, Method},
+    Router,
+};
+use kernel::model::id::UserId;
+use registry::{AppRegistry, AppRegistryImpl};
 use shared::config::AppConfig;
 use tokio::net::TcpListener;
 
@@ -27,14 +35,28 @@ use tower_http::cors::{self, CorsLayer};
 fn cors() -> CorsLayer {
     CorsLayer::new()
         .allow_headers(cors::Any)
-        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+        ])
         .allow_origin(cors::Any)
+        // ブラウザから ETag を読み取り、If-Match に指定できるようにする
+        .expose_headers([header::ETAG])
 }
 
+// サブコマンドを指定しない場合は API サーバーを起動する
 #[tokio::main]
 async fn main() -> Result<()> {
     init_logger()?;
-    bootstrap().await
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.first().map(String::as_str) {
+        None => bootstrap().await,
+        Some("import-marc") => import_marc(&args[1..]).await,
+        Some(command) => anyhow::bail!("unknown subcommand: {}", command),
+    }
 }
 
 fn init_logger() -> Result<()> {
@@ -58,12 +80,16 @@ fn init_logger() -> Result<()> {
     Ok(())
 }
 
-async fn bootstrap() -> Result<()> {
+fn build_registry() -> Result<AppRegistry> {
     let app_config = AppConfig::new()?;
     let pool = connect_database_with(&app_config.database);
     let kv = Arc::new(RedisClient::new(&app_config.redis)?);
 
-    let registry = Arc::new(AppRegistryImpl::new(pool, kv, app_config));
+    Ok(Arc::new(AppRegistryImpl::new(pool, kv, app_config)))
+}
+
+async fn bootstrap() -> Result<()> {
+    let registry = build_registry()?;
 
     let app = Router::new()
         .merge(v1::routes())
@@ -93,3 +119,43 @@ async fn bootstrap() -> Result<()> {
             )
         })
 }
+
+const IMPORT_MARC_USAGE: &str = "usage: app import-marc <FILE> --owner <USER_ID> [--dry-run]";
+
+// MARC21（バイナリ形式または MARCXML）のファイルから蔵書を一括で登録する
+// API の POST /api/v1/books/import/marc と同じく、変換できなかったレコードは登録せずに報告する
+async fn import_marc(args: &[String]) -> Result<()> {
+    let (mut file, mut owner, mut dry_run) = (None, None, false);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--owner" => owner = args.next(),
+            "--dry-run" => dry_run = true,
+            _ if file.is_none() && !arg.starts_with("--") => file = Some(arg),
+            _ => anyhow::bail!(IMPORT_MARC_USAGE),
+        }
+    }
+    let (Some(file), Some(owner)) = (file, owner) else {
+        anyhow::bail!(IMPORT_MARC_USAGE);
+    };
+    let owner = owner
+        .parse::<UserId>()
+        .with_context(|| format!("invalid user id: {}", owner))?;
+    let input = std::fs::read(file).with_context(|| format!("failed to read {}", file))?;
+    let rows = BookImportRows::from_marc(&input)?;
+
+    let registry = build_registry()?;
+    let result = import_book_rows(&registry, owner, dry_run, rows).await?;
+
+    for error in &result.errors {
+        println!("record {}: {}", error.record, error.message);
+    }
+    println!(
+        "{} records, {} valid, {} imported{}",
+        result.total_rows,
+        result.valid_rows,
+        result.imported_rows,
+        if result.dry_run { " (dry run)" } else { "" }
+    );
+    Ok(())
+}